@@ -1,4 +1,11 @@
+use crate::advantage::{AdvantageScope, AdvantageToken};
+use crate::clock::{Charges, Recharge};
+use crate::grid::GridPos;
+use crate::inventory::Inventory;
+use crate::legendary::LegendaryTraits;
+use crate::visibility::Senses;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -8,17 +15,209 @@ pub enum ActorType {
     Monster,
 }
 
+/// What doing something costs on a creature's turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionCost {
+    Action,
+    BonusAction,
+    Free,
+}
+
+impl ActionCost {
+    pub fn name(self) -> &'static str {
+        match self {
+            ActionCost::Action => "action",
+            ActionCost::BonusAction => "bonus action",
+            ActionCost::Free => "free action",
+        }
+    }
+}
+
+/// How a creature came under another creature's control
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlKind {
+    /// Conjured by a spell (Conjure Animals, Summon Beast)
+    Summon,
+    /// Find Familiar
+    Familiar,
+    /// A ranger's animal companion
+    Companion,
+    /// Charmed or dominated into obeying
+    Charmed,
+}
+
+impl ControlKind {
+    /// Summons and familiars vanish when dismissed; the others just stop obeying
+    pub fn leaves_when_dismissed(self) -> bool {
+        matches!(self, ControlKind::Summon | ControlKind::Familiar)
+    }
+
+    /// Companions act on their ranger's turn; the others roll their own initiative
+    pub fn shares_initiative(self) -> bool {
+        matches!(self, ControlKind::Companion)
+    }
+}
+
+/// Link from a controlled creature to the creature it obeys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Control {
+    pub controller_id: Uuid,
+    pub kind: ControlKind,
+    /// Acts right after its controller instead of on its own initiative
+    pub shared_initiative: bool,
+    /// Effect (usually a concentration spell) whose end dismisses the creature
+    #[serde(default)]
+    pub effect_id: Option<Uuid>,
+}
+
+impl Control {
+    pub fn new(controller_id: Uuid, kind: ControlKind) -> Self {
+        Self {
+            controller_id,
+            kind,
+            shared_initiative: kind.shares_initiative(),
+            effect_id: None,
+        }
+    }
+
+    pub fn with_effect(mut self, effect_id: Uuid) -> Self {
+        self.effect_id = Some(effect_id);
+        self
+    }
+
+    /// Override the default, e.g. for summon spells that act right after the caster
+    pub fn with_shared_initiative(mut self, shared: bool) -> Self {
+        self.shared_initiative = shared;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CreatureSize {
+    Tiny,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
+
+impl CreatureSize {
+    /// Side of the creature's space in 5 ft squares
+    pub fn squares(&self) -> i32 {
+        match self {
+            CreatureSize::Tiny | CreatureSize::Small | CreatureSize::Medium => 1,
+            CreatureSize::Large => 2,
+            CreatureSize::Huge => 3,
+            CreatureSize::Gargantuan => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbilityScores {
+    pub strength: i32,
+    pub dexterity: i32,
+    pub constitution: i32,
+    pub intelligence: i32,
+    pub wisdom: i32,
+    pub charisma: i32,
+}
+
+impl Default for AbilityScores {
+    fn default() -> Self {
+        Self {
+            strength: 10,
+            dexterity: 10,
+            constitution: 10,
+            intelligence: 10,
+            wisdom: 10,
+            charisma: 10,
+        }
+    }
+}
+
+impl AbilityScores {
+    pub fn modifier(score: i32) -> i32 {
+        (score - 10).div_euclid(2)
+    }
+}
+
+fn default_proficiency_bonus() -> i32 {
+    2
+}
+
+fn default_speed() -> i32 {
+    30
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Actor {
     pub id: Uuid,
     pub name: String,
+    /// Other names the creature answers to (e.g. "the orc chief", "Grukk")
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub actor_type: ActorType,
-    pub position: (f32, f32, f32), // x, y, z
+    pub position: (f32, f32, f32), // x, y, z (in grid squares)
     pub hp: i32,
     pub max_hp: i32,
     pub ac: i32,
     pub initiative: Option<i32>,
     pub active: bool,
+    #[serde(default)]
+    pub abilities: AbilityScores,
+    #[serde(default = "default_proficiency_bonus")]
+    pub proficiency_bonus: i32,
+    /// Feats and class features by name (e.g. "Alert", "Jack of All Trades")
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Stat block this creature was created from; identical monsters share it
+    #[serde(default)]
+    pub stat_block: Option<String>,
+    /// One-shot advantage granted by other creatures (e.g. Help)
+    #[serde(default)]
+    pub advantage_tokens: Vec<AdvantageToken>,
+    /// Legendary actions, lair actions and legendary resistances from the stat block
+    #[serde(default)]
+    pub legendary: Option<LegendaryTraits>,
+    /// Walking speed in feet
+    #[serde(default = "default_speed")]
+    pub speed: i32,
+    /// Feet of movement spent this turn
+    #[serde(default)]
+    pub movement_used: i32,
+    /// Action taken this turn (tracked in combat)
+    #[serde(default)]
+    pub action_used: bool,
+    /// Bonus action taken this turn (tracked in combat)
+    #[serde(default)]
+    pub bonus_action_used: bool,
+    #[serde(default)]
+    pub size: CreatureSize,
+    /// Darkvision, blindsight and truesight ranges
+    #[serde(default)]
+    pub senses: Senses,
+    #[serde(default)]
+    pub inventory: Inventory,
+    /// Limited-use abilities and items by name
+    #[serde(default)]
+    pub charges: BTreeMap<String, Charges>,
+    /// Spell slots by spell level; creatures without any cast without tracked slots
+    #[serde(default)]
+    pub spell_slots: BTreeMap<u8, Charges>,
+    /// Levels of exhaustion (0-6)
+    #[serde(default)]
+    pub exhaustion: u8,
+    /// Scene the creature is in; `None` while it is off stage
+    #[serde(default)]
+    pub scene_id: Option<Uuid>,
+    /// Creature this one obeys (summoner, ranger, charmer)
+    #[serde(default)]
+    pub controlled_by: Option<Control>,
 }
 
 impl Default for Actor {
@@ -32,6 +231,7 @@ impl Actor {
         Self {
             id: Uuid::new_v4(),
             name,
+            aliases: Vec::new(),
             actor_type,
             position: (0.0, 0.0, 0.0),
             hp: 100,
@@ -39,6 +239,24 @@ impl Actor {
             ac: 10,
             initiative: None,
             active: true,
+            abilities: AbilityScores::default(),
+            proficiency_bonus: default_proficiency_bonus(),
+            features: Vec::new(),
+            stat_block: None,
+            advantage_tokens: Vec::new(),
+            legendary: None,
+            speed: default_speed(),
+            movement_used: 0,
+            action_used: false,
+            bonus_action_used: false,
+            size: CreatureSize::default(),
+            senses: Senses::default(),
+            inventory: Inventory::default(),
+            charges: BTreeMap::new(),
+            spell_slots: BTreeMap::new(),
+            exhaustion: 0,
+            scene_id: None,
+            controlled_by: None,
         }
     }
 
@@ -46,6 +264,7 @@ impl Actor {
         Self {
             id: Uuid::new_v4(),
             name,
+            aliases: Vec::new(),
             actor_type,
             position: (0.0, 0.0, 0.0),
             hp,
@@ -53,6 +272,24 @@ impl Actor {
             ac,
             initiative: None,
             active: true,
+            abilities: AbilityScores::default(),
+            proficiency_bonus: default_proficiency_bonus(),
+            features: Vec::new(),
+            stat_block: None,
+            advantage_tokens: Vec::new(),
+            legendary: None,
+            speed: default_speed(),
+            movement_used: 0,
+            action_used: false,
+            bonus_action_used: false,
+            size: CreatureSize::default(),
+            senses: Senses::default(),
+            inventory: Inventory::default(),
+            charges: BTreeMap::new(),
+            spell_slots: BTreeMap::new(),
+            exhaustion: 0,
+            scene_id: None,
+            controlled_by: None,
         }
     }
 
@@ -60,6 +297,45 @@ impl Actor {
         self.position = (x, y, z);
     }
 
+    pub fn grid_position(&self) -> GridPos {
+        GridPos::from_position(self.position)
+    }
+
+    /// Squares this creature occupies (its position is the top-left square)
+    pub fn footprint(&self) -> Vec<GridPos> {
+        crate::pathfinding::footprint(self.grid_position(), self.size.squares()).collect()
+    }
+
+    /// Monsters are hostile to players and NPCs, and the other way around
+    pub fn is_hostile_to(&self, other: &Actor) -> bool {
+        (self.actor_type == ActorType::Monster) != (other.actor_type == ActorType::Monster)
+    }
+
+    pub fn set_grid_position(&mut self, pos: GridPos, elevation: i32) {
+        self.position = (pos.x as f32, pos.y as f32, elevation as f32);
+    }
+
+    pub fn remaining_movement(&self) -> i32 {
+        (self.speed - self.movement_used).max(0)
+    }
+
+    /// Whether the creature still has the action `cost` takes this turn
+    pub fn has_action(&self, cost: ActionCost) -> bool {
+        match cost {
+            ActionCost::Action => !self.action_used,
+            ActionCost::BonusAction => !self.bonus_action_used,
+            ActionCost::Free => true,
+        }
+    }
+
+    pub fn spend_action(&mut self, cost: ActionCost) {
+        match cost {
+            ActionCost::Action => self.action_used = true,
+            ActionCost::BonusAction => self.bonus_action_used = true,
+            ActionCost::Free => {}
+        }
+    }
+
     pub fn take_damage(&mut self, damage: i32) {
         self.hp = (self.hp - damage).max(0);
     }
@@ -72,9 +348,106 @@ impl Actor {
         self.hp > 0
     }
 
+    pub fn with_abilities(mut self, abilities: AbilityScores) -> Self {
+        self.abilities = abilities;
+        self
+    }
+
+    pub fn with_alias(mut self, alias: String) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+
+    pub fn with_stat_block(mut self, stat_block: String) -> Self {
+        self.stat_block = Some(stat_block);
+        self
+    }
+
+    pub fn with_senses(mut self, senses: Senses) -> Self {
+        self.senses = senses;
+        self
+    }
+
+    /// Spell slots as (spell level, count) pairs, regained on a long rest
+    pub fn with_spell_slots(mut self, slots: &[(u8, u32)]) -> Self {
+        self.spell_slots = slots
+            .iter()
+            .map(|&(level, count)| (level, Charges::new(count, Recharge::LongRest)))
+            .collect();
+        self
+    }
+
+    pub fn with_legendary(mut self, legendary: LegendaryTraits) -> Self {
+        self.legendary = Some(legendary);
+        self
+    }
+
+    pub fn is_controlled_by(&self, controller_id: Uuid) -> bool {
+        self.controlled_by
+            .as_ref()
+            .is_some_and(|c| c.controller_id == controller_id)
+    }
+
     pub fn set_initiative(&mut self, initiative: i32) {
         self.initiative = Some(initiative);
     }
+
+    pub fn grant_advantage(&mut self, token: AdvantageToken) {
+        self.advantage_tokens.push(token);
+    }
+
+    pub fn has_advantage_for(&self, scope: AdvantageScope) -> bool {
+        self.advantage_tokens.iter().any(|t| t.applies_to(scope))
+    }
+
+    /// Use up every token matching the roll being made.
+    /// Returns true if the roll gains advantage.
+    pub fn consume_advantage(&mut self, scope: AdvantageScope) -> bool {
+        let before = self.advantage_tokens.len();
+        self.advantage_tokens.retain(|t| !t.applies_to(scope));
+        self.advantage_tokens.len() != before
+    }
+
+    /// Drop tokens granted by `source_id` (e.g. at the start of the helper's turn)
+    pub fn expire_advantage_from(&mut self, source_id: Uuid) {
+        self.advantage_tokens.retain(|t| t.source_id != source_id);
+    }
+
+    /// Spend one charge of a limited-use ability or item, returning how many are left
+    pub fn use_charge(&mut self, name: &str) -> Option<u32> {
+        let charges = self
+            .charges
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, c)| c)?;
+        charges.current = charges.current.checked_sub(1)?;
+        Some(charges.current)
+    }
+
+    /// Spend a spell slot of `level`, returning how many of that level are left
+    pub fn use_spell_slot(&mut self, level: u8) -> Option<u32> {
+        let slots = self.spell_slots.get_mut(&level)?;
+        slots.current = slots.current.checked_sub(1)?;
+        Some(slots.current)
+    }
+
+    /// Restore every charge that comes back at `recharge`
+    pub fn recharge(&mut self, recharge: Recharge) {
+        for charges in self
+            .charges
+            .values_mut()
+            .chain(self.spell_slots.values_mut())
+        {
+            if charges.recharge == recharge {
+                charges.current = charges.max;
+            }
+        }
+        if recharge == Recharge::Dawn {
+            if let Some(legendary) = self.legendary.as_mut() {
+                legendary.reset_resistances();
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -111,4 +484,30 @@ mod tests {
         actor.heal(100);
         assert_eq!(actor.hp, actor.max_hp);
     }
+
+    #[test]
+    fn test_actor_advantage_token_is_one_shot() {
+        let mut actor = Actor::new("Test".to_string(), ActorType::Player);
+        let helper = Uuid::new_v4();
+        actor.grant_advantage(AdvantageToken::new(helper, AdvantageScope::AbilityCheck));
+
+        assert!(actor.has_advantage_for(AdvantageScope::AbilityCheck));
+        assert!(actor.consume_advantage(AdvantageScope::AbilityCheck));
+        assert!(!actor.consume_advantage(AdvantageScope::AbilityCheck));
+    }
+
+    #[test]
+    fn test_spell_slots_run_out_until_a_long_rest() {
+        let mut actor =
+            Actor::new("Test".to_string(), ActorType::Player).with_spell_slots(&[(1, 2), (2, 1)]);
+        assert_eq!(actor.use_spell_slot(2), Some(0));
+        assert_eq!(actor.use_spell_slot(2), None);
+        assert_eq!(actor.use_spell_slot(3), None);
+
+        actor.recharge(Recharge::Dawn);
+        assert_eq!(actor.use_spell_slot(2), None);
+        actor.recharge(Recharge::LongRest);
+        assert_eq!(actor.use_spell_slot(2), Some(0));
+        assert_eq!(actor.use_spell_slot(1), Some(1));
+    }
 }
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What a one-shot advantage token applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdvantageScope {
+    /// The holder's next ability check
+    AbilityCheck,
+    /// The holder's next attack roll against the given creature
+    AttackAgainst(Uuid),
+}
+
+/// One-shot advantage granted to a creature by another (e.g. the Help action)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdvantageToken {
+    pub id: Uuid,
+    /// Creature that granted the advantage
+    pub source_id: Uuid,
+    pub scope: AdvantageScope,
+}
+
+impl AdvantageToken {
+    pub fn new(source_id: Uuid, scope: AdvantageScope) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            source_id,
+            scope,
+        }
+    }
+
+    pub fn applies_to(&self, scope: AdvantageScope) -> bool {
+        self.scope == scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_scope_matching() {
+        let helper = Uuid::new_v4();
+        let goblin = Uuid::new_v4();
+        let orc = Uuid::new_v4();
+
+        let token = AdvantageToken::new(helper, AdvantageScope::AttackAgainst(goblin));
+        assert!(token.applies_to(AdvantageScope::AttackAgainst(goblin)));
+        assert!(!token.applies_to(AdvantageScope::AttackAgainst(orc)));
+        assert!(!token.applies_to(AdvantageScope::AbilityCheck));
+    }
+}
@@ -0,0 +1,411 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Seconds in one combat round
+pub const ROUND_SECONDS: u64 = 6;
+pub const MINUTE_SECONDS: u64 = 60;
+pub const HOUR_SECONDS: u64 = 60 * MINUTE_SECONDS;
+pub const DAY_SECONDS: u64 = 24 * HOUR_SECONDS;
+/// Longest single move of the clock; longer downtime takes several advances
+pub const MAX_ADVANCE_SECONDS: u64 = 365 * DAY_SECONDS;
+
+/// A named month and how many days it has
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Month {
+    pub name: String,
+    pub days: u32,
+}
+
+/// Months, moon cycle and daylight hours of the campaign world
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calendar {
+    pub months: Vec<Month>,
+    /// Days from one new moon to the next
+    pub moon_cycle_days: u32,
+    /// Days into the cycle on the first day of year 0
+    #[serde(default)]
+    pub moon_offset_days: u32,
+    pub dawn_hour: u32,
+    pub dusk_hour: u32,
+}
+
+impl Default for Calendar {
+    /// Calendar of Harptos: twelve months of thirty days
+    fn default() -> Self {
+        let months = [
+            "Hammer",
+            "Alturiak",
+            "Ches",
+            "Tarsakh",
+            "Mirtul",
+            "Kythorn",
+            "Flamerule",
+            "Eleasis",
+            "Eleint",
+            "Marpenoth",
+            "Uktar",
+            "Nightal",
+        ];
+        Self {
+            months: months
+                .iter()
+                .map(|name| Month {
+                    name: name.to_string(),
+                    days: 30,
+                })
+                .collect(),
+            moon_cycle_days: 30,
+            moon_offset_days: 0,
+            dawn_hour: 6,
+            dusk_hour: 18,
+        }
+    }
+}
+
+impl Calendar {
+    pub fn days_per_year(&self) -> u64 {
+        self.months
+            .iter()
+            .map(|m| m.days as u64)
+            .sum::<u64>()
+            .max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeOfDay {
+    Dawn,
+    Morning,
+    Afternoon,
+    Dusk,
+    Night,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoonPhase {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+/// The clock read as a calendar date
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldTime {
+    pub year: u64,
+    pub month: String,
+    /// Day of the month, starting at 1
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub time_of_day: TimeOfDay,
+    pub moon_phase: MoonPhase,
+}
+
+/// What an NPC does at a time of day, every day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NpcSchedule {
+    pub actor_id: Uuid,
+    pub hour: u32,
+    pub minute: u32,
+    pub activity: String,
+}
+
+/// Something that happened while time passed, in the order it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClockEvent {
+    Dawn {
+        day: u64,
+    },
+    Dusk {
+        day: u64,
+    },
+    EffectExpired {
+        effect_id: Uuid,
+        target_id: Uuid,
+    },
+    NpcActivity {
+        actor_id: Uuid,
+        activity: String,
+    },
+    /// A one-off event scheduled with [`WorldClock::schedule_at`]
+    Scheduled {
+        label: String,
+    },
+}
+
+/// When spent uses of an ability or item come back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recharge {
+    Dawn,
+    Dusk,
+    /// At the end of a long rest (e.g. spell slots)
+    LongRest,
+}
+
+/// Limited uses of an ability or item (e.g. a wand with 7 charges that regains them at dawn)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Charges {
+    pub current: u32,
+    pub max: u32,
+    pub recharge: Recharge,
+}
+
+impl Charges {
+    pub fn new(max: u32, recharge: Recharge) -> Self {
+        Self {
+            current: max,
+            max,
+            recharge,
+        }
+    }
+}
+
+/// In-game time, counted in seconds since the start of year 0
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldClock {
+    pub calendar: Calendar,
+    elapsed: u64,
+    #[serde(default)]
+    schedules: Vec<NpcSchedule>,
+    #[serde(default)]
+    scheduled: Vec<(u64, String)>,
+}
+
+impl Default for WorldClock {
+    /// Eight in the morning on the first day of the year
+    fn default() -> Self {
+        Self::new(Calendar::default(), 8 * HOUR_SECONDS)
+    }
+}
+
+impl WorldClock {
+    pub fn new(calendar: Calendar, elapsed: u64) -> Self {
+        Self {
+            calendar,
+            elapsed,
+            schedules: Vec::new(),
+            scheduled: Vec::new(),
+        }
+    }
+
+    /// Seconds since the start of year 0
+    pub fn now(&self) -> u64 {
+        self.elapsed
+    }
+
+    pub fn day(&self) -> u64 {
+        self.elapsed / DAY_SECONDS
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.elapsed % DAY_SECONDS / HOUR_SECONDS) as u32
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        let hour = self.hour();
+        let (dawn, dusk) = (self.calendar.dawn_hour, self.calendar.dusk_hour);
+        if hour == dawn {
+            TimeOfDay::Dawn
+        } else if hour == dusk {
+            TimeOfDay::Dusk
+        } else if hour < dawn || hour > dusk {
+            TimeOfDay::Night
+        } else if hour < 12 {
+            TimeOfDay::Morning
+        } else {
+            TimeOfDay::Afternoon
+        }
+    }
+
+    pub fn moon_phase(&self) -> MoonPhase {
+        let cycle = self.calendar.moon_cycle_days.max(1) as u64;
+        let age = (self.day() + self.calendar.moon_offset_days as u64) % cycle;
+        match age * 8 / cycle {
+            0 => MoonPhase::New,
+            1 => MoonPhase::WaxingCrescent,
+            2 => MoonPhase::FirstQuarter,
+            3 => MoonPhase::WaxingGibbous,
+            4 => MoonPhase::Full,
+            5 => MoonPhase::WaningGibbous,
+            6 => MoonPhase::LastQuarter,
+            _ => MoonPhase::WaningCrescent,
+        }
+    }
+
+    pub fn world_time(&self) -> WorldTime {
+        let days_per_year = self.calendar.days_per_year();
+        let mut day_of_year = (self.day() % days_per_year) as u32;
+        let mut month = String::new();
+        for m in &self.calendar.months {
+            month = m.name.clone();
+            if day_of_year < m.days {
+                break;
+            }
+            day_of_year -= m.days;
+        }
+        WorldTime {
+            year: self.day() / days_per_year,
+            month,
+            day: day_of_year + 1,
+            hour: self.hour(),
+            minute: (self.elapsed % HOUR_SECONDS / MINUTE_SECONDS) as u32,
+            time_of_day: self.time_of_day(),
+            moon_phase: self.moon_phase(),
+        }
+    }
+
+    /// Run `activity` for `actor_id` every day at `hour:minute`
+    pub fn add_npc_schedule(&mut self, schedule: NpcSchedule) {
+        self.schedules.push(schedule);
+    }
+
+    pub fn remove_npc_schedules(&mut self, actor_id: Uuid) {
+        self.schedules.retain(|s| s.actor_id != actor_id);
+    }
+
+    /// Fire a [`ClockEvent::Scheduled`] once the clock reaches `at`
+    pub fn schedule_at(&mut self, at: u64, label: String) {
+        self.scheduled.push((at, label));
+    }
+
+    /// Move the clock forward (at most [`MAX_ADVANCE_SECONDS`]), returning dawns, dusks,
+    /// NPC activities and scheduled events passed on the way (effect expiry is handled by
+    /// the session). Daily events only come from the last day: earlier ones would just
+    /// repeat them.
+    pub fn advance(&mut self, seconds: u64) -> Vec<(u64, ClockEvent)> {
+        let from = self.elapsed;
+        let to = from.saturating_add(seconds.min(MAX_ADVANCE_SECONDS));
+        let last_day = from.max(to.saturating_sub(DAY_SECONDS));
+        let passed = |at: u64| last_day < at && at <= to;
+        let mut events = Vec::new();
+
+        for day in last_day / DAY_SECONDS..=to / DAY_SECONDS {
+            let start = day * DAY_SECONDS;
+            let dawn = start + self.calendar.dawn_hour as u64 * HOUR_SECONDS;
+            let dusk = start + self.calendar.dusk_hour as u64 * HOUR_SECONDS;
+            if passed(dawn) {
+                events.push((dawn, ClockEvent::Dawn { day }));
+            }
+            if passed(dusk) {
+                events.push((dusk, ClockEvent::Dusk { day }));
+            }
+            for schedule in &self.schedules {
+                let at = start
+                    + schedule.hour as u64 * HOUR_SECONDS
+                    + schedule.minute as u64 * MINUTE_SECONDS;
+                if passed(at) {
+                    events.push((
+                        at,
+                        ClockEvent::NpcActivity {
+                            actor_id: schedule.actor_id,
+                            activity: schedule.activity.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        let (due, later): (Vec<_>, Vec<_>) = std::mem::take(&mut self.scheduled)
+            .into_iter()
+            .partition(|(at, _)| *at <= to);
+        self.scheduled = later;
+        events.extend(
+            due.into_iter()
+                .map(|(at, label)| (at, ClockEvent::Scheduled { label })),
+        );
+
+        events.sort_by_key(|(at, _)| *at);
+        self.elapsed = to;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_date_and_phases() {
+        let mut clock = WorldClock::default();
+        let time = clock.world_time();
+        assert_eq!((time.year, time.month.as_str(), time.day), (0, "Hammer", 1));
+        assert_eq!(time.time_of_day, TimeOfDay::Morning);
+        assert_eq!(time.moon_phase, MoonPhase::New);
+
+        clock.advance(45 * DAY_SECONDS + 12 * HOUR_SECONDS);
+        let time = clock.world_time();
+        assert_eq!(
+            (time.month.as_str(), time.day, time.hour),
+            ("Alturiak", 16, 20)
+        );
+        assert_eq!(time.time_of_day, TimeOfDay::Night);
+        assert_eq!(time.moon_phase, MoonPhase::Full);
+
+        clock.advance(320 * DAY_SECONDS);
+        assert_eq!(clock.world_time().year, 1);
+    }
+
+    #[test]
+    fn test_long_advance_is_capped_and_reports_one_day() {
+        let mut clock = WorldClock::default();
+        let start = clock.now();
+        let events: Vec<ClockEvent> = clock
+            .advance(u64::MAX)
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect();
+        assert_eq!(clock.now() - start, MAX_ADVANCE_SECONDS);
+        // A year from 08:00 on day 0: only the last dusk and dawn
+        assert_eq!(
+            events,
+            vec![ClockEvent::Dusk { day: 364 }, ClockEvent::Dawn { day: 365 }]
+        );
+    }
+
+    #[test]
+    fn test_advance_fires_hooks_in_order() {
+        let mut clock = WorldClock::default();
+        let smith = Uuid::new_v4();
+        clock.add_npc_schedule(NpcSchedule {
+            actor_id: smith,
+            hour: 7,
+            minute: 30,
+            activity: "opens the forge".to_string(),
+        });
+        clock.schedule_at(
+            clock.now() + 2 * HOUR_SECONDS,
+            "caravan arrives".to_string(),
+        );
+
+        // 08:00 on day 0 to 08:00 on day 1
+        let events: Vec<ClockEvent> = clock
+            .advance(DAY_SECONDS)
+            .into_iter()
+            .map(|(_, e)| e)
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ClockEvent::Scheduled {
+                    label: "caravan arrives".to_string()
+                },
+                ClockEvent::Dusk { day: 0 },
+                ClockEvent::Dawn { day: 1 },
+                ClockEvent::NpcActivity {
+                    actor_id: smith,
+                    activity: "opens the forge".to_string()
+                },
+            ]
+        );
+        assert!(clock.advance(ROUND_SECONDS).is_empty());
+    }
+}
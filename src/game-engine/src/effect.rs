@@ -1,3 +1,4 @@
+use crate::clock::ROUND_SECONDS;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -20,6 +21,12 @@ pub struct Effect {
     pub duration_rounds: Option<u32>,
     pub applied_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// World clock second the effect ends at, set when it's applied to a session
+    #[serde(default)]
+    pub ends_at: Option<u64>,
+    /// Caster concentrating on the spell behind this effect
+    #[serde(default)]
+    pub concentration_of: Option<Uuid>,
 }
 
 impl Effect {
@@ -43,9 +50,44 @@ impl Effect {
             duration_rounds,
             applied_at: now,
             expires_at,
+            ends_at: None,
+            concentration_of: None,
         }
     }
 
+    /// Effect with a given id, applied at world clock second `now`. Nothing comes from
+    /// the system clock or a random id, so a replayed session builds the same effect.
+    pub fn with_id(
+        id: Uuid,
+        name: String,
+        effect_type: EffectType,
+        target_id: Uuid,
+        duration_rounds: Option<u32>,
+        now: u64,
+    ) -> Self {
+        let applied_at = DateTime::<Utc>::from_timestamp(now as i64, 0).unwrap_or_default();
+        let rounds_seconds = |rounds: u32| rounds as u64 * ROUND_SECONDS;
+        Self {
+            id,
+            name,
+            effect_type,
+            target_id,
+            duration_rounds,
+            applied_at,
+            expires_at: duration_rounds.map(|rounds| {
+                applied_at + chrono::Duration::seconds(rounds_seconds(rounds) as i64)
+            }),
+            ends_at: duration_rounds.map(|rounds| now + rounds_seconds(rounds)),
+            concentration_of: None,
+        }
+    }
+
+    /// Mark the effect as held by `caster_id`'s concentration
+    pub fn with_concentration(mut self, caster_id: Uuid) -> Self {
+        self.concentration_of = Some(caster_id);
+        self
+    }
+
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
             Utc::now() > expires_at
@@ -54,6 +96,11 @@ impl Effect {
         }
     }
 
+    /// Whether the effect has run out by world clock second `now`
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.ends_at.is_some_and(|ends_at| now >= ends_at)
+    }
+
     pub fn apply_damage(&self) -> Option<i32> {
         if let EffectType::Damage(amount) = self.effect_type {
             Some(amount)
@@ -69,12 +116,45 @@ impl Effect {
             None
         }
     }
+
+    /// Conditions that leave a creature unable to take actions (it loses its turn)
+    pub fn is_incapacitating(&self) -> bool {
+        match &self.effect_type {
+            EffectType::Condition(condition) => matches!(
+                condition.to_lowercase().as_str(),
+                "incapacitated" | "paralyzed" | "petrified" | "stunned" | "unconscious"
+            ),
+            _ => false,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_effect_with_id_uses_the_world_clock() {
+        let (id, target_id) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let effect = |now| {
+            Effect::with_id(
+                id,
+                "Bless".to_string(),
+                EffectType::Buff("Bless".to_string(), 0),
+                target_id,
+                Some(10),
+                now,
+            )
+        };
+        let first = effect(3600);
+        assert_eq!(first.ends_at, Some(3660));
+        assert_eq!(first.applied_at.timestamp(), 3600);
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::to_value(effect(3600)).unwrap()
+        );
+    }
+
     #[test]
     fn test_effect_creation() {
         let target_id = Uuid::new_v4();
@@ -0,0 +1,836 @@
+use crate::actor::{ActionCost, Actor, Control};
+use crate::advantage::{AdvantageScope, AdvantageToken};
+use crate::clock::Charges;
+use crate::effect::Effect;
+use crate::error::{GameError, Result};
+use crate::grid::GridPos;
+use crate::session::GameSession;
+use crate::travel::{TravelPace, WatchRolls};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Events between snapshots when no interval is given
+pub const DEFAULT_SNAPSHOT_INTERVAL: usize = 50;
+
+/// A change to the game state. Applying the same events to the same state always
+/// gives the same result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    SceneCreated {
+        scene_id: Uuid,
+        name: String,
+    },
+    SceneEntered {
+        scene_id: Uuid,
+    },
+    /// Creatures (the party, or part of it) moved to another scene
+    SceneTransitioned {
+        scene_id: Uuid,
+        actor_ids: Vec<Uuid>,
+    },
+    /// Something to remember about a scene when the party comes back
+    SceneStateSet {
+        scene_id: Uuid,
+        key: String,
+        value: serde_json::Value,
+    },
+    ActorAdded {
+        scene_id: Uuid,
+        actor: Box<Actor>,
+    },
+    ActorRemoved {
+        actor_id: Uuid,
+    },
+    Damaged {
+        actor_id: Uuid,
+        amount: i32,
+        #[serde(default)]
+        damage_type: Option<String>,
+    },
+    Healed {
+        actor_id: Uuid,
+        amount: i32,
+    },
+    Moved {
+        actor_id: Uuid,
+        path: Vec<GridPos>,
+    },
+    /// The DM set a creature's hit points outright
+    HitPointsSet {
+        actor_id: Uuid,
+        hp: i32,
+    },
+    /// The DM put a token on a square without moving it there
+    Placed {
+        actor_id: Uuid,
+        position: GridPos,
+    },
+    EffectApplied {
+        effect: Effect,
+    },
+    ConditionRemoved {
+        actor_id: Uuid,
+        condition: String,
+    },
+    HelpGranted {
+        helped_id: Uuid,
+        token: AdvantageToken,
+    },
+    /// A roll used up the advantage tokens matching it
+    AdvantageUsed {
+        actor_id: Uuid,
+        scope: AdvantageScope,
+    },
+    /// A creature starts obeying another (summon, familiar, companion, charm)
+    ControlGranted {
+        actor_id: Uuid,
+        control: Control,
+    },
+    /// A controlled creature is sent away or stops obeying
+    Dismissed {
+        actor_id: Uuid,
+    },
+    /// A caster loses or drops concentration
+    ConcentrationEnded {
+        actor_id: Uuid,
+    },
+    AmmunitionSpent {
+        actor_id: Uuid,
+        ammunition: String,
+    },
+    /// A creature uses an item: a charge or one of the stack is spent
+    ItemUsed {
+        actor_id: Uuid,
+        item: String,
+        cost: ActionCost,
+        /// The item's full charges and when they come back, for items that have them
+        #[serde(default)]
+        charges: Option<Charges>,
+    },
+    /// A caster spends a spell slot of `level`
+    SpellSlotUsed {
+        actor_id: Uuid,
+        level: u8,
+    },
+    AmmunitionRecovered,
+    LegendaryActionUsed {
+        actor_id: Uuid,
+        action: String,
+    },
+    LairActionUsed {
+        actor_id: Uuid,
+        action: String,
+    },
+    /// A legendary creature turns a failed saving throw into a success
+    LegendaryResistanceUsed {
+        actor_id: Uuid,
+    },
+    CombatStarted {
+        order: Vec<(Uuid, i32)>,
+        #[serde(default)]
+        surprised: Vec<Uuid>,
+    },
+    TurnAdvanced,
+    TurnDelayed {
+        actor_id: Uuid,
+    },
+    TurnResumed {
+        actor_id: Uuid,
+    },
+    CombatEnded,
+    /// World clock moved forward outside combat (travel, rests, downtime)
+    TimeAdvanced {
+        seconds: u64,
+    },
+    TravelStarted {
+        destination: String,
+        pace: TravelPace,
+    },
+    /// One watch of overland travel with its rolled checks
+    WatchTraveled {
+        rolls: WatchRolls,
+    },
+}
+
+impl GameEvent {
+    /// Creatures the event is about
+    pub fn actors(&self) -> Vec<Uuid> {
+        match self {
+            GameEvent::ActorAdded { actor, .. } => vec![actor.id],
+            GameEvent::ActorRemoved { actor_id }
+            | GameEvent::Damaged { actor_id, .. }
+            | GameEvent::Healed { actor_id, .. }
+            | GameEvent::Moved { actor_id, .. }
+            | GameEvent::HitPointsSet { actor_id, .. }
+            | GameEvent::Placed { actor_id, .. }
+            | GameEvent::ConditionRemoved { actor_id, .. }
+            | GameEvent::AmmunitionSpent { actor_id, .. }
+            | GameEvent::SpellSlotUsed { actor_id, .. }
+            | GameEvent::ItemUsed { actor_id, .. }
+            | GameEvent::LegendaryActionUsed { actor_id, .. }
+            | GameEvent::LegendaryResistanceUsed { actor_id }
+            | GameEvent::AdvantageUsed { actor_id, .. }
+            | GameEvent::LairActionUsed { actor_id, .. }
+            | GameEvent::TurnDelayed { actor_id }
+            | GameEvent::TurnResumed { actor_id }
+            | GameEvent::Dismissed { actor_id }
+            | GameEvent::ConcentrationEnded { actor_id } => vec![*actor_id],
+            GameEvent::ControlGranted { actor_id, control } => {
+                vec![*actor_id, control.controller_id]
+            }
+            GameEvent::EffectApplied { effect } => vec![effect.target_id],
+            GameEvent::HelpGranted { helped_id, token } => vec![token.source_id, *helped_id],
+            GameEvent::CombatStarted { order, .. } => order.iter().map(|(id, _)| *id).collect(),
+            GameEvent::SceneTransitioned { actor_ids, .. } => actor_ids.clone(),
+            GameEvent::SceneCreated { .. }
+            | GameEvent::SceneEntered { .. }
+            | GameEvent::SceneStateSet { .. }
+            | GameEvent::AmmunitionRecovered
+            | GameEvent::TurnAdvanced
+            | GameEvent::TimeAdvanced { .. }
+            | GameEvent::TravelStarted { .. }
+            | GameEvent::CombatEnded => Vec::new(),
+            GameEvent::WatchTraveled { rolls } => {
+                rolls.forced_march.iter().map(|(id, _)| *id).collect()
+            }
+        }
+    }
+
+    pub fn involves(&self, actor_id: Uuid) -> bool {
+        self.actors().contains(&actor_id)
+    }
+}
+
+/// An event in the log with where it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Position in the log, starting at 0
+    pub sequence: usize,
+    /// Combat round once the event was applied (0 outside combat)
+    pub round: u32,
+    pub event: GameEvent,
+    /// Who made the change by hand (the DM's console); `None` for rules results
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// Filter for [`EventLog::query`]; unset fields match everything
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventQuery {
+    pub actor_id: Option<Uuid>,
+    pub round: Option<u32>,
+    /// Only hand-made changes (`true`) or only rules results (`false`)
+    pub authored: Option<bool>,
+}
+
+impl EventQuery {
+    pub fn matches(&self, recorded: &RecordedEvent) -> bool {
+        self.actor_id.map_or(true, |id| recorded.event.involves(id))
+            && self.round.map_or(true, |round| recorded.round == round)
+            && self
+                .authored
+                .map_or(true, |authored| recorded.author.is_some() == authored)
+    }
+}
+
+impl GameSession {
+    /// Reducer: apply one event to the state. A rejected event may leave partial
+    /// changes behind; [`EventLog`] applies events to a copy.
+    pub fn apply_event(&mut self, event: &GameEvent) -> Result<()> {
+        match event {
+            GameEvent::SceneCreated { scene_id, name } => {
+                self.create_scene_with_id(*scene_id, name.clone())
+            }
+            GameEvent::SceneEntered { scene_id } => self.set_current_scene(*scene_id),
+            GameEvent::SceneTransitioned {
+                scene_id,
+                actor_ids,
+            } => self.transition(*scene_id, actor_ids).map(|_| ()),
+            GameEvent::SceneStateSet {
+                scene_id,
+                key,
+                value,
+            } => {
+                self.scenes
+                    .get_mut(scene_id)
+                    .ok_or_else(|| GameError::State(format!("Scene not found: {}", scene_id)))?
+                    .state
+                    .insert(key.clone(), value.clone());
+                Ok(())
+            }
+            GameEvent::ActorAdded { scene_id, actor } => {
+                self.add_actor_to_scene(*scene_id, actor.as_ref().clone())
+            }
+            GameEvent::ActorRemoved { actor_id } => {
+                self.remove_from_combat(*actor_id);
+                self.remove_actor(*actor_id).map(|_| ())
+            }
+            GameEvent::Damaged {
+                actor_id, amount, ..
+            } => {
+                let amount = non_negative(*amount)?;
+                let actor = self.actor_or_err(*actor_id)?;
+                actor.take_damage(amount);
+                // Dropping to 0 hit points breaks concentration
+                if !actor.is_alive() {
+                    self.end_concentration(*actor_id);
+                }
+                Ok(())
+            }
+            GameEvent::Healed { actor_id, amount } => {
+                let amount = non_negative(*amount)?;
+                self.actor_or_err(*actor_id)?.heal(amount);
+                Ok(())
+            }
+            GameEvent::Moved { actor_id, path } => self.move_actor(*actor_id, path).map(|_| ()),
+            GameEvent::HitPointsSet { actor_id, hp } => {
+                let hp = non_negative(*hp)?;
+                let actor = self.actor_or_err(*actor_id)?;
+                actor.hp = hp.min(actor.max_hp);
+                if !actor.is_alive() {
+                    self.end_concentration(*actor_id);
+                }
+                Ok(())
+            }
+            GameEvent::Placed { actor_id, position } => self.place_actor(*actor_id, *position),
+            GameEvent::EffectApplied { effect } => {
+                self.actor_or_err(effect.target_id)?;
+                self.apply_effect(effect.clone());
+                Ok(())
+            }
+            GameEvent::ConditionRemoved {
+                actor_id,
+                condition,
+            } => {
+                if self.remove_condition(*actor_id, condition).is_empty() {
+                    return Err(GameError::State(format!(
+                        "Actor {} is not {}",
+                        actor_id, condition
+                    )));
+                }
+                Ok(())
+            }
+            GameEvent::HelpGranted { helped_id, token } => {
+                self.grant_help_token(*helped_id, token.clone())
+            }
+            GameEvent::AdvantageUsed { actor_id, scope } => {
+                if !self.consume_advantage(*actor_id, *scope) {
+                    return Err(GameError::State(format!(
+                        "No advantage to use: {} {:?}",
+                        actor_id, scope
+                    )));
+                }
+                Ok(())
+            }
+            GameEvent::ControlGranted { actor_id, control } => {
+                self.set_control(*actor_id, control.clone())
+            }
+            GameEvent::Dismissed { actor_id } => self.dismiss(*actor_id).map(|_| ()),
+            GameEvent::ConcentrationEnded { actor_id } => {
+                self.actor_or_err(*actor_id)?;
+                self.end_concentration(*actor_id);
+                Ok(())
+            }
+            GameEvent::AmmunitionSpent {
+                actor_id,
+                ammunition,
+            } => self.spend_ammunition(*actor_id, ammunition).map(|_| ()),
+            GameEvent::ItemUsed {
+                actor_id,
+                item,
+                cost,
+                charges,
+            } => self.use_item(*actor_id, item, *cost, *charges).map(|_| ()),
+            GameEvent::SpellSlotUsed { actor_id, level } => {
+                self.actor_or_err(*actor_id)?
+                    .use_spell_slot(*level)
+                    .ok_or_else(|| {
+                        GameError::State(format!("No level {} spell slots left", level))
+                    })?;
+                Ok(())
+            }
+            GameEvent::AmmunitionRecovered => {
+                self.recover_ammunition();
+                Ok(())
+            }
+            GameEvent::LegendaryActionUsed { actor_id, action } => {
+                self.use_legendary_action(*actor_id, action).map(|_| ())
+            }
+            GameEvent::LegendaryResistanceUsed { actor_id } => {
+                if !self.use_legendary_resistance(*actor_id)? {
+                    return Err(GameError::State(format!(
+                        "No legendary resistances left: {}",
+                        actor_id
+                    )));
+                }
+                Ok(())
+            }
+            GameEvent::LairActionUsed { actor_id, action } => {
+                self.use_lair_action(*actor_id, action).map(|_| ())
+            }
+            GameEvent::CombatStarted { order, surprised } => {
+                self.start_combat_with_initiative(order.clone(), surprised)
+            }
+            GameEvent::TurnAdvanced => self.next_turn().map(|_| ()),
+            GameEvent::TurnDelayed { actor_id } => {
+                self.delay_turn(*actor_id)?;
+                self.next_turn().map(|_| ())
+            }
+            GameEvent::TurnResumed { actor_id } => self.resume_delayed(*actor_id),
+            GameEvent::CombatEnded => {
+                self.current_scene_or_err()?.end_combat();
+                Ok(())
+            }
+            GameEvent::TimeAdvanced { seconds } => {
+                self.advance_time(*seconds);
+                Ok(())
+            }
+            GameEvent::TravelStarted { destination, pace } => {
+                self.start_journey(destination, *pace)
+            }
+            GameEvent::WatchTraveled { rolls } => self.travel_watch(rolls).map(|_| ()),
+        }
+    }
+
+    fn current_scene_or_err(&mut self) -> Result<&mut crate::scene::Scene> {
+        self.get_current_scene_mut()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))
+    }
+
+    fn actor_or_err(&mut self, actor_id: Uuid) -> Result<&mut Actor> {
+        self.get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))
+    }
+}
+
+fn non_negative(amount: i32) -> Result<i32> {
+    if amount < 0 {
+        return Err(GameError::State(format!("Negative amount: {}", amount)));
+    }
+    Ok(amount)
+}
+
+/// Append-only history of a session: the current state is the initial state with every
+/// event applied in order. Snapshots every `snapshot_interval` events keep undo cheap.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLog {
+    /// State before the first event
+    initial: GameSession,
+    state: GameSession,
+    events: Vec<RecordedEvent>,
+    /// Undone events, most recent last
+    undone: Vec<RecordedEvent>,
+    /// State after the first `n` events, oldest first (always includes `n = 0`)
+    snapshots: Vec<(usize, GameSession)>,
+    snapshot_interval: usize,
+    /// The state was changed outside the log since the last event
+    #[serde(default)]
+    dirty: bool,
+}
+
+impl EventLog {
+    pub fn new(state: GameSession) -> Self {
+        Self {
+            initial: state.clone(),
+            snapshots: vec![(0, state.clone())],
+            state,
+            events: Vec::new(),
+            undone: Vec::new(),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            dirty: false,
+        }
+    }
+
+    pub fn with_snapshot_interval(mut self, interval: usize) -> Self {
+        self.snapshot_interval = interval.max(1);
+        self
+    }
+
+    pub fn state(&self) -> &GameSession {
+        &self.state
+    }
+
+    pub fn initial_state(&self) -> &GameSession {
+        &self.initial
+    }
+
+    /// Change the state without recording an event (e.g. bookkeeping with no rules
+    /// meaning). Such changes are kept by undo as long as an event follows them.
+    pub fn state_mut(&mut self) -> &mut GameSession {
+        self.dirty = true;
+        &mut self.state
+    }
+
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    pub fn query(&self, query: EventQuery) -> Vec<&RecordedEvent> {
+        self.events.iter().filter(|e| query.matches(e)).collect()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Apply and record an event. A rejected event leaves the state untouched.
+    pub fn dispatch(&mut self, event: GameEvent) -> Result<&RecordedEvent> {
+        self.dispatch_as(event, None)
+    }
+
+    /// Apply and record an event made by hand by `author`, or by the rules without one
+    pub fn dispatch_as(
+        &mut self,
+        event: GameEvent,
+        author: Option<String>,
+    ) -> Result<&RecordedEvent> {
+        self.append(event, author)?;
+        self.undone.clear();
+        Ok(self.events.last().expect("event was just recorded"))
+    }
+
+    fn append(&mut self, event: GameEvent, author: Option<String>) -> Result<()> {
+        if self.dirty {
+            self.snapshot();
+        }
+        let mut next = self.state.clone();
+        next.apply_event(&event)?;
+        let recorded = RecordedEvent {
+            sequence: self.events.len(),
+            round: if next.in_combat() {
+                next.get_round()
+            } else {
+                0
+            },
+            event,
+            author,
+        };
+        self.state = next;
+        self.events.push(recorded);
+        if self.events.len() % self.snapshot_interval == 0 {
+            self.snapshot();
+        }
+        Ok(())
+    }
+
+    fn snapshot(&mut self) {
+        let applied = self.events.len();
+        self.snapshots.retain(|(n, _)| *n < applied);
+        self.snapshots.push((applied, self.state.clone()));
+        self.dirty = false;
+    }
+
+    /// Revert the last event by replaying the log up to it from the nearest snapshot.
+    /// Changes made through [`EventLog::state_mut`] since the last event are lost.
+    pub fn undo(&mut self) -> Result<GameEvent> {
+        let last = self
+            .events
+            .pop()
+            .ok_or_else(|| GameError::State("Nothing to undo".to_string()))?;
+        let applied = self.events.len();
+        self.snapshots.retain(|(n, _)| *n <= applied);
+        let (start, snapshot) = self
+            .snapshots
+            .last()
+            .cloned()
+            .ok_or_else(|| GameError::State("Event log has no snapshot".to_string()))?;
+
+        let mut state = snapshot;
+        for recorded in &self.events[start..] {
+            state.apply_event(&recorded.event)?;
+        }
+        self.state = state;
+        self.dirty = false;
+        let event = last.event.clone();
+        self.undone.push(last);
+        Ok(event)
+    }
+
+    /// Re-apply the most recently undone event
+    pub fn redo(&mut self) -> Result<GameEvent> {
+        let next = self
+            .undone
+            .pop()
+            .ok_or_else(|| GameError::State("Nothing to redo".to_string()))?;
+        let event = next.event.clone();
+        if let Err(e) = self.append(next.event.clone(), next.author.clone()) {
+            self.undone.push(next);
+            return Err(e);
+        }
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::ActorType;
+
+    fn goblins() -> (EventLog, Uuid, Uuid) {
+        let mut log = EventLog::new(GameSession::new("Test".to_string())).with_snapshot_interval(2);
+        let scene_id = Uuid::new_v4();
+        log.dispatch(GameEvent::SceneCreated {
+            scene_id,
+            name: "Cave".to_string(),
+        })
+        .unwrap();
+        let mut ids = Vec::new();
+        for name in ["Goblin 1", "Goblin 2"] {
+            let goblin = Actor::with_stats(name.to_string(), ActorType::Monster, 7, 15);
+            ids.push(goblin.id);
+            log.dispatch(GameEvent::ActorAdded {
+                scene_id,
+                actor: Box::new(goblin),
+            })
+            .unwrap();
+        }
+        (log, ids[0], ids[1])
+    }
+
+    fn hp(log: &EventLog, actor_id: Uuid) -> i32 {
+        log.state().get_actor(actor_id).unwrap().hp
+    }
+
+    #[test]
+    fn test_undo_redo_damage_to_the_wrong_goblin() {
+        let (mut log, first, second) = goblins();
+        log.dispatch(GameEvent::Damaged {
+            actor_id: first,
+            amount: 5,
+            damage_type: Some("slashing".to_string()),
+        })
+        .unwrap();
+        assert_eq!(hp(&log, first), 2);
+
+        assert!(matches!(log.undo().unwrap(), GameEvent::Damaged { .. }));
+        assert_eq!(hp(&log, first), 7);
+        log.redo().unwrap();
+        assert_eq!(hp(&log, first), 2);
+
+        log.undo().unwrap();
+        log.dispatch(GameEvent::Damaged {
+            actor_id: second,
+            amount: 5,
+            damage_type: None,
+        })
+        .unwrap();
+        assert_eq!((hp(&log, first), hp(&log, second)), (7, 2));
+        // A new event drops the redo history
+        assert!(!log.can_redo());
+
+        // Undo all the way back through the snapshots
+        while log.can_undo() {
+            log.undo().unwrap();
+        }
+        assert!(log.state().get_current_scene().is_none());
+    }
+
+    #[test]
+    fn test_rejected_events_and_queries() {
+        let (mut log, first, second) = goblins();
+        assert!(log
+            .dispatch(GameEvent::Healed {
+                actor_id: Uuid::new_v4(),
+                amount: 3,
+            })
+            .is_err());
+        assert!(log
+            .dispatch(GameEvent::Damaged {
+                actor_id: first,
+                amount: -4,
+                damage_type: None,
+            })
+            .is_err());
+        assert_eq!(log.events().len(), 3);
+
+        log.dispatch(GameEvent::CombatStarted {
+            order: vec![(first, 15), (second, 10)],
+            surprised: Vec::new(),
+        })
+        .unwrap();
+        log.dispatch(GameEvent::Damaged {
+            actor_id: second,
+            amount: 3,
+            damage_type: None,
+        })
+        .unwrap();
+        log.dispatch(GameEvent::TurnAdvanced).unwrap();
+
+        let in_round = log.query(EventQuery {
+            actor_id: Some(second),
+            round: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(in_round.len(), 2);
+        assert!(matches!(
+            in_round[1].event,
+            GameEvent::Damaged { amount: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_hand_made_events_keep_their_author() {
+        let (mut log, first, _) = goblins();
+        log.dispatch(GameEvent::Damaged {
+            actor_id: first,
+            amount: 2,
+            damage_type: None,
+        })
+        .unwrap();
+        let edit = log
+            .dispatch_as(
+                GameEvent::HitPointsSet {
+                    actor_id: first,
+                    hp: 1,
+                },
+                Some("dm".to_string()),
+            )
+            .unwrap();
+        assert_eq!(edit.author.as_deref(), Some("dm"));
+
+        log.undo().unwrap();
+        log.redo().unwrap();
+        let authored = |authored| {
+            log.query(EventQuery {
+                actor_id: Some(first),
+                authored: Some(authored),
+                ..Default::default()
+            })
+            .len()
+        };
+        assert_eq!((authored(true), authored(false)), (1, 2));
+        assert_eq!(log.events().last().unwrap().author.as_deref(), Some("dm"));
+    }
+
+    #[test]
+    fn test_dm_edits_hit_points_conditions_and_position() {
+        let (mut log, first, _) = goblins();
+        log.dispatch(GameEvent::HitPointsSet {
+            actor_id: first,
+            hp: 3,
+        })
+        .unwrap();
+        assert_eq!(hp(&log, first), 3);
+        // Capped at the creature's maximum
+        log.dispatch(GameEvent::HitPointsSet {
+            actor_id: first,
+            hp: 40,
+        })
+        .unwrap();
+        assert_eq!(hp(&log, first), 7);
+        assert!(log
+            .dispatch(GameEvent::HitPointsSet {
+                actor_id: first,
+                hp: -1,
+            })
+            .is_err());
+
+        let effect = Effect::new(
+            "Hold Person".to_string(),
+            crate::effect::EffectType::Condition("paralyzed".to_string()),
+            first,
+            Some(10),
+        );
+        log.dispatch(GameEvent::EffectApplied { effect }).unwrap();
+        log.dispatch(GameEvent::ConditionRemoved {
+            actor_id: first,
+            condition: "Paralyzed".to_string(),
+        })
+        .unwrap();
+        assert!(log.state().effects.is_empty());
+        assert!(log
+            .dispatch(GameEvent::ConditionRemoved {
+                actor_id: first,
+                condition: "paralyzed".to_string(),
+            })
+            .is_err());
+
+        let position = GridPos { x: 4, y: 2 };
+        log.dispatch(GameEvent::Placed {
+            actor_id: first,
+            position,
+        })
+        .unwrap();
+        let goblin = log.state().get_actor(first).unwrap();
+        assert_eq!(
+            (goblin.grid_position(), goblin.movement_used),
+            (position, 0)
+        );
+        log.undo().unwrap();
+        assert_ne!(
+            log.state().get_actor(first).unwrap().grid_position(),
+            position
+        );
+    }
+
+    #[test]
+    fn test_legendary_resistance_is_spent_and_refilled_at_dawn() {
+        let (mut log, first, _) = goblins();
+        let scene_id = log.state().get_current_scene().unwrap().id;
+        let dragon =
+            Actor::with_stats("Dragon".to_string(), ActorType::Monster, 200, 19).with_legendary(
+                crate::legendary::LegendaryTraits::new(3, Vec::new(), 2, Vec::new()),
+            );
+        let dragon_id = dragon.id;
+        log.dispatch(GameEvent::ActorAdded {
+            scene_id,
+            actor: Box::new(dragon),
+        })
+        .unwrap();
+        let resistances = |log: &EventLog| {
+            let actor = log.state().get_actor(dragon_id).unwrap();
+            actor.legendary.as_ref().unwrap().resistances_remaining
+        };
+
+        let used = GameEvent::LegendaryResistanceUsed {
+            actor_id: dragon_id,
+        };
+        log.dispatch(used.clone()).unwrap();
+        log.dispatch(used.clone()).unwrap();
+        assert_eq!(resistances(&log), 0);
+        assert!(log.dispatch(used.clone()).is_err());
+        assert!(log
+            .dispatch(GameEvent::LegendaryResistanceUsed { actor_id: first })
+            .is_err());
+        log.undo().unwrap();
+        assert_eq!(resistances(&log), 1);
+
+        log.dispatch(GameEvent::TimeAdvanced { seconds: 86_400 })
+            .unwrap();
+        assert_eq!(resistances(&log), 2);
+    }
+
+    #[test]
+    fn test_advantage_is_used_up_by_an_event() {
+        let (mut log, first, second) = goblins();
+        let scope = AdvantageScope::AttackAgainst(second);
+        log.dispatch(GameEvent::HelpGranted {
+            helped_id: first,
+            token: AdvantageToken::new(second, scope),
+        })
+        .unwrap();
+        let used = GameEvent::AdvantageUsed {
+            actor_id: first,
+            scope,
+        };
+        log.dispatch(used.clone()).unwrap();
+        assert!(!log
+            .state()
+            .get_actor(first)
+            .unwrap()
+            .has_advantage_for(scope));
+        assert!(log.dispatch(used).is_err());
+
+        log.undo().unwrap();
+        assert!(log
+            .state()
+            .get_actor(first)
+            .unwrap()
+            .has_advantage_for(scope));
+    }
+}
@@ -0,0 +1,285 @@
+use crate::error::{GameError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Side of a grid square in feet
+pub const SQUARE_FEET: i32 = 5;
+
+/// A square on the scene grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct GridPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPos {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Square under an actor position (measured in squares)
+    pub fn from_position(position: (f32, f32, f32)) -> Self {
+        Self::new(position.0.round() as i32, position.1.round() as i32)
+    }
+
+    /// Squares between two positions, counting a diagonal as one square
+    pub fn squares_to(self, other: GridPos) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Distance in feet using the 5/10/5 diagonal rule
+    pub fn distance_ft(self, other: GridPos) -> i32 {
+        let dx = (self.x - other.x).abs();
+        let dy = (self.y - other.y).abs();
+        let diagonals = dx.min(dy);
+        let straight = dx.max(dy) - diagonals;
+        (straight + diagonals + diagonals / 2) * SQUARE_FEET
+    }
+
+    pub fn is_adjacent(self, other: GridPos) -> bool {
+        self != other && self.squares_to(other) == 1
+    }
+
+    pub fn neighbors(self) -> [GridPos; 8] {
+        [
+            GridPos::new(self.x - 1, self.y - 1),
+            GridPos::new(self.x, self.y - 1),
+            GridPos::new(self.x + 1, self.y - 1),
+            GridPos::new(self.x - 1, self.y),
+            GridPos::new(self.x + 1, self.y),
+            GridPos::new(self.x - 1, self.y + 1),
+            GridPos::new(self.x, self.y + 1),
+            GridPos::new(self.x + 1, self.y + 1),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Terrain {
+    #[default]
+    Open,
+    /// Every foot of movement costs an extra foot
+    Difficult,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoorState {
+    Open,
+    Closed,
+    Locked,
+}
+
+/// Something harmful in a square (fire, acid, spikes, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hazard {
+    pub name: String,
+    /// Damage dealt on entering, as a dice expression (e.g. "2d6")
+    #[serde(default)]
+    pub damage: Option<String>,
+    #[serde(default)]
+    pub damage_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridCell {
+    #[serde(default)]
+    pub terrain: Terrain,
+    #[serde(default)]
+    pub door: Option<DoorState>,
+    #[serde(default)]
+    pub hazard: Option<Hazard>,
+    /// Floor height in feet
+    #[serde(default)]
+    pub elevation: i32,
+}
+
+impl GridCell {
+    pub fn is_passable(&self) -> bool {
+        self.terrain != Terrain::Wall
+            && !matches!(self.door, Some(DoorState::Closed | DoorState::Locked))
+    }
+
+    /// Walls and closed doors block line of sight
+    pub fn blocks_sight(&self) -> bool {
+        !self.is_passable()
+    }
+}
+
+/// Result of a validated move
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Movement {
+    /// Squares entered, in order (excludes the starting square)
+    pub path: Vec<GridPos>,
+    pub cost_ft: i32,
+    pub remaining_ft: i32,
+    /// Hazards in the squares entered
+    pub hazards: Vec<Hazard>,
+}
+
+impl Movement {
+    pub fn destination(&self) -> Option<GridPos> {
+        self.path.last().copied()
+    }
+}
+
+/// Tactical grid of 5 ft squares for a scene
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grid {
+    width: i32,
+    height: i32,
+    cells: Vec<GridCell>,
+}
+
+impl Grid {
+    pub fn new(width: i32, height: i32) -> Self {
+        let width = width.max(1);
+        let height = height.max(1);
+        Self {
+            width,
+            height,
+            cells: vec![GridCell::default(); (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: GridPos) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.width && pos.y < self.height
+    }
+
+    fn index(&self, pos: GridPos) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.y * self.width + pos.x) as usize)
+    }
+
+    pub fn cell(&self, pos: GridPos) -> Option<&GridCell> {
+        self.index(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn cell_mut(&mut self, pos: GridPos) -> Option<&mut GridCell> {
+        self.index(pos).map(move |i| &mut self.cells[i])
+    }
+
+    pub fn set_terrain(&mut self, pos: GridPos, terrain: Terrain) -> Result<()> {
+        self.existing_cell_mut(pos)?.terrain = terrain;
+        Ok(())
+    }
+
+    pub fn set_door(&mut self, pos: GridPos, door: Option<DoorState>) -> Result<()> {
+        self.existing_cell_mut(pos)?.door = door;
+        Ok(())
+    }
+
+    pub fn set_hazard(&mut self, pos: GridPos, hazard: Option<Hazard>) -> Result<()> {
+        self.existing_cell_mut(pos)?.hazard = hazard;
+        Ok(())
+    }
+
+    pub fn set_elevation(&mut self, pos: GridPos, elevation: i32) -> Result<()> {
+        self.existing_cell_mut(pos)?.elevation = elevation;
+        Ok(())
+    }
+
+    fn existing_cell_mut(&mut self, pos: GridPos) -> Result<&mut GridCell> {
+        self.cell_mut(pos)
+            .ok_or_else(|| GameError::State(format!("Square out of bounds: {:?}", pos)))
+    }
+
+    pub fn is_passable(&self, pos: GridPos) -> bool {
+        self.cell(pos).is_some_and(|c| c.is_passable())
+    }
+
+    /// Cost in feet of stepping to an adjacent square, or `None` if the step is blocked.
+    ///
+    /// `diagonals_taken` is the number of diagonal steps already made this move: under
+    /// the 5/10/5 rule every second diagonal costs 10 ft. Difficult terrain doubles the
+    /// step and climbing costs an extra foot per foot of height gained.
+    pub fn step_cost(&self, from: GridPos, to: GridPos, diagonals_taken: u32) -> Option<i32> {
+        if !from.is_adjacent(to) {
+            return None;
+        }
+        let from_cell = self.cell(from)?;
+        let to_cell = self.cell(to)?;
+        if !to_cell.is_passable() {
+            return None;
+        }
+
+        let diagonal = from.x != to.x && from.y != to.y;
+        if diagonal {
+            // No squeezing between two blocked squares
+            let side_a = GridPos::new(to.x, from.y);
+            let side_b = GridPos::new(from.x, to.y);
+            if !self.is_passable(side_a) && !self.is_passable(side_b) {
+                return None;
+            }
+        }
+
+        let mut cost = base_step_ft(from, to, diagonals_taken);
+        if to_cell.terrain == Terrain::Difficult {
+            cost *= 2;
+        }
+        cost += (to_cell.elevation - from_cell.elevation).max(0);
+        Some(cost)
+    }
+}
+
+/// Feet for a step before terrain: every second diagonal of a move costs 10 ft (5/10/5)
+pub(crate) fn base_step_ft(from: GridPos, to: GridPos, diagonals_taken: u32) -> i32 {
+    let diagonal = from.x != to.x && from.y != to.y;
+    if diagonal && diagonals_taken % 2 == 1 {
+        2 * SQUARE_FEET
+    } else {
+        SQUARE_FEET
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_uses_five_ten_five_diagonals() {
+        let origin = GridPos::new(0, 0);
+        assert_eq!(origin.distance_ft(GridPos::new(3, 0)), 15);
+        assert_eq!(origin.distance_ft(GridPos::new(1, 1)), 5);
+        assert_eq!(origin.distance_ft(GridPos::new(2, 2)), 15);
+        assert_eq!(origin.distance_ft(GridPos::new(3, 3)), 20);
+        assert_eq!(origin.distance_ft(GridPos::new(4, 1)), 20);
+    }
+
+    #[test]
+    fn test_walls_doors_hazards_and_elevation() {
+        let mut grid = Grid::new(5, 5);
+        grid.set_terrain(GridPos::new(1, 0), Terrain::Wall).unwrap();
+        grid.set_door(GridPos::new(0, 1), Some(DoorState::Closed))
+            .unwrap();
+        let start = GridPos::new(0, 0);
+
+        assert!(grid.step_cost(start, GridPos::new(1, 0), 0).is_none());
+        // Both sides blocked: no cutting the corner
+        assert!(grid.step_cost(start, GridPos::new(1, 1), 0).is_none());
+
+        grid.set_door(GridPos::new(0, 1), Some(DoorState::Open))
+            .unwrap();
+        grid.set_elevation(GridPos::new(1, 1), 10).unwrap();
+        grid.set_hazard(
+            GridPos::new(1, 1),
+            Some(Hazard {
+                name: "Brazier".to_string(),
+                damage: Some("1d10".to_string()),
+                damage_type: Some("fire".to_string()),
+            }),
+        )
+        .unwrap();
+
+        // Climbing 10 ft on top of the step
+        assert_eq!(grid.step_cost(start, GridPos::new(1, 1), 0), Some(15));
+        assert!(grid.cell(GridPos::new(1, 1)).unwrap().hazard.is_some());
+    }
+}
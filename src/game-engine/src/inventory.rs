@@ -0,0 +1,110 @@
+use crate::error::{GameError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Items a creature carries, by name and quantity
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    #[serde(default)]
+    items: BTreeMap<String, u32>,
+    /// Ammunition fired since the last recovery
+    #[serde(default)]
+    spent: BTreeMap<String, u32>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stored name for `name`, matched case-insensitively
+    fn key(&self, name: &str) -> Option<String> {
+        self.items
+            .keys()
+            .find(|k| k.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    pub fn add(&mut self, name: &str, quantity: u32) {
+        let key = self.key(name).unwrap_or_else(|| name.to_string());
+        *self.items.entry(key).or_insert(0) += quantity;
+    }
+
+    pub fn count(&self, name: &str) -> u32 {
+        self.key(name).map(|k| self.items[&k]).unwrap_or(0)
+    }
+
+    /// Take `quantity` of an item out, returning how many are left
+    pub fn remove(&mut self, name: &str, quantity: u32) -> Result<u32> {
+        let available = self.count(name);
+        if available < quantity {
+            return Err(GameError::State(format!(
+                "Not enough {}: have {}, need {}",
+                name, available, quantity
+            )));
+        }
+        let key = self.key(name).unwrap_or_else(|| name.to_string());
+        let left = available - quantity;
+        if left == 0 {
+            self.items.remove(&key);
+        } else {
+            self.items.insert(key, left);
+        }
+        Ok(left)
+    }
+
+    /// Fire one piece of ammunition, remembering it for recovery after the fight
+    pub fn spend_ammunition(&mut self, name: &str) -> Result<u32> {
+        let key = self.key(name).unwrap_or_else(|| name.to_string());
+        let left = self.remove(&key, 1)?;
+        *self.spent.entry(key).or_insert(0) += 1;
+        Ok(left)
+    }
+
+    /// Recover half the ammunition fired (rounded down) and forget the rest
+    pub fn recover_ammunition(&mut self) -> Vec<(String, u32)> {
+        let spent = std::mem::take(&mut self.spent);
+        spent
+            .into_iter()
+            .filter_map(|(name, count)| {
+                let recovered = count / 2;
+                if recovered == 0 {
+                    return None;
+                }
+                self.add(&name, recovered);
+                Some((name, recovered))
+            })
+            .collect()
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.items.iter().map(|(k, v)| (k.as_str(), *v))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spend_and_recover_ammunition() {
+        let mut inventory = Inventory::new();
+        inventory.add("Arrow", 5);
+        for _ in 0..5 {
+            inventory.spend_ammunition("arrow").unwrap();
+        }
+        assert_eq!(inventory.count("Arrow"), 0);
+        assert!(inventory.spend_ammunition("Arrow").is_err());
+
+        assert_eq!(
+            inventory.recover_ammunition(),
+            vec![("Arrow".to_string(), 2)]
+        );
+        assert_eq!(inventory.count("Arrow"), 2);
+        assert!(inventory.recover_ammunition().is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::{GameError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Initiative count on which lair actions happen (losing ties)
+pub const LAIR_INITIATIVE: i32 = 20;
+
+fn default_cost() -> u32 {
+    1
+}
+
+fn default_actions_per_round() -> u32 {
+    3
+}
+
+/// An option a legendary creature can take at the end of another creature's turn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegendaryAction {
+    pub name: String,
+    /// Legendary actions spent (e.g. "Wing Attack (Costs 2 Actions)")
+    #[serde(default = "default_cost")]
+    pub cost: u32,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Legendary and lair abilities from a monster's stat block, with their remaining uses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "SavedTraits")]
+pub struct LegendaryTraits {
+    pub actions_per_round: u32,
+    pub actions: Vec<LegendaryAction>,
+    /// Legendary Resistance (X/Day)
+    pub resistances_per_day: u32,
+    pub lair_actions: Vec<String>,
+    pub actions_remaining: u32,
+    pub resistances_remaining: u32,
+}
+
+/// Legendary traits as stored; a stat block without remaining uses starts with all of them
+#[derive(Deserialize)]
+struct SavedTraits {
+    #[serde(default = "default_actions_per_round")]
+    actions_per_round: u32,
+    #[serde(default)]
+    actions: Vec<LegendaryAction>,
+    #[serde(default)]
+    resistances_per_day: u32,
+    #[serde(default)]
+    lair_actions: Vec<String>,
+    #[serde(default)]
+    actions_remaining: Option<u32>,
+    #[serde(default)]
+    resistances_remaining: Option<u32>,
+}
+
+impl From<SavedTraits> for LegendaryTraits {
+    fn from(saved: SavedTraits) -> Self {
+        Self {
+            actions_remaining: saved.actions_remaining.unwrap_or(saved.actions_per_round),
+            resistances_remaining: saved
+                .resistances_remaining
+                .unwrap_or(saved.resistances_per_day),
+            actions_per_round: saved.actions_per_round,
+            actions: saved.actions,
+            resistances_per_day: saved.resistances_per_day,
+            lair_actions: saved.lair_actions,
+        }
+    }
+}
+
+impl LegendaryTraits {
+    pub fn new(
+        actions_per_round: u32,
+        actions: Vec<LegendaryAction>,
+        resistances_per_day: u32,
+        lair_actions: Vec<String>,
+    ) -> Self {
+        Self {
+            actions_per_round,
+            actions,
+            resistances_per_day,
+            lair_actions,
+            actions_remaining: actions_per_round,
+            resistances_remaining: resistances_per_day,
+        }
+    }
+
+    pub fn has_legendary_actions(&self) -> bool {
+        self.actions_per_round > 0 && !self.actions.is_empty()
+    }
+
+    pub fn has_lair_actions(&self) -> bool {
+        !self.lair_actions.is_empty()
+    }
+
+    /// Spent legendary actions come back at the start of the creature's turn
+    pub fn reset_actions(&mut self) {
+        self.actions_remaining = self.actions_per_round;
+    }
+
+    /// Legendary resistances come back each day, at dawn
+    pub fn reset_resistances(&mut self) {
+        self.resistances_remaining = self.resistances_per_day;
+    }
+
+    /// Options that can still be paid for this round
+    pub fn affordable_actions(&self) -> Vec<&LegendaryAction> {
+        self.actions
+            .iter()
+            .filter(|a| a.cost <= self.actions_remaining)
+            .collect()
+    }
+
+    /// Spend a legendary action by name (case-insensitive)
+    pub fn spend_action(&mut self, name: &str) -> Result<LegendaryAction> {
+        let action = self
+            .actions
+            .iter()
+            .find(|a| a.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| GameError::State(format!("Unknown legendary action: {}", name)))?;
+        if action.cost > self.actions_remaining {
+            return Err(GameError::State(format!(
+                "Not enough legendary actions for {} ({} left, costs {})",
+                action.name, self.actions_remaining, action.cost
+            )));
+        }
+        self.actions_remaining -= action.cost;
+        Ok(action)
+    }
+
+    /// Turn a failed saving throw into a success. Returns false when no charges are left.
+    pub fn use_resistance(&mut self) -> bool {
+        if self.resistances_remaining == 0 {
+            return false;
+        }
+        self.resistances_remaining -= 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dragon() -> LegendaryTraits {
+        LegendaryTraits::new(
+            3,
+            vec![
+                LegendaryAction {
+                    name: "Detect".to_string(),
+                    cost: 1,
+                    description: String::new(),
+                },
+                LegendaryAction {
+                    name: "Wing Attack".to_string(),
+                    cost: 2,
+                    description: String::new(),
+                },
+            ],
+            3,
+            vec!["Tremor".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_legendary_action_costs() {
+        let mut traits = dragon();
+        traits.spend_action("wing attack").unwrap();
+        assert_eq!(traits.actions_remaining, 1);
+        assert_eq!(traits.affordable_actions().len(), 1);
+        assert!(traits.spend_action("Wing Attack").is_err());
+        assert!(traits.spend_action("Fly").is_err());
+
+        traits.reset_actions();
+        assert_eq!(traits.actions_remaining, 3);
+    }
+
+    #[test]
+    fn test_legendary_resistance_charges() {
+        let mut traits = dragon();
+        assert!(traits.use_resistance());
+        assert!(traits.use_resistance());
+        assert!(traits.use_resistance());
+        assert!(!traits.use_resistance());
+    }
+
+    #[test]
+    fn test_stat_block_without_remaining_uses_starts_full() {
+        let traits: LegendaryTraits =
+            serde_json::from_str(r#"{"resistances_per_day": 3, "lair_actions": ["Tremor"]}"#)
+                .unwrap();
+        assert_eq!(
+            (traits.actions_remaining, traits.resistances_remaining),
+            (3, 3)
+        );
+
+        let mut spent = dragon();
+        spent.use_resistance();
+        let saved = serde_json::to_string(&spent).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LegendaryTraits>(&saved).unwrap(),
+            spent
+        );
+    }
+}
@@ -2,18 +2,42 @@
 // This module provides the core game state management
 
 pub mod actor;
+pub mod advantage;
+pub mod clock;
 pub mod effect;
 pub mod error;
+pub mod event;
+pub mod grid;
+pub mod inventory;
+pub mod legendary;
+pub mod pathfinding;
 pub mod scene;
 pub mod session;
+pub mod travel;
 pub mod turn;
+pub mod visibility;
 
-pub use actor::{Actor, ActorType};
+pub use actor::{AbilityScores, ActionCost, Actor, ActorType, Control, ControlKind, CreatureSize};
+pub use advantage::{AdvantageScope, AdvantageToken};
+pub use clock::{
+    Calendar, Charges, ClockEvent, Month, MoonPhase, NpcSchedule, Recharge, TimeOfDay, WorldClock,
+    WorldTime,
+};
 pub use effect::{Effect, EffectType};
 pub use error::{GameError, Result};
+pub use event::{EventLog, EventQuery, GameEvent, RecordedEvent};
+pub use grid::{DoorState, Grid, GridCell, GridPos, Hazard, Movement, Terrain, SQUARE_FEET};
+pub use inventory::Inventory;
+pub use legendary::{LegendaryAction, LegendaryTraits, LAIR_INITIATIVE};
+pub use pathfinding::{find_path, PathGoal, PathOptions, PathResult};
 pub use scene::Scene;
-pub use session::GameSession;
-pub use turn::TurnOrder;
+pub use session::{Dismissal, GameSession, SceneTransition};
+pub use travel::{
+    Journey, Leg, Location, Route, TravelMap, TravelPace, TravelState, TravelTerrain, WatchReport,
+    WatchRolls,
+};
+pub use turn::{SkipReason, TurnEvent, TurnOrder};
+pub use visibility::{AttackVisibility, Cover, LightLevel, LightSource, Senses, Sight};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,504 @@
+use crate::error::{GameError, Result};
+use crate::grid::{base_step_ft, Grid, GridPos, Movement, Terrain, SQUARE_FEET};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Where a path should end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathGoal {
+    /// The mover's (top-left) square ends exactly here
+    Square(GridPos),
+    /// Stop as soon as a creature of `target_size` squares at `target` is within `reach` squares
+    Reach {
+        target: GridPos,
+        target_size: i32,
+        reach: i32,
+    },
+}
+
+impl PathGoal {
+    fn is_reached(&self, pos: GridPos, size: i32) -> bool {
+        match *self {
+            PathGoal::Square(square) => pos == square,
+            PathGoal::Reach {
+                target,
+                target_size,
+                reach,
+            } => footprint_distance(pos, size, target, target_size) <= reach,
+        }
+    }
+
+    /// Lower bound on the feet left to walk
+    fn heuristic(&self, pos: GridPos, size: i32) -> i32 {
+        let squares = match *self {
+            PathGoal::Square(square) => pos.squares_to(square),
+            PathGoal::Reach {
+                target,
+                target_size,
+                reach,
+            } => (footprint_distance(pos, size, target, target_size) - reach).max(0),
+        };
+        squares * SQUARE_FEET
+    }
+}
+
+/// Squares between two square footprints (0 when they touch or overlap)
+pub fn footprint_distance(a: GridPos, a_size: i32, b: GridPos, b_size: i32) -> i32 {
+    let gap = |a0: i32, a_len: i32, b0: i32, b_len: i32| {
+        (b0 - (a0 + a_len - 1)).max(a0 - (b0 + b_len - 1)).max(0)
+    };
+    let dx = gap(a.x, a_size, b.x, b_size);
+    let dy = gap(a.y, a_size, b.y, b_size);
+    // Adjacent footprints are 1 square apart, like adjacent squares
+    if dx == 0 && dy == 0 {
+        0
+    } else {
+        dx.max(dy)
+    }
+}
+
+/// Squares covered by a creature of `size` squares whose top-left square is `pos`
+pub fn footprint(pos: GridPos, size: i32) -> impl Iterator<Item = GridPos> {
+    let size = size.max(1);
+    (0..size).flat_map(move |dy| (0..size).map(move |dx| GridPos::new(pos.x + dx, pos.y + dy)))
+}
+
+/// What a moving creature has to route around
+#[derive(Debug, Clone, Default)]
+pub struct PathOptions {
+    /// Side of the mover's footprint in squares (1 for Medium and smaller)
+    pub size: i32,
+    /// Squares of hostile creatures: can't be entered
+    pub hostile: HashSet<GridPos>,
+    /// Squares of allied creatures: can be passed through but not ended in
+    pub allied: HashSet<GridPos>,
+    /// Hostile creatures that can reach each square
+    pub threatened: HashMap<GridPos, HashSet<Uuid>>,
+    /// Extra search cost, in feet, for each opportunity attack a step provokes
+    pub opportunity_cost: i32,
+    /// Give up on paths costing more movement than this
+    pub max_cost_ft: Option<i32>,
+}
+
+impl PathOptions {
+    fn threats(&self, pos: GridPos) -> HashSet<Uuid> {
+        footprint(pos, self.size)
+            .filter_map(|square| self.threatened.get(&square))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn can_enter(&self, grid: &Grid, pos: GridPos) -> bool {
+        footprint(pos, self.size)
+            .all(|square| grid.is_passable(square) && !self.hostile.contains(&square))
+    }
+
+    fn can_stop(&self, pos: GridPos) -> bool {
+        footprint(pos, self.size).all(|square| !self.allied.contains(&square))
+    }
+}
+
+/// A path found on the grid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathResult {
+    /// Squares entered, in order (excludes the starting square)
+    pub path: Vec<GridPos>,
+    /// Movement cost in feet
+    pub cost_ft: i32,
+    /// Opportunity attacks the mover would provoke along the path
+    pub opportunity_attacks: u32,
+    /// False when the path was cut short by the movement available
+    pub reaches_goal: bool,
+}
+
+/// Movement cost of a footprint stepping to an adjacent square, or `None` if blocked
+fn step_cost(
+    grid: &Grid,
+    options: &PathOptions,
+    from: GridPos,
+    to: GridPos,
+    diagonals_taken: u32,
+) -> Option<i32> {
+    if !options.can_enter(grid, to) {
+        return None;
+    }
+    if options.size <= 1 {
+        return grid.step_cost(from, to, diagonals_taken);
+    }
+    if !from.is_adjacent(to) {
+        return None;
+    }
+    let mut cost = base_step_ft(from, to, diagonals_taken);
+    let difficult = footprint(to, options.size).any(|square| {
+        grid.cell(square)
+            .is_some_and(|c| c.terrain == Terrain::Difficult)
+    });
+    if difficult {
+        cost *= 2;
+    }
+    let from_elevation = grid.cell(from).map_or(0, |c| c.elevation);
+    let to_elevation = grid.cell(to).map_or(0, |c| c.elevation);
+    Some(cost + (to_elevation - from_elevation).max(0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Node {
+    pos: GridPos,
+    /// Parity of diagonals taken so far (5/10/5 rule)
+    odd_diagonals: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Visit {
+    parent: Option<Node>,
+    /// Movement cost in feet
+    cost_ft: i32,
+    /// Movement cost plus opportunity attack penalties
+    score: i32,
+    opportunity_attacks: u32,
+}
+
+/// A* search from `start` to `goal` over walls, difficult terrain, creatures and
+/// opportunity attack risk. Returns `None` if the goal can't be reached.
+pub fn find_path(
+    grid: &Grid,
+    start: GridPos,
+    goal: PathGoal,
+    options: &PathOptions,
+) -> Option<PathResult> {
+    let size = options.size.max(1);
+    let start_node = Node {
+        pos: start,
+        odd_diagonals: false,
+    };
+    let mut visits: HashMap<Node, Visit> = HashMap::new();
+    visits.insert(
+        start_node,
+        Visit {
+            parent: None,
+            cost_ft: 0,
+            score: 0,
+            opportunity_attacks: 0,
+        },
+    );
+
+    // Ties on estimated total prefer the node closest to the goal
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((
+        goal.heuristic(start, size),
+        goal.heuristic(start, size),
+        start_node,
+    )));
+    let mut closed: HashSet<Node> = HashSet::new();
+
+    while let Some(Reverse((_, _, node))) = open.pop() {
+        if !closed.insert(node) {
+            continue;
+        }
+        let visit = visits[&node];
+        if goal.is_reached(node.pos, size) && (node.pos == start || options.can_stop(node.pos)) {
+            return Some(build_result(&visits, node, true));
+        }
+
+        let threats_here = options.threats(node.pos);
+        for next in node.pos.neighbors() {
+            let Some(cost) = step_cost(grid, options, node.pos, next, node.odd_diagonals as u32)
+            else {
+                continue;
+            };
+            let cost_ft = visit.cost_ft + cost;
+            if options.max_cost_ft.is_some_and(|max| cost_ft > max) {
+                continue;
+            }
+            let diagonal = next.x != node.pos.x && next.y != node.pos.y;
+            let next_node = Node {
+                pos: next,
+                odd_diagonals: node.odd_diagonals ^ diagonal,
+            };
+            if closed.contains(&next_node) {
+                continue;
+            }
+
+            // Leaving a hostile creature's reach provokes an opportunity attack
+            let threats_next = options.threats(next);
+            let provoked = threats_here.difference(&threats_next).count() as u32;
+            let score = visit.score + cost + provoked as i32 * options.opportunity_cost;
+
+            if visits.get(&next_node).is_some_and(|v| v.score <= score) {
+                continue;
+            }
+            visits.insert(
+                next_node,
+                Visit {
+                    parent: Some(node),
+                    cost_ft,
+                    score,
+                    opportunity_attacks: visit.opportunity_attacks + provoked,
+                },
+            );
+            let remaining = goal.heuristic(next, size);
+            open.push(Reverse((score + remaining, remaining, next_node)));
+        }
+    }
+
+    None
+}
+
+fn build_result(visits: &HashMap<Node, Visit>, end: Node, reaches_goal: bool) -> PathResult {
+    let visit = visits[&end];
+    let mut path = Vec::new();
+    let mut node = end;
+    while let Some(parent) = visits[&node].parent {
+        path.push(node.pos);
+        node = parent;
+    }
+    path.reverse();
+    PathResult {
+        path,
+        cost_ft: visit.cost_ft,
+        opportunity_attacks: visit.opportunity_attacks,
+        reaches_goal,
+    }
+}
+
+/// Cut a path down to the movement available, ending on the last square the mover
+/// may stop in
+pub fn truncate_path(
+    grid: &Grid,
+    start: GridPos,
+    result: &PathResult,
+    available_ft: i32,
+    options: &PathOptions,
+) -> PathResult {
+    let mut position = start;
+    let mut diagonals = 0;
+    let mut cost_ft = 0;
+    let mut opportunity_attacks = 0;
+    // (squares walked, cost, opportunity attacks) at the last square the mover can stop in
+    let mut last_stop = (0, 0, 0);
+
+    for (index, &step) in result.path.iter().enumerate() {
+        let Some(cost) = step_cost(grid, options, position, step, diagonals) else {
+            break;
+        };
+        if cost_ft + cost > available_ft {
+            break;
+        }
+        if position.x != step.x && position.y != step.y {
+            diagonals += 1;
+        }
+        let threats_here = options.threats(position);
+        opportunity_attacks += threats_here.difference(&options.threats(step)).count() as u32;
+        cost_ft += cost;
+        position = step;
+        if options.can_stop(step) {
+            last_stop = (index + 1, cost_ft, opportunity_attacks);
+        }
+    }
+
+    let (walked, cost_ft, opportunity_attacks) = last_stop;
+    PathResult {
+        path: result.path[..walked].to_vec(),
+        cost_ft,
+        opportunity_attacks,
+        reaches_goal: walked == result.path.len() && result.reaches_goal,
+    }
+}
+
+/// Validate a move along `path` for the creature described by `options`, with
+/// `available_ft` of movement left
+pub fn trace_path(
+    grid: &Grid,
+    start: GridPos,
+    path: &[GridPos],
+    available_ft: i32,
+    options: &PathOptions,
+) -> Result<Movement> {
+    let mut position = start;
+    let mut diagonals = 0;
+    let mut cost_ft = 0;
+    let mut hazards = Vec::new();
+
+    for &step in path {
+        let cost = step_cost(grid, options, position, step, diagonals).ok_or_else(|| {
+            GameError::State(format!("Blocked move from {:?} to {:?}", position, step))
+        })?;
+        if position.x != step.x && position.y != step.y {
+            diagonals += 1;
+        }
+        cost_ft += cost;
+        if cost_ft > available_ft {
+            return Err(GameError::State(format!(
+                "Not enough movement: needs {} ft, {} ft left",
+                cost_ft, available_ft
+            )));
+        }
+        hazards.extend(
+            footprint(step, options.size)
+                .filter_map(|square| grid.cell(square).and_then(|c| c.hazard.clone())),
+        );
+        position = step;
+    }
+
+    if position != start && !options.can_stop(position) {
+        return Err(GameError::State(format!(
+            "Cannot end a move in an occupied square: {:?}",
+            position
+        )));
+    }
+
+    Ok(Movement {
+        path: path.to_vec(),
+        cost_ft,
+        remaining_ft: available_ft - cost_ft,
+        hazards,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> PathOptions {
+        PathOptions {
+            size: 1,
+            opportunity_cost: 30,
+            ..PathOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_path_goes_around_walls() {
+        let mut grid = Grid::new(7, 7);
+        for y in 0..6 {
+            grid.set_terrain(GridPos::new(3, y), Terrain::Wall).unwrap();
+        }
+
+        let result = find_path(
+            &grid,
+            GridPos::new(0, 0),
+            PathGoal::Square(GridPos::new(6, 0)),
+            &options(),
+        )
+        .unwrap();
+        assert!(result.reaches_goal);
+        assert!(result.path.contains(&GridPos::new(3, 6)));
+        assert_eq!(result.path.last(), Some(&GridPos::new(6, 0)));
+        assert!(result.cost_ft > GridPos::new(0, 0).distance_ft(GridPos::new(6, 0)));
+    }
+
+    #[test]
+    fn test_path_prefers_cheaper_terrain_and_passes_allies() {
+        let mut grid = Grid::new(5, 3);
+        for x in 1..4 {
+            grid.set_terrain(GridPos::new(x, 1), Terrain::Difficult)
+                .unwrap();
+        }
+        let mut opts = options();
+        opts.allied.insert(GridPos::new(2, 0));
+        opts.hostile.insert(GridPos::new(2, 2));
+
+        let result = find_path(
+            &grid,
+            GridPos::new(0, 1),
+            PathGoal::Square(GridPos::new(4, 1)),
+            &opts,
+        )
+        .unwrap();
+        // Through the ally's square rather than the mud or the enemy
+        assert!(result.path.contains(&GridPos::new(2, 0)));
+        assert_eq!(result.cost_ft, 25);
+
+        // Can't stop on the ally
+        assert!(find_path(
+            &grid,
+            GridPos::new(0, 0),
+            PathGoal::Square(GridPos::new(2, 0)),
+            &opts
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_reach_goal_and_large_footprint() {
+        let mut grid = Grid::new(8, 8);
+        grid.set_terrain(GridPos::new(3, 1), Terrain::Wall).unwrap();
+        let goal = PathGoal::Reach {
+            target: GridPos::new(7, 0),
+            target_size: 1,
+            reach: 1,
+        };
+
+        let mut opts = options();
+        opts.size = 2;
+        let result = find_path(&grid, GridPos::new(0, 0), goal, &opts).unwrap();
+        let end = *result.path.last().unwrap();
+        assert_eq!(footprint_distance(end, 2, GridPos::new(7, 0), 1), 1);
+        // A 2x2 creature can't squeeze past the wall at (3, 1) in rows 0-1
+        assert!(result
+            .path
+            .iter()
+            .all(|pos| footprint(*pos, 2).all(|sq| sq != GridPos::new(3, 1))));
+    }
+
+    #[test]
+    fn test_opportunity_attacks_are_avoided_and_truncated() {
+        let grid = Grid::new(6, 3);
+        let orc = Uuid::new_v4();
+        let mut opts = options();
+        opts.hostile.insert(GridPos::new(1, 1));
+        for pos in GridPos::new(1, 1).neighbors() {
+            opts.threatened.entry(pos).or_default().insert(orc);
+        }
+
+        // Starting next to the orc, any route provokes once
+        let result = find_path(
+            &grid,
+            GridPos::new(0, 1),
+            PathGoal::Square(GridPos::new(5, 1)),
+            &opts,
+        )
+        .unwrap();
+        assert_eq!(result.opportunity_attacks, 1);
+
+        let short = truncate_path(&grid, GridPos::new(0, 1), &result, 10, &opts);
+        assert!(!short.reaches_goal);
+        assert_eq!(short.cost_ft, 10);
+        assert_eq!(short.path.len(), 2);
+    }
+
+    #[test]
+    fn test_trace_path_costs_hazards_and_stops() {
+        let mut grid = Grid::new(10, 10);
+        grid.set_terrain(GridPos::new(2, 2), Terrain::Difficult)
+            .unwrap();
+        grid.set_hazard(
+            GridPos::new(3, 3),
+            Some(crate::grid::Hazard {
+                name: "Brazier".to_string(),
+                damage: Some("1d10".to_string()),
+                damage_type: Some("fire".to_string()),
+            }),
+        )
+        .unwrap();
+        let start = GridPos::new(0, 0);
+        let path = [GridPos::new(1, 1), GridPos::new(2, 2), GridPos::new(3, 3)];
+
+        // 5 + 20 (second diagonal, difficult) + 5
+        let movement = trace_path(&grid, start, &path, 30, &options()).unwrap();
+        assert_eq!(movement.cost_ft, 30);
+        assert_eq!(movement.remaining_ft, 0);
+        assert_eq!(movement.destination(), Some(GridPos::new(3, 3)));
+        assert_eq!(movement.hazards.len(), 1);
+        assert!(trace_path(&grid, start, &path, 25, &options()).is_err());
+
+        // Through an ally, but not ending on one
+        let mut opts = options();
+        opts.allied.insert(GridPos::new(1, 1));
+        assert!(trace_path(&grid, start, &path, 30, &opts).is_ok());
+        assert!(trace_path(&grid, start, &path[..1], 30, &opts).is_err());
+    }
+}
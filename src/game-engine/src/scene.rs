@@ -1,16 +1,34 @@
-use crate::actor::Actor;
-use crate::error::{GameError, Result};
+use crate::grid::{Grid, GridPos};
+use crate::visibility::{self, LightLevel, LightSource};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+/// A place the party can be. Creatures belong to the session and point at their scene,
+/// so a scene keeps its map, lights and state while nobody is in it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Scene {
     pub id: Uuid,
     pub name: String,
     pub description: String,
-    pub actors: HashMap<Uuid, Actor>,
     pub combat_active: bool,
+    /// Tactical map; scenes without one are theatre of the mind
+    #[serde(default)]
+    pub grid: Option<Grid>,
+    /// Light where no source reaches (daylight, dusk, a dark cave)
+    #[serde(default)]
+    pub ambient_light: LightLevel,
+    #[serde(default)]
+    pub lights: Vec<LightSource>,
+    /// Square arriving creatures are placed around
+    #[serde(default)]
+    pub entrance: Option<GridPos>,
+    /// Anything worth remembering when the party comes back (doors forced, loot taken)
+    #[serde(default)]
+    pub state: BTreeMap<String, serde_json::Value>,
+    /// World clock time of the last arrival
+    #[serde(default)]
+    pub visited_at: Option<u64>,
 }
 
 impl Default for Scene {
@@ -21,36 +39,34 @@ impl Default for Scene {
 
 impl Scene {
     pub fn new(name: String) -> Self {
+        Self::with_id(Uuid::new_v4(), name)
+    }
+
+    pub fn with_id(id: Uuid, name: String) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id,
             name,
             description: String::new(),
-            actors: HashMap::new(),
             combat_active: false,
+            grid: None,
+            ambient_light: LightLevel::default(),
+            lights: Vec::new(),
+            entrance: None,
+            state: BTreeMap::new(),
+            visited_at: None,
         }
     }
 
-    pub fn add_actor(&mut self, actor: Actor) {
-        self.actors.insert(actor.id, actor);
-    }
-
-    pub fn remove_actor(&mut self, actor_id: Uuid) -> Result<()> {
-        self.actors
-            .remove(&actor_id)
-            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
-        Ok(())
-    }
-
-    pub fn get_actor(&self, actor_id: Uuid) -> Option<&Actor> {
-        self.actors.get(&actor_id)
-    }
-
-    pub fn get_actor_mut(&mut self, actor_id: Uuid) -> Option<&mut Actor> {
-        self.actors.get_mut(&actor_id)
+    pub fn set_grid(&mut self, grid: Grid) {
+        self.grid = Some(grid);
     }
 
-    pub fn all_actors(&self) -> Vec<&Actor> {
-        self.actors.values().collect()
+    /// Light level in a square; without a grid the whole scene has the ambient light
+    pub fn light_at(&self, pos: GridPos) -> LightLevel {
+        match &self.grid {
+            Some(grid) => visibility::light_at(grid, pos, self.ambient_light, &self.lights),
+            None => self.ambient_light,
+        }
     }
 
     pub fn start_combat(&mut self) {
@@ -65,7 +81,6 @@ impl Scene {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::actor::ActorType;
 
     #[test]
     fn test_scene_creation() {
@@ -74,15 +89,6 @@ mod tests {
         assert!(!scene.combat_active);
     }
 
-    #[test]
-    fn test_scene_add_actor() {
-        let mut scene = Scene::new("Test".to_string());
-        let actor = Actor::new("Player".to_string(), ActorType::Player);
-        scene.add_actor(actor);
-
-        assert_eq!(scene.all_actors().len(), 1);
-    }
-
     #[test]
     fn test_scene_combat() {
         let mut scene = Scene::new("Test".to_string());
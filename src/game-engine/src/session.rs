@@ -1,21 +1,68 @@
-use crate::actor::Actor;
-use crate::effect::Effect;
+use crate::actor::{ActionCost, Actor, ActorType, Control};
+use crate::advantage::{AdvantageScope, AdvantageToken};
+use crate::clock::{Charges, ClockEvent, Recharge, WorldClock, HOUR_SECONDS, ROUND_SECONDS};
+use crate::effect::{Effect, EffectType};
 use crate::error::{GameError, Result};
+use crate::grid::{Grid, GridPos, Movement, SQUARE_FEET};
+use crate::legendary::{LegendaryAction, LegendaryTraits};
+use crate::pathfinding::{self, footprint, PathGoal, PathOptions, PathResult};
 use crate::scene::Scene;
-use crate::turn::TurnOrder;
+use crate::travel::{TravelPace, TravelState, WatchReport, WatchRolls, WATCH_HOURS};
+use crate::turn::{SkipReason, TurnEvent, TurnOrder};
+use crate::visibility::{self, AttackVisibility};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use uuid::Uuid;
 
+/// Extra feet a path is allowed to cost to avoid one opportunity attack
+const OPPORTUNITY_ATTACK_COST_FT: i32 = 15;
+
+/// A controlled creature that was sent away or stopped obeying
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dismissal {
+    pub actor_id: Uuid,
+    pub name: String,
+    pub control: Control,
+    /// Whether the creature left play (summons, familiars) or just stopped obeying
+    pub removed: bool,
+}
+
+/// Creatures that moved to another scene together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SceneTransition {
+    pub scene_id: Uuid,
+    /// Scenes the creatures left (empty for creatures coming on stage)
+    pub from: Vec<Uuid>,
+    pub actor_ids: Vec<Uuid>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSession {
     pub id: Uuid,
     pub name: String,
     pub current_scene: Option<Uuid>,
     pub scenes: HashMap<Uuid, Scene>,
+    /// Every creature in the session, wherever it is (see [`Actor::scene_id`])
+    #[serde(default)]
+    pub actors: HashMap<Uuid, Actor>,
     pub turn_order: TurnOrder,
     pub effects: Vec<Effect>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// In-game date and time
+    #[serde(default)]
+    pub clock: WorldClock,
+    /// Clock hooks fired since the last `take_clock_events`
+    #[serde(skip)]
+    clock_events: Vec<ClockEvent>,
+    /// Overland map, party location and the journey underway
+    #[serde(default)]
+    pub travel: TravelState,
+    /// Scene transitions since the last `take_scene_transitions`
+    #[serde(skip)]
+    transitions: Vec<SceneTransition>,
+    /// Dismissals since the last `take_dismissals`
+    #[serde(skip)]
+    dismissals: Vec<Dismissal>,
 }
 
 impl GameSession {
@@ -25,22 +72,42 @@ impl GameSession {
             name,
             current_scene: None,
             scenes: HashMap::new(),
+            actors: HashMap::new(),
             turn_order: TurnOrder::new(),
             effects: Vec::new(),
             created_at: chrono::Utc::now(),
+            clock: WorldClock::default(),
+            clock_events: Vec::new(),
+            travel: TravelState::default(),
+            transitions: Vec::new(),
+            dismissals: Vec::new(),
         }
     }
 
     pub fn create_scene(&mut self, name: String) -> Uuid {
-        let scene = Scene::new(name);
-        let id = scene.id;
-        self.scenes.insert(id, scene);
+        let id = Uuid::new_v4();
+        self.scenes.insert(id, Scene::with_id(id, name));
         if self.current_scene.is_none() {
             self.current_scene = Some(id);
         }
         id
     }
 
+    /// Create a scene with a known id (used when replaying events)
+    pub fn create_scene_with_id(&mut self, scene_id: Uuid, name: String) -> Result<()> {
+        if self.scenes.contains_key(&scene_id) {
+            return Err(GameError::State(format!(
+                "Scene already exists: {}",
+                scene_id
+            )));
+        }
+        self.scenes.insert(scene_id, Scene::with_id(scene_id, name));
+        if self.current_scene.is_none() {
+            self.current_scene = Some(scene_id);
+        }
+        Ok(())
+    }
+
     pub fn set_current_scene(&mut self, scene_id: Uuid) -> Result<()> {
         if !self.scenes.contains_key(&scene_id) {
             return Err(GameError::State(format!("Scene not found: {}", scene_id)));
@@ -57,74 +124,1117 @@ impl GameSession {
         self.current_scene.and_then(|id| self.scenes.get_mut(&id))
     }
 
-    pub fn add_actor_to_scene(&mut self, scene_id: Uuid, actor: Actor) -> Result<()> {
-        let scene = self
-            .scenes
-            .get_mut(&scene_id)
-            .ok_or_else(|| GameError::State(format!("Scene not found: {}", scene_id)))?;
-        scene.add_actor(actor);
+    pub fn add_actor_to_scene(&mut self, scene_id: Uuid, mut actor: Actor) -> Result<()> {
+        if !self.scenes.contains_key(&scene_id) {
+            return Err(GameError::State(format!("Scene not found: {}", scene_id)));
+        }
+        actor.scene_id = Some(scene_id);
+        self.actors.insert(actor.id, actor);
         Ok(())
     }
 
+    /// Take a creature out of the session altogether
+    pub fn remove_actor(&mut self, actor_id: Uuid) -> Result<Actor> {
+        self.actors
+            .remove(&actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))
+    }
+
+    pub fn get_actor(&self, actor_id: Uuid) -> Option<&Actor> {
+        self.actors.get(&actor_id)
+    }
+
+    pub fn get_actor_mut(&mut self, actor_id: Uuid) -> Option<&mut Actor> {
+        self.actors.get_mut(&actor_id)
+    }
+
+    /// Creatures in `scene_id`
+    pub fn actors_in(&self, scene_id: Uuid) -> Vec<&Actor> {
+        self.actors
+            .values()
+            .filter(|a| a.scene_id == Some(scene_id))
+            .collect()
+    }
+
+    /// Creatures in the current scene
+    pub fn scene_actors(&self) -> Vec<&Actor> {
+        self.current_scene
+            .map(|id| self.actors_in(id))
+            .unwrap_or_default()
+    }
+
+    fn scene_actors_mut(&mut self) -> impl Iterator<Item = &mut Actor> {
+        let scene_id = self.current_scene;
+        self.actors
+            .values_mut()
+            .filter(move |a| scene_id.is_some() && a.scene_id == scene_id)
+    }
+
+    /// A creature in the current scene
+    fn actor_here(&self, actor_id: Uuid) -> Result<&Actor> {
+        self.get_actor(actor_id)
+            .filter(|a| self.current_scene.is_some() && a.scene_id == self.current_scene)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))
+    }
+
+    /// Squares taken by living creatures in the current scene other than `except`
+    pub fn occupied_squares(&self, except: Option<Uuid>) -> HashSet<GridPos> {
+        self.scene_actors()
+            .into_iter()
+            .filter(|a| Some(a.id) != except && a.is_alive())
+            .flat_map(|a| a.footprint())
+            .collect()
+    }
+
+    /// Creatures `mover_id` must route around: hostile squares block, allied squares can
+    /// be passed through, and leaving a hostile creature's reach costs `opportunity_cost` ft
+    pub fn path_options(&self, mover_id: Uuid, opportunity_cost: i32) -> Option<PathOptions> {
+        let mover = self.actor_here(mover_id).ok()?;
+        let mut options = PathOptions {
+            size: mover.size.squares(),
+            opportunity_cost,
+            ..PathOptions::default()
+        };
+
+        for other in self.scene_actors() {
+            if other.id == mover_id || !other.is_alive() {
+                continue;
+            }
+            if !mover.is_hostile_to(other) {
+                options.allied.extend(other.footprint());
+                continue;
+            }
+            options.hostile.extend(other.footprint());
+            // Reach of 5 ft around the hostile creature's space
+            let size = other.size.squares();
+            let corner = other.grid_position();
+            for square in footprint(GridPos::new(corner.x - 1, corner.y - 1), size + 2) {
+                options
+                    .threatened
+                    .entry(square)
+                    .or_default()
+                    .insert(other.id);
+            }
+        }
+        Some(options)
+    }
+
+    /// The player characters and the creatures they control, by name
+    pub fn party(&self) -> Vec<Uuid> {
+        let is_player = |id: Uuid| {
+            self.get_actor(id)
+                .is_some_and(|a| a.actor_type == ActorType::Player)
+        };
+        let mut party: Vec<&Actor> = self
+            .actors
+            .values()
+            .filter(|a| {
+                a.actor_type == ActorType::Player
+                    || a.controlled_by
+                        .as_ref()
+                        .is_some_and(|c| is_player(c.controller_id))
+            })
+            .collect();
+        party.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        party.into_iter().map(|a| a.id).collect()
+    }
+
+    /// Where each part of a (possibly split) party is: scene -> members
+    pub fn party_locations(&self) -> BTreeMap<Uuid, Vec<Uuid>> {
+        let mut locations: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+        for actor_id in self.party() {
+            if let Some(scene_id) = self.actors.get(&actor_id).and_then(|a| a.scene_id) {
+                locations.entry(scene_id).or_default().push(actor_id);
+            }
+        }
+        locations
+    }
+
+    /// Move creatures to `scene_id` together, keeping all their state, and follow them
+    /// there. Arrivals are placed around the scene's entrance if it has one.
+    pub fn transition(&mut self, scene_id: Uuid, actor_ids: &[Uuid]) -> Result<SceneTransition> {
+        if !self.scenes.contains_key(&scene_id) {
+            return Err(GameError::State(format!("Scene not found: {}", scene_id)));
+        }
+        let mut movers: Vec<Uuid> = Vec::with_capacity(actor_ids.len());
+        for actor_id in actor_ids {
+            if !movers.contains(actor_id) {
+                movers.push(*actor_id);
+            }
+        }
+        if movers.is_empty() {
+            return Err(GameError::State("Nobody to move".to_string()));
+        }
+
+        let mut from = Vec::new();
+        for actor_id in &movers {
+            let actor = self
+                .get_actor(*actor_id)
+                .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+            let Some(origin) = actor.scene_id.filter(|id| *id != scene_id) else {
+                continue;
+            };
+            if self.scenes.get(&origin).is_some_and(|s| s.combat_active) {
+                return Err(GameError::State(format!(
+                    "{} cannot leave a scene during combat",
+                    actor.name
+                )));
+            }
+            if !from.contains(&origin) {
+                from.push(origin);
+            }
+        }
+
+        let squares = self.arrival_squares(scene_id, &movers);
+        for (index, actor_id) in movers.iter().enumerate() {
+            if let Some(actor) = self.actors.get_mut(actor_id) {
+                actor.scene_id = Some(scene_id);
+                actor.movement_used = 0;
+                if let Some(square) = squares.get(index) {
+                    actor.set_grid_position(*square, 0);
+                }
+            }
+        }
+        if let Some(scene) = self.scenes.get_mut(&scene_id) {
+            scene.visited_at = Some(self.clock.now());
+        }
+        self.current_scene = Some(scene_id);
+
+        let transition = SceneTransition {
+            scene_id,
+            from,
+            actor_ids: movers,
+        };
+        self.transitions.push(transition.clone());
+        Ok(transition)
+    }
+
+    /// Bring the whole party, wherever its members are, to `scene_id`
+    pub fn transition_party(&mut self, scene_id: Uuid) -> Result<SceneTransition> {
+        let party = self.party();
+        self.transition(scene_id, &party)
+    }
+
+    /// Free squares closest to the entrance of `scene_id`, one per mover (none without
+    /// a grid or entrance)
+    fn arrival_squares(&self, scene_id: Uuid, movers: &[Uuid]) -> Vec<GridPos> {
+        let Some(scene) = self.scenes.get(&scene_id) else {
+            return Vec::new();
+        };
+        let (Some(grid), Some(entrance)) = (scene.grid.as_ref(), scene.entrance) else {
+            return Vec::new();
+        };
+        let occupied: HashSet<GridPos> = self
+            .actors_in(scene_id)
+            .into_iter()
+            .filter(|a| a.is_alive() && !movers.contains(&a.id))
+            .flat_map(|a| a.footprint())
+            .collect();
+        let mut free: Vec<GridPos> = (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| GridPos::new(x, y)))
+            .filter(|pos| grid.is_passable(*pos) && !occupied.contains(pos))
+            .collect();
+        free.sort_by_key(|pos| (pos.squares_to(entrance), pos.y, pos.x));
+        free.truncate(movers.len());
+        free
+    }
+
+    /// Drain scene transitions made since the last call
+    pub fn take_scene_transitions(&mut self) -> Vec<SceneTransition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// Start combat using each actor's current initiative (highest first, DEX breaks ties)
     pub fn start_combat(&mut self) -> Result<()> {
-        let scene = self
-            .get_current_scene_mut()
-            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        if self.current_scene.is_none() {
+            return Err(GameError::State("No current scene".to_string()));
+        }
 
-        scene.start_combat();
+        let mut actors = self.scene_actors();
+        actors.sort_by_key(|a| {
+            std::cmp::Reverse((a.initiative.unwrap_or(i32::MIN), a.abilities.dexterity))
+        });
+        let actor_ids = actors.iter().map(|a| a.id).collect();
 
-        // Initialize turn order with all actors in scene
-        let actor_ids: Vec<Uuid> = scene
-            .all_actors()
-            .iter()
-            .filter(|a| a.active && a.is_alive())
-            .map(|a| a.id)
+        self.begin_combat(actor_ids, &[])
+    }
+
+    /// Start combat with initiative rolled by the rules engine
+    ///
+    /// `order` is the final turn order (ties already broken), highest initiative first.
+    /// Surprised creatures skip their turn in round 1.
+    pub fn start_combat_with_initiative(
+        &mut self,
+        order: Vec<(Uuid, i32)>,
+        surprised: &[Uuid],
+    ) -> Result<()> {
+        if self.current_scene.is_none() {
+            return Err(GameError::State("No current scene".to_string()));
+        }
+
+        let mut actor_ids = Vec::with_capacity(order.len());
+        for (actor_id, initiative) in order {
+            self.actor_here(actor_id)?;
+            if let Some(actor) = self.get_actor_mut(actor_id) {
+                actor.set_initiative(initiative);
+                actor.action_used = false;
+                actor.bonus_action_used = false;
+            }
+            actor_ids.push(actor_id);
+        }
+
+        self.begin_combat(actor_ids, surprised)
+    }
+
+    fn begin_combat(&mut self, actor_ids: Vec<Uuid>, surprised: &[Uuid]) -> Result<()> {
+        self.get_current_scene_mut()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?
+            .start_combat();
+
+        // Only active, living actors in the scene take turns
+        let actor_ids: Vec<Uuid> = actor_ids
+            .into_iter()
+            .filter(|id| {
+                self.actor_here(*id)
+                    .map(|a| a.active && a.is_alive())
+                    .unwrap_or(false)
+            })
             .collect();
 
         if actor_ids.is_empty() {
             return Err(GameError::State("No active actors in scene".to_string()));
         }
 
+        // Creatures sharing their controller's initiative act right after it
+        let (followers, mut actor_ids): (Vec<Uuid>, Vec<Uuid>) =
+            actor_ids.into_iter().partition(|id| {
+                self.shares_turn_with(*id)
+                    .is_some_and(|controller_id| self.actor_here(controller_id).is_ok())
+            });
+        for follower in followers {
+            let controller_id = self.shares_turn_with(follower).unwrap_or_default();
+            match self.follow_position(&actor_ids, controller_id) {
+                Some(position) => {
+                    actor_ids.insert(position, follower);
+                    let initiative = self.get_actor(controller_id).and_then(|a| a.initiative);
+                    if let (Some(initiative), Some(actor)) =
+                        (initiative, self.actors.get_mut(&follower))
+                    {
+                        actor.set_initiative(initiative);
+                    }
+                }
+                None => actor_ids.push(follower),
+            }
+        }
+
+        let scores: Vec<(Uuid, i32)> = actor_ids
+            .iter()
+            .filter_map(|id| {
+                self.get_actor(*id)
+                    .and_then(|a| a.initiative.map(|i| (*id, i)))
+            })
+            .collect();
+
+        let lair_owner = actor_ids.iter().copied().find(|id| {
+            self.get_actor(*id)
+                .and_then(|a| a.legendary.as_ref())
+                .is_some_and(|l| l.has_lair_actions())
+        });
+        for actor in self.scene_actors_mut() {
+            if let Some(legendary) = actor.legendary.as_mut() {
+                legendary.reset_actions();
+            }
+        }
+
         self.turn_order.set_initiative_order(actor_ids)?;
+        self.turn_order.set_initiative_scores(scores);
+        self.turn_order.set_surprised(surprised.iter().copied());
+        self.turn_order.set_lair(lair_owner);
+
+        let (actors, effects) = (&self.actors, &self.effects);
+        self.turn_order
+            .start(|actor_id| Self::skip_reason(actors, effects, actor_id));
         Ok(())
     }
 
-    pub fn next_turn(&mut self) -> Result<Option<Uuid>> {
-        // Process expired effects
-        self.effects.retain(|e| !e.is_expired());
+    /// Controller whose turn `actor_id` follows, if it shares its controller's initiative
+    fn shares_turn_with(&self, actor_id: Uuid) -> Option<Uuid> {
+        self.get_actor(actor_id)?
+            .controlled_by
+            .as_ref()
+            .filter(|c| c.shared_initiative)
+            .map(|c| c.controller_id)
+    }
 
-        // Apply active effects
+    /// Index right after `controller_id` and the creatures already following it in `order`
+    fn follow_position(&self, order: &[Uuid], controller_id: Uuid) -> Option<usize> {
+        let mut index = order.iter().position(|id| *id == controller_id)?;
+        while order
+            .get(index + 1)
+            .is_some_and(|id| self.shares_turn_with(*id) == Some(controller_id))
+        {
+            index += 1;
+        }
+        Some(index + 1)
+    }
+
+    /// Why `actor_id` can't act this turn, if it can't
+    fn skip_reason(
+        actors: &HashMap<Uuid, Actor>,
+        effects: &[Effect],
+        actor_id: Uuid,
+    ) -> Option<SkipReason> {
+        let actor = actors.get(&actor_id)?;
+        if !actor.is_alive() {
+            return Some(SkipReason::Dead);
+        }
+        if effects
+            .iter()
+            .any(|e| e.target_id == actor_id && e.is_incapacitating())
+        {
+            return Some(SkipReason::Incapacitated);
+        }
+        None
+    }
+
+    pub fn next_turn(&mut self) -> Result<Option<Uuid>> {
+        // Apply active effects (expired ones were removed when the clock passed their end)
         if let Some(current_actor_id) = self.turn_order.current_actor() {
             // Collect effects to apply
             let effects_to_apply: Vec<(i32, i32)> = self
                 .effects
                 .iter()
-                .filter(|e| e.target_id == current_actor_id && !e.is_expired())
+                .filter(|e| e.target_id == current_actor_id)
                 .map(|e| (e.apply_damage().unwrap_or(0), e.apply_heal().unwrap_or(0)))
                 .collect();
 
             // Apply effects
-            if let Some(scene) = self.get_current_scene_mut() {
-                if let Some(actor) = scene.get_actor_mut(current_actor_id) {
-                    for (damage, heal) in effects_to_apply {
-                        if damage > 0 {
-                            actor.take_damage(damage);
-                        }
-                        if heal > 0 {
-                            actor.heal(heal);
-                        }
+            if let Some(actor) = self.get_actor_mut(current_actor_id) {
+                for (damage, heal) in effects_to_apply {
+                    if damage > 0 {
+                        actor.take_damage(damage);
+                    }
+                    if heal > 0 {
+                        actor.heal(heal);
                     }
                 }
             }
         }
 
-        Ok(self.turn_order.next_turn())
+        // Dead monsters leave the order; fallen players keep their place
+        let fallen: Vec<Uuid> = self
+            .turn_order
+            .all_actors()
+            .into_iter()
+            .filter(|id| {
+                self.actor_here(*id)
+                    .map_or(true, |a| !a.is_alive() && a.actor_type != ActorType::Player)
+            })
+            .collect();
+        for actor_id in fallen {
+            self.turn_order.remove_actor(actor_id);
+        }
+
+        let (actors, effects) = (&self.actors, &self.effects);
+        let round = self.turn_order.round();
+        let next_actor = self.turn_order.next_turn_with(
+            |actor_id| Self::skip_reason(actors, effects, actor_id),
+            |actor_id| Self::can_take_legendary_action(actors, effects, actor_id),
+        );
+
+        // Each new round is six seconds of world time
+        let rounds_passed = self.turn_order.round().saturating_sub(round);
+        if rounds_passed > 0 {
+            self.advance_time(rounds_passed as u64 * ROUND_SECONDS);
+        }
+
+        // Movement, actions and legendary actions are regained at the start of the
+        // creature's turn
+        if let Some(actor) = next_actor.and_then(|id| self.get_actor_mut(id)) {
+            actor.movement_used = 0;
+            actor.action_used = false;
+            actor.bonus_action_used = false;
+            if let Some(legendary) = actor.legendary.as_mut() {
+                legendary.reset_actions();
+            }
+        }
+
+        // Advantage granted by this creature (e.g. Help) lasts until the start of its next turn
+        if let Some(next_actor_id) = next_actor {
+            for actor in self.scene_actors_mut() {
+                actor.expire_advantage_from(next_actor_id);
+            }
+        }
+
+        Ok(next_actor)
+    }
+
+    /// Whether `actor_id` can spend a legendary action at the end of another creature's turn
+    fn can_take_legendary_action(
+        actors: &HashMap<Uuid, Actor>,
+        effects: &[Effect],
+        actor_id: Uuid,
+    ) -> bool {
+        let affordable = actors
+            .get(&actor_id)
+            .and_then(|a| a.legendary.as_ref())
+            .is_some_and(|l| l.has_legendary_actions() && !l.affordable_actions().is_empty());
+        affordable && Self::skip_reason(actors, effects, actor_id).is_none()
+    }
+
+    /// Spend one of `actor_id`'s legendary actions by name
+    pub fn use_legendary_action(
+        &mut self,
+        actor_id: Uuid,
+        action: &str,
+    ) -> Result<LegendaryAction> {
+        self.legendary_traits_mut(actor_id)?.spend_action(action)
+    }
+
+    /// Legendary Resistance: turn a failed save into a success.
+    /// Returns false if the creature has no charges left.
+    pub fn use_legendary_resistance(&mut self, actor_id: Uuid) -> Result<bool> {
+        Ok(self.legendary_traits_mut(actor_id)?.use_resistance())
+    }
+
+    /// Check that `action` is one of the lair owner's lair actions
+    pub fn use_lair_action(&mut self, actor_id: Uuid, action: &str) -> Result<String> {
+        if self.turn_order.lair_owner() != Some(actor_id) {
+            return Err(GameError::State(format!(
+                "Actor has no lair in this combat: {}",
+                actor_id
+            )));
+        }
+        self.legendary_traits_mut(actor_id)?
+            .lair_actions
+            .iter()
+            .find(|a| a.eq_ignore_ascii_case(action))
+            .cloned()
+            .ok_or_else(|| GameError::State(format!("Unknown lair action: {}", action)))
+    }
+
+    fn legendary_traits_mut(&mut self, actor_id: Uuid) -> Result<&mut LegendaryTraits> {
+        let actor = self
+            .get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+        actor
+            .legendary
+            .as_mut()
+            .ok_or_else(|| GameError::State(format!("Actor is not legendary: {}", actor.name)))
+    }
+
+    /// Move an actor along `path` on the current scene's grid, spending movement.
+    /// Out of combat each move is only limited by the actor's speed.
+    pub fn move_actor(&mut self, actor_id: Uuid, path: &[GridPos]) -> Result<Movement> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        let grid = scene
+            .grid
+            .as_ref()
+            .ok_or_else(|| GameError::State(format!("Scene has no grid: {}", scene.name)))?;
+        let actor = self.actor_here(actor_id)?;
+
+        let available = if scene.combat_active {
+            actor.remaining_movement()
+        } else {
+            actor.speed
+        };
+        let options = self.path_options(actor_id, 0).unwrap_or_default();
+        let movement =
+            pathfinding::trace_path(grid, actor.grid_position(), path, available, &options)?;
+        let elevation = movement
+            .destination()
+            .and_then(|pos| grid.cell(pos))
+            .map(|cell| cell.elevation);
+
+        let combat_active = scene.combat_active;
+        if let (Some(actor), Some(destination), Some(elevation)) = (
+            self.get_actor_mut(actor_id),
+            movement.destination(),
+            elevation,
+        ) {
+            actor.set_grid_position(destination, elevation);
+            if combat_active {
+                actor.movement_used += movement.cost_ft;
+            }
+        }
+        Ok(movement)
+    }
+
+    /// Move an actor to `destination` along the cheapest path
+    pub fn move_actor_to(&mut self, actor_id: Uuid, destination: GridPos) -> Result<Movement> {
+        let path = self.find_path_for(actor_id, PathGoal::Square(destination))?;
+        self.move_actor(actor_id, &path.path)
+    }
+
+    /// Put an actor on `pos` without walking there or spending movement (the DM moving a
+    /// token by hand). The square must be on the grid when the scene has one.
+    pub fn place_actor(&mut self, actor_id: Uuid, pos: GridPos) -> Result<()> {
+        let elevation = match self.get_current_scene().and_then(|s| s.grid.as_ref()) {
+            Some(grid) => grid
+                .cell(pos)
+                .map(|cell| cell.elevation)
+                .ok_or_else(|| GameError::State(format!("Square off the grid: {:?}", pos)))?,
+            None => 0,
+        };
+        self.actor_here(actor_id)?;
+        if let Some(actor) = self.get_actor_mut(actor_id) {
+            actor.set_grid_position(pos, elevation);
+        }
+        Ok(())
+    }
+
+    /// Path for `actor_id` toward `target_id` that stops within `reach_ft`, cut short to
+    /// the movement the actor has left. Routes around hostile creatures and avoids
+    /// provoking opportunity attacks when it can.
+    pub fn path_toward(
+        &self,
+        actor_id: Uuid,
+        target_id: Uuid,
+        reach_ft: i32,
+    ) -> Result<PathResult> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        let target = self.actor_here(target_id)?;
+        let goal = PathGoal::Reach {
+            target: target.grid_position(),
+            target_size: target.size.squares(),
+            reach: (reach_ft / SQUARE_FEET).max(1),
+        };
+        let path = self.find_path_for(actor_id, goal)?;
+
+        let (grid, actor, options) = self.path_context(actor_id)?;
+        let available = if scene.combat_active {
+            actor.remaining_movement()
+        } else {
+            actor.speed
+        };
+        Ok(pathfinding::truncate_path(
+            grid,
+            actor.grid_position(),
+            &path,
+            available,
+            &options,
+        ))
+    }
+
+    /// Move `actor_id` as far as it can toward `target_id`, stopping within `reach_ft`
+    pub fn move_toward(
+        &mut self,
+        actor_id: Uuid,
+        target_id: Uuid,
+        reach_ft: i32,
+    ) -> Result<PathResult> {
+        let path = self.path_toward(actor_id, target_id, reach_ft)?;
+        self.move_actor(actor_id, &path.path)?;
+        Ok(path)
+    }
+
+    fn find_path_for(&self, actor_id: Uuid, goal: PathGoal) -> Result<PathResult> {
+        let (grid, actor, options) = self.path_context(actor_id)?;
+        pathfinding::find_path(grid, actor.grid_position(), goal, &options)
+            .ok_or_else(|| GameError::State(format!("No path for {} to {:?}", actor.name, goal)))
+    }
+
+    fn path_context(&self, actor_id: Uuid) -> Result<(&Grid, &Actor, PathOptions)> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        let grid = scene
+            .grid
+            .as_ref()
+            .ok_or_else(|| GameError::State(format!("Scene has no grid: {}", scene.name)))?;
+        let actor = self.actor_here(actor_id)?;
+        let options = self
+            .path_options(actor_id, OPPORTUNITY_ATTACK_COST_FT)
+            .unwrap_or_default();
+        Ok((grid, actor, options))
+    }
+
+    /// Cover and sight between an attacker and its target, or `None` without a grid
+    pub fn attack_visibility(
+        &self,
+        attacker_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<Option<AttackVisibility>> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        let Some(grid) = scene.grid.as_ref() else {
+            return Ok(None);
+        };
+        let (attacker, target) = (self.actor_here(attacker_id)?, self.actor_here(target_id)?);
+        let (from, to) = (attacker.grid_position(), target.grid_position());
+
+        let mut creatures = self.occupied_squares(Some(attacker_id));
+        for square in target.footprint() {
+            creatures.remove(&square);
+        }
+        let attacker_sight =
+            visibility::sight(grid, from, &attacker.senses, to, scene.light_at(to));
+        let target_sight = visibility::sight(grid, to, &target.senses, from, scene.light_at(from));
+        Ok(Some(AttackVisibility {
+            cover: visibility::cover(grid, from, to, &creatures),
+            attacker_sees_target: attacker_sight.can_see(),
+            target_sees_attacker: target_sight.can_see(),
+        }))
+    }
+
+    /// Squares `actor_id` can currently see, for fog of war
+    pub fn visible_cells(&self, actor_id: Uuid) -> Result<HashSet<GridPos>> {
+        let (grid, actor, _) = self.path_context(actor_id)?;
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        Ok(visibility::visible_cells(
+            grid,
+            actor.grid_position(),
+            &actor.senses,
+            scene.ambient_light,
+            &scene.lights,
+        ))
+    }
+
+    /// Distance in feet between the closest squares of two creatures, or `None` without a grid
+    pub fn distance_between(&self, actor_id: Uuid, other_id: Uuid) -> Result<Option<i32>> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        if scene.grid.is_none() {
+            return Ok(None);
+        }
+        let (actor, other) = (self.actor_here(actor_id)?, self.actor_here(other_id)?);
+        let theirs = other.footprint();
+        Ok(actor
+            .footprint()
+            .iter()
+            .flat_map(|a| theirs.iter().map(move |b| a.distance_ft(*b)))
+            .min())
+    }
+
+    /// Whether a hostile creature that can see `actor_id` and isn't incapacitated is
+    /// within 5 ft (ranged attacks then have disadvantage)
+    pub fn hostile_within_5ft(&self, actor_id: Uuid) -> bool {
+        let Ok(actor) = self.actor_here(actor_id) else {
+            return false;
+        };
+        self.scene_actors().into_iter().any(|other| {
+            other.is_hostile_to(actor)
+                && Self::skip_reason(&self.actors, &self.effects, other.id).is_none()
+                && self
+                    .distance_between(other.id, actor_id)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|d| d <= SQUARE_FEET)
+                && self
+                    .attack_visibility(other.id, actor_id)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|v| v.attacker_sees_target)
+        })
+    }
+
+    /// Take one piece of ammunition from `actor_id`'s inventory, returning how many are left
+    pub fn spend_ammunition(&mut self, actor_id: Uuid, ammunition: &str) -> Result<u32> {
+        let actor = self
+            .get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+        actor
+            .inventory
+            .spend_ammunition(ammunition)
+            .map_err(|_| GameError::State(format!("{} is out of {}", actor.name, ammunition)))
+    }
+
+    /// After a fight, every creature in the scene recovers half its spent ammunition
+    pub fn recover_ammunition(&mut self) -> Vec<(Uuid, String, u32)> {
+        self.scene_actors_mut()
+            .flat_map(|actor| {
+                let id = actor.id;
+                actor
+                    .inventory
+                    .recover_ammunition()
+                    .into_iter()
+                    .map(move |(name, count)| (id, name, count))
+            })
+            .collect()
+    }
+
+    /// Help action: `helper_id` grants `helped_id` advantage on its next roll matching `scope`
+    pub fn grant_help(
+        &mut self,
+        helper_id: Uuid,
+        helped_id: Uuid,
+        scope: AdvantageScope,
+    ) -> Result<()> {
+        self.grant_help_token(helped_id, AdvantageToken::new(helper_id, scope))
+    }
+
+    /// Help with a token whose id is chosen by the caller (for reproducible replays)
+    pub fn grant_help_token(&mut self, helped_id: Uuid, token: AdvantageToken) -> Result<()> {
+        let (helper_id, scope) = (token.source_id, token.scope);
+        if helper_id == helped_id {
+            return Err(GameError::State(
+                "A creature cannot take the Help action on itself".to_string(),
+            ));
+        }
+
+        if self.current_scene.is_none() {
+            return Err(GameError::State("No current scene".to_string()));
+        }
+
+        self.actor_here(helper_id)?;
+        if let AdvantageScope::AttackAgainst(target_id) = scope {
+            self.actor_here(target_id)?;
+        }
+
+        self.actor_here(helped_id)?;
+        if let Some(helped) = self.get_actor_mut(helped_id) {
+            helped.grant_advantage(token);
+        }
+        Ok(())
+    }
+
+    /// Consume any advantage token `actor_id` holds for a roll matching `scope`.
+    /// Returns true if the roll gains advantage.
+    pub fn consume_advantage(&mut self, actor_id: Uuid, scope: AdvantageScope) -> bool {
+        self.get_actor_mut(actor_id)
+            .map(|actor| actor.consume_advantage(scope))
+            .unwrap_or(false)
+    }
+
+    /// Add a creature already in the current scene to the running combat at `initiative`
+    pub fn add_to_combat(&mut self, actor_id: Uuid, initiative: i32) -> Result<()> {
+        let scene = self
+            .get_current_scene()
+            .ok_or_else(|| GameError::State("No current scene".to_string()))?;
+        if !scene.combat_active {
+            return Err(GameError::State("Combat is not active".to_string()));
+        }
+        self.actor_here(actor_id)?;
+
+        // A creature sharing its controller's initiative ignores `initiative`
+        if let Some(controller_id) = self.shares_turn_with(actor_id) {
+            let order = self.turn_order.all_actors();
+            if let Some(position) = self.follow_position(&order, controller_id) {
+                self.turn_order
+                    .insert_after(actor_id, order[position - 1])?;
+                if let (Some(initiative), Some(actor)) = (
+                    self.turn_order.initiative_of(actor_id),
+                    self.actors.get_mut(&actor_id),
+                ) {
+                    actor.set_initiative(initiative);
+                }
+                return Ok(());
+            }
+        }
+
+        let actor = self
+            .get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+        actor.set_initiative(initiative);
+        let has_lair = actor
+            .legendary
+            .as_ref()
+            .is_some_and(|l| l.has_lair_actions());
+
+        self.turn_order.insert_actor(actor_id, initiative);
+        if has_lair && self.turn_order.lair_owner().is_none() {
+            self.turn_order.set_lair(Some(actor_id));
+        }
+        Ok(())
+    }
+
+    /// Put `actor_id` under `control`. In a running combat a creature that now shares its
+    /// controller's initiative moves to right after the controller.
+    pub fn set_control(&mut self, actor_id: Uuid, control: Control) -> Result<()> {
+        if actor_id == control.controller_id {
+            return Err(GameError::State(
+                "A creature cannot control itself".to_string(),
+            ));
+        }
+        let controller = self.get_actor(control.controller_id).ok_or_else(|| {
+            GameError::State(format!("Actor not found: {}", control.controller_id))
+        })?;
+        if controller.is_controlled_by(actor_id) {
+            return Err(GameError::State(format!(
+                "{} is already controlled by this creature",
+                controller.name
+            )));
+        }
+        let shared = control.shared_initiative;
+        self.get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?
+            .controlled_by = Some(control);
+
+        if shared && self.turn_order.all_actors().contains(&actor_id) {
+            let initiative = self.turn_order.initiative_of(actor_id).unwrap_or(0);
+            self.turn_order.remove_actor(actor_id);
+            self.add_to_combat(actor_id, initiative)?;
+        }
+        Ok(())
+    }
+
+    /// Creatures obeying `controller_id`
+    pub fn controlled_by(&self, controller_id: Uuid) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = self
+            .actors
+            .values()
+            .filter(|a| a.is_controlled_by(controller_id))
+            .map(|a| a.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// End control of `actor_id`: summons and familiars vanish, companions and charmed
+    /// creatures stay but no longer obey
+    pub fn dismiss(&mut self, actor_id: Uuid) -> Result<Dismissal> {
+        let actor = self
+            .get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+        let control = actor
+            .controlled_by
+            .take()
+            .ok_or_else(|| GameError::State(format!("{} is not controlled", actor.name)))?;
+        let dismissal = Dismissal {
+            actor_id,
+            name: actor.name.clone(),
+            removed: control.kind.leaves_when_dismissed(),
+            control,
+        };
+        if dismissal.removed {
+            self.remove_from_combat(actor_id);
+            self.remove_actor(actor_id)?;
+        }
+        self.dismissals.push(dismissal.clone());
+        Ok(dismissal)
+    }
+
+    /// End every effect `caster_id` concentrates on, dismissing creatures bound to them.
+    /// Returns the ended effects' ids.
+    pub fn end_concentration(&mut self, caster_id: Uuid) -> Vec<Uuid> {
+        let ended: Vec<Uuid> = self
+            .effects
+            .iter()
+            .filter(|e| e.concentration_of == Some(caster_id))
+            .map(|e| e.id)
+            .collect();
+        self.effects.retain(|e| !ended.contains(&e.id));
+        self.dismiss_bound_to(&ended);
+        ended
+    }
+
+    /// End `condition` on `actor_id` whatever caused it. Returns the ended effects' ids.
+    pub fn remove_condition(&mut self, actor_id: Uuid, condition: &str) -> Vec<Uuid> {
+        let ended: Vec<Uuid> = self
+            .effects
+            .iter()
+            .filter(|e| {
+                e.target_id == actor_id
+                    && matches!(&e.effect_type, EffectType::Condition(c) if c.eq_ignore_ascii_case(condition))
+            })
+            .map(|e| e.id)
+            .collect();
+        self.effects.retain(|e| !ended.contains(&e.id));
+        self.dismiss_bound_to(&ended);
+        ended
+    }
+
+    /// Dismiss creatures whose controlling effect is one of `effect_ids`
+    fn dismiss_bound_to(&mut self, effect_ids: &[Uuid]) {
+        let mut bound: Vec<Uuid> = self
+            .actors
+            .values()
+            .filter(|a| {
+                a.controlled_by
+                    .as_ref()
+                    .and_then(|c| c.effect_id)
+                    .is_some_and(|id| effect_ids.contains(&id))
+            })
+            .map(|a| a.id)
+            .collect();
+        bound.sort();
+        for actor_id in bound {
+            let _ = self.dismiss(actor_id);
+        }
+    }
+
+    /// Drain dismissals made since the last call
+    pub fn take_dismissals(&mut self) -> Vec<Dismissal> {
+        std::mem::take(&mut self.dismissals)
+    }
+
+    /// A creature uses one of its items. In combat this takes the action `cost` says;
+    /// then a charge is spent (`charges` is the item's full charges, for its first use) or
+    /// one of the stack is used up. Returns how many charges or items are left.
+    pub fn use_item(
+        &mut self,
+        actor_id: Uuid,
+        item: &str,
+        cost: ActionCost,
+        charges: Option<Charges>,
+    ) -> Result<u32> {
+        let in_combat = self.in_combat();
+        let actor = self
+            .get_actor_mut(actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not found: {}", actor_id)))?;
+        if actor.inventory.count(item) == 0 {
+            return Err(GameError::State(format!("{} has no {}", actor.name, item)));
+        }
+        if in_combat && !actor.has_action(cost) {
+            return Err(GameError::State(format!(
+                "{} has already used its {} this turn",
+                actor.name,
+                cost.name()
+            )));
+        }
+        let left = match charges {
+            Some(full) => {
+                if !actor.charges.keys().any(|k| k.eq_ignore_ascii_case(item)) {
+                    actor
+                        .charges
+                        .insert(item.to_string(), Charges::new(full.max, full.recharge));
+                }
+                actor
+                    .use_charge(item)
+                    .ok_or_else(|| GameError::State(format!("{} has no charges left", item)))?
+            }
+            None => actor.inventory.remove(item, 1)?,
+        };
+        if in_combat {
+            actor.spend_action(cost);
+        }
+        Ok(left)
+    }
+
+    /// Take a creature out of the turn order (fled, banished, killed). It stays in the scene.
+    pub fn remove_from_combat(&mut self, actor_id: Uuid) {
+        self.turn_order.remove_actor(actor_id);
     }
 
-    pub fn apply_effect(&mut self, effect: Effect) {
+    /// The current creature delays its turn until it chooses to resume
+    pub fn delay_turn(&mut self, actor_id: Uuid) -> Result<()> {
+        self.turn_order.delay_turn(actor_id)
+    }
+
+    /// A delaying creature takes its turn right after the current one
+    pub fn resume_delayed(&mut self, actor_id: Uuid) -> Result<()> {
+        self.turn_order.resume_delayed(actor_id)?;
+        let initiative = self.turn_order.initiative_of(actor_id);
+        if let (Some(initiative), Some(actor)) = (initiative, self.actors.get_mut(&actor_id)) {
+            actor.set_initiative(initiative);
+        }
+        Ok(())
+    }
+
+    /// DM override: move a creature to `index` in the turn order
+    pub fn move_in_initiative(&mut self, actor_id: Uuid, index: usize) -> Result<()> {
+        self.turn_order.move_actor(actor_id, index)
+    }
+
+    /// Drain turn start/end/skip events emitted since the last call
+    pub fn take_turn_events(&mut self) -> Vec<TurnEvent> {
+        self.turn_order.take_events()
+    }
+
+    /// Apply an effect; its duration runs on the world clock from now. A caster can only
+    /// concentrate on one spell, so a new concentration effect ends the old ones.
+    pub fn apply_effect(&mut self, mut effect: Effect) {
+        if let Some(caster_id) = effect.concentration_of {
+            let previous: Vec<Uuid> = self
+                .effects
+                .iter()
+                .filter(|e| e.concentration_of == Some(caster_id))
+                .map(|e| e.id)
+                .collect();
+            // Several effects of one spell (e.g. Bless on three allies) share a name
+            if self
+                .effects
+                .iter()
+                .any(|e| previous.contains(&e.id) && e.name != effect.name)
+            {
+                self.end_concentration(caster_id);
+            }
+        }
+        if effect.ends_at.is_none() {
+            effect.ends_at = effect
+                .duration_rounds
+                .map(|rounds| self.clock.now() + rounds as u64 * ROUND_SECONDS);
+        }
         self.effects.push(effect);
     }
 
+    /// Move the world clock forward, expiring effects and recharging abilities at dawn
+    /// and dusk. Returns the hooks that fired, in order.
+    pub fn advance_time(&mut self, seconds: u64) -> Vec<ClockEvent> {
+        let from = self.clock.now();
+        let mut timed = self.clock.advance(seconds);
+        let to = self.clock.now();
+        timed.extend(self.effects.iter().filter_map(|e| {
+            // Effects that had already run out end as soon as time moves
+            e.ends_at.filter(|at| *at <= to).map(|at| {
+                (
+                    at.max(from),
+                    ClockEvent::EffectExpired {
+                        effect_id: e.id,
+                        target_id: e.target_id,
+                    },
+                )
+            })
+        }));
+        timed.sort_by_key(|(at, _)| *at);
+
+        let events: Vec<ClockEvent> = timed.into_iter().map(|(_, e)| e).collect();
+        for event in &events {
+            match event {
+                ClockEvent::Dawn { .. } => self.recharge_all(Recharge::Dawn),
+                ClockEvent::Dusk { .. } => self.recharge_all(Recharge::Dusk),
+                ClockEvent::EffectExpired { effect_id, .. } => {
+                    self.effects.retain(|e| e.id != *effect_id);
+                    self.dismiss_bound_to(&[*effect_id]);
+                }
+                ClockEvent::NpcActivity { .. } | ClockEvent::Scheduled { .. } => {}
+            }
+        }
+        self.clock_events.extend(events.iter().cloned());
+        events
+    }
+
+    fn recharge_all(&mut self, recharge: Recharge) {
+        for actor in self.actors.values_mut() {
+            actor.recharge(recharge);
+        }
+    }
+
+    /// Set out from the party's location toward `destination`
+    pub fn start_journey(&mut self, destination: &str, pace: TravelPace) -> Result<()> {
+        self.travel.start_journey(destination, pace)
+    }
+
+    /// Travel one watch: move along the journey, apply forced march exhaustion and
+    /// advance the clock
+    pub fn travel_watch(&mut self, rolls: &WatchRolls) -> Result<WatchReport> {
+        let report = self.travel.travel_watch(self.clock.day(), rolls)?;
+        for actor_id in &report.exhausted {
+            if let Some(actor) = self.actors.get_mut(actor_id) {
+                actor.exhaustion = (actor.exhaustion + 1).min(6);
+            }
+        }
+        self.advance_time(WATCH_HOURS as u64 * HOUR_SECONDS);
+        Ok(report)
+    }
+
+    /// Drain clock hooks fired since the last call
+    pub fn take_clock_events(&mut self) -> Vec<ClockEvent> {
+        std::mem::take(&mut self.clock_events)
+    }
+
+    pub fn in_combat(&self) -> bool {
+        self.get_current_scene().is_some_and(|s| s.combat_active)
+    }
+
     pub fn get_round(&self) -> u32 {
         self.turn_order.round()
     }
@@ -133,6 +1243,7 @@ impl GameSession {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::effect::EffectType;
 
     #[test]
     fn test_session_creation() {
@@ -163,4 +1274,609 @@ mod tests {
         let scene = session.get_current_scene().unwrap();
         assert!(scene.combat_active);
     }
+
+    #[test]
+    fn test_session_help_expires_at_helpers_next_turn() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Combat".to_string());
+
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        let goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        let (fighter_id, rogue_id, goblin_id) = (fighter.id, rogue.id, goblin.id);
+        session.add_actor_to_scene(scene_id, fighter).unwrap();
+        session.add_actor_to_scene(scene_id, rogue).unwrap();
+        session.add_actor_to_scene(scene_id, goblin).unwrap();
+        session
+            .turn_order
+            .set_initiative_order(vec![fighter_id, rogue_id, goblin_id])
+            .unwrap();
+
+        let scope = AdvantageScope::AttackAgainst(goblin_id);
+        session.grant_help(fighter_id, rogue_id, scope).unwrap();
+        assert!(session.grant_help(fighter_id, fighter_id, scope).is_err());
+
+        // Rogue's and goblin's turns: the token survives until it is used
+        session.next_turn().unwrap();
+        session.next_turn().unwrap();
+        let rogue = session.get_actor(rogue_id).unwrap();
+        assert!(rogue.has_advantage_for(scope));
+
+        // Fighter's next turn starts: the unused token expires
+        assert_eq!(session.next_turn().unwrap(), Some(fighter_id));
+        assert!(!session.consume_advantage(rogue_id, scope));
+    }
+
+    #[test]
+    fn test_session_start_combat_with_initiative() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Combat".to_string());
+
+        let wizard = Actor::new("Wizard".to_string(), ActorType::Player);
+        let goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        let (wizard_id, goblin_id) = (wizard.id, goblin.id);
+        session.add_actor_to_scene(scene_id, wizard).unwrap();
+        session.add_actor_to_scene(scene_id, goblin).unwrap();
+
+        session
+            .start_combat_with_initiative(vec![(goblin_id, 17), (wizard_id, 9)], &[goblin_id])
+            .unwrap();
+
+        assert_eq!(session.get_actor(goblin_id).unwrap().initiative, Some(17));
+        assert_eq!(session.turn_order.all_actors(), vec![goblin_id, wizard_id]);
+        // The surprised goblin loses its first turn
+        assert_eq!(session.turn_order.current_actor(), Some(wizard_id));
+    }
+
+    #[test]
+    fn test_session_next_turn_skips_dead_and_incapacitated() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Combat".to_string());
+
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let cleric = Actor::new("Cleric".to_string(), ActorType::Player);
+        let goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        let orc = Actor::new("Orc".to_string(), ActorType::Monster);
+        let (fighter_id, cleric_id, goblin_id, orc_id) = (fighter.id, cleric.id, goblin.id, orc.id);
+        for actor in [fighter, cleric, goblin, orc] {
+            session.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+        session
+            .start_combat_with_initiative(
+                vec![
+                    (fighter_id, 20),
+                    (goblin_id, 15),
+                    (cleric_id, 10),
+                    (orc_id, 5),
+                ],
+                &[],
+            )
+            .unwrap();
+        session.take_turn_events();
+
+        // The fighter kills the goblin and the cleric is stunned
+        session.get_actor_mut(goblin_id).unwrap().take_damage(1000);
+        session.apply_effect(Effect::new(
+            "Stunned".to_string(),
+            EffectType::Condition("stunned".to_string()),
+            cleric_id,
+            None,
+        ));
+
+        assert_eq!(session.next_turn().unwrap(), Some(orc_id));
+        assert!(!session.turn_order.all_actors().contains(&goblin_id));
+        let events = session.take_turn_events();
+        assert!(events.contains(&TurnEvent::TurnSkipped {
+            actor_id: cleric_id,
+            round: 1,
+            reason: SkipReason::Incapacitated,
+        }));
+        assert_eq!(
+            events.last(),
+            Some(&TurnEvent::TurnStarted {
+                actor_id: orc_id,
+                round: 1
+            })
+        );
+
+        // Reinforcements join between the fighter and the cleric
+        let ogre = Actor::new("Ogre".to_string(), ActorType::Monster);
+        let ogre_id = ogre.id;
+        session.add_actor_to_scene(scene_id, ogre).unwrap();
+        session.add_to_combat(ogre_id, 12).unwrap();
+
+        assert_eq!(session.next_turn().unwrap(), Some(fighter_id));
+        assert_eq!(session.get_round(), 2);
+        assert_eq!(session.next_turn().unwrap(), Some(ogre_id));
+    }
+
+    #[test]
+    fn test_session_legendary_actions_and_resistance() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Lair".to_string());
+
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let dragon = Actor::new("Dragon".to_string(), ActorType::Monster).with_legendary(
+            LegendaryTraits::new(
+                3,
+                vec![LegendaryAction {
+                    name: "Tail Attack".to_string(),
+                    cost: 1,
+                    description: String::new(),
+                }],
+                1,
+                vec!["Tremor".to_string()],
+            ),
+        );
+        let (fighter_id, dragon_id) = (fighter.id, dragon.id);
+        session.add_actor_to_scene(scene_id, fighter).unwrap();
+        session.add_actor_to_scene(scene_id, dragon).unwrap();
+        session
+            .start_combat_with_initiative(vec![(fighter_id, 18), (dragon_id, 12)], &[])
+            .unwrap();
+        assert_eq!(session.turn_order.lair_owner(), Some(dragon_id));
+        // Everyone is below 20, so the lair acts before the fighter
+        assert_eq!(
+            session.take_turn_events().first(),
+            Some(&TurnEvent::LairAction {
+                actor_id: dragon_id,
+                round: 1
+            })
+        );
+
+        for _ in 0..3 {
+            session
+                .use_legendary_action(dragon_id, "tail attack")
+                .unwrap();
+        }
+        assert!(session
+            .use_legendary_action(dragon_id, "Tail Attack")
+            .is_err());
+
+        // Out of actions: no window when the fighter's turn ends; regained on its own turn
+        assert_eq!(session.next_turn().unwrap(), Some(dragon_id));
+        assert!(!session
+            .take_turn_events()
+            .iter()
+            .any(|e| matches!(e, TurnEvent::LegendaryWindow { .. })));
+        session
+            .use_legendary_action(dragon_id, "Tail Attack")
+            .unwrap();
+
+        assert_eq!(
+            session.use_lair_action(dragon_id, "tremor").unwrap(),
+            "Tremor"
+        );
+        assert!(session.use_legendary_resistance(dragon_id).unwrap());
+        assert!(!session.use_legendary_resistance(dragon_id).unwrap());
+        assert!(session.use_legendary_resistance(fighter_id).is_err());
+    }
+
+    #[test]
+    fn test_session_move_actor_spends_movement() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Crypt".to_string());
+        session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(Grid::new(12, 12));
+
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let mut goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        goblin.set_grid_position(GridPos::new(6, 0), 0);
+        let (fighter_id, goblin_id) = (fighter.id, goblin.id);
+        session.add_actor_to_scene(scene_id, fighter).unwrap();
+        session.add_actor_to_scene(scene_id, goblin).unwrap();
+        session
+            .start_combat_with_initiative(vec![(fighter_id, 15), (goblin_id, 5)], &[])
+            .unwrap();
+
+        let movement = session
+            .move_actor_to(fighter_id, GridPos::new(4, 0))
+            .unwrap();
+        assert_eq!(movement.cost_ft, 20);
+        assert_eq!(movement.remaining_ft, 10);
+        // The goblin's square is taken, and 15 ft is more than what's left
+        assert!(session
+            .move_actor_to(fighter_id, GridPos::new(6, 0))
+            .is_err());
+        assert!(session
+            .move_actor_to(fighter_id, GridPos::new(4, 3))
+            .is_err());
+        assert_eq!(
+            session
+                .move_actor_to(fighter_id, GridPos::new(5, 1))
+                .unwrap()
+                .remaining_ft,
+            5
+        );
+
+        // Movement comes back on the fighter's next turn
+        session.next_turn().unwrap();
+        session.next_turn().unwrap();
+        let fighter = session.get_actor(fighter_id).unwrap();
+        assert_eq!(fighter.grid_position(), GridPos::new(5, 1));
+        assert_eq!(fighter.remaining_movement(), 30);
+    }
+
+    #[test]
+    fn test_session_move_toward_stops_in_reach() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Cave".to_string());
+        // A 2x2 ogre only fits through the bottom two rows
+        let mut grid = Grid::new(12, 7);
+        for y in 0..5 {
+            grid.set_terrain(GridPos::new(4, y), crate::grid::Terrain::Wall)
+                .unwrap();
+        }
+        session.get_current_scene_mut().unwrap().set_grid(grid);
+
+        let mut ogre = Actor::new("Ogre".to_string(), ActorType::Monster);
+        ogre.size = crate::actor::CreatureSize::Large;
+        ogre.speed = 40;
+        let mut wizard = Actor::new("Wizard".to_string(), ActorType::Player);
+        wizard.set_grid_position(GridPos::new(8, 0), 0);
+        let (ogre_id, wizard_id) = (ogre.id, wizard.id);
+        session.add_actor_to_scene(scene_id, ogre).unwrap();
+        session.add_actor_to_scene(scene_id, wizard).unwrap();
+        session
+            .start_combat_with_initiative(vec![(ogre_id, 12), (wizard_id, 10)], &[])
+            .unwrap();
+
+        // Around the wall is too far for one turn: the ogre gets as close as 40 ft allows
+        let first = session.move_toward(ogre_id, wizard_id, 5).unwrap();
+        assert!(!first.reaches_goal);
+        assert!(first.cost_ft <= 40);
+
+        session.next_turn().unwrap();
+        session.next_turn().unwrap();
+        let second = session.move_toward(ogre_id, wizard_id, 5).unwrap();
+        assert!(second.reaches_goal);
+
+        let ogre = session.get_actor(ogre_id).unwrap();
+        assert_eq!(
+            pathfinding::footprint_distance(ogre.grid_position(), 2, GridPos::new(8, 0), 1),
+            1
+        );
+    }
+    #[test]
+    fn test_session_attack_visibility_in_the_dark() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Crypt".to_string());
+        let scene = session.get_current_scene_mut().unwrap();
+        scene.set_grid(Grid::new(10, 5));
+        scene.ambient_light = crate::visibility::LightLevel::Darkness;
+
+        let mut fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        fighter.set_grid_position(GridPos::new(0, 2), 0);
+        let mut ghoul = Actor::new("Ghoul".to_string(), ActorType::Monster).with_senses(
+            crate::visibility::Senses {
+                darkvision_ft: 60,
+                ..Default::default()
+            },
+        );
+        ghoul.set_grid_position(GridPos::new(6, 2), 0);
+        let (fighter_id, ghoul_id) = (fighter.id, ghoul.id);
+        session.add_actor_to_scene(scene_id, fighter).unwrap();
+        session.add_actor_to_scene(scene_id, ghoul).unwrap();
+
+        let fighter_attack = session
+            .attack_visibility(fighter_id, ghoul_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fighter_attack.roll_mode(), Some(false));
+        let ghoul_attack = session
+            .attack_visibility(ghoul_id, fighter_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(ghoul_attack.roll_mode(), Some(true));
+        assert!(session.visible_cells(fighter_id).unwrap().is_empty());
+        assert_eq!(session.visible_cells(ghoul_id).unwrap().len(), 50);
+    }
+    #[test]
+    fn test_session_ranged_distance_and_adjacent_hostiles() {
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Field".to_string());
+        session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(Grid::new(20, 10));
+
+        let mut ranger = Actor::new("Ranger".to_string(), ActorType::Player);
+        ranger.inventory.add("Arrow", 1);
+        let mut goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        goblin.set_grid_position(GridPos::new(1, 1), 0);
+        let mut ogre = Actor::new("Ogre".to_string(), ActorType::Monster);
+        ogre.size = crate::actor::CreatureSize::Large;
+        ogre.set_grid_position(GridPos::new(10, 0), 0);
+        let (ranger_id, goblin_id, ogre_id) = (ranger.id, goblin.id, ogre.id);
+        session.add_actor_to_scene(scene_id, ranger).unwrap();
+        session.add_actor_to_scene(scene_id, goblin).unwrap();
+        session.add_actor_to_scene(scene_id, ogre).unwrap();
+
+        assert_eq!(
+            session.distance_between(ranger_id, ogre_id).unwrap(),
+            Some(50)
+        );
+        assert!(session.hostile_within_5ft(ranger_id));
+
+        session.get_actor_mut(goblin_id).unwrap().hp = 0;
+        assert!(!session.hostile_within_5ft(ranger_id));
+
+        assert_eq!(session.spend_ammunition(ranger_id, "Arrow").unwrap(), 0);
+        assert!(session.spend_ammunition(ranger_id, "Arrow").is_err());
+        // One arrow fired: half of it rounds down to nothing
+        assert!(session.recover_ammunition().is_empty());
+    }
+
+    #[test]
+    fn test_use_item_spends_actions_stacks_and_charges() {
+        let wand = Some(Charges::new(7, Recharge::Dawn));
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Crypt".to_string());
+        let mut cleric = Actor::new("Cleric".to_string(), ActorType::Player);
+        cleric.inventory.add("Potion of Healing", 2);
+        cleric.inventory.add("Wand of Web", 1);
+        let cleric_id = cleric.id;
+        let ghoul = Actor::new("Ghoul".to_string(), ActorType::Monster);
+        let ghoul_id = ghoul.id;
+        session.add_actor_to_scene(scene_id, cleric).unwrap();
+        session.add_actor_to_scene(scene_id, ghoul).unwrap();
+
+        // Out of combat nothing limits how often items are used
+        let potion = "potion of healing";
+        assert_eq!(
+            session
+                .use_item(cleric_id, potion, ActionCost::Action, None)
+                .unwrap(),
+            1
+        );
+        // The wand starts with its full charges on first use
+        assert_eq!(
+            session
+                .use_item(cleric_id, "Wand of Web", ActionCost::Action, wand)
+                .unwrap(),
+            6
+        );
+        assert_eq!(
+            session
+                .get_actor(cleric_id)
+                .unwrap()
+                .inventory
+                .count("Wand of Web"),
+            1
+        );
+
+        session
+            .start_combat_with_initiative(vec![(cleric_id, 15), (ghoul_id, 10)], &[])
+            .unwrap();
+        session.next_turn().unwrap();
+        session
+            .use_item(cleric_id, potion, ActionCost::BonusAction, None)
+            .unwrap();
+        assert!(session
+            .use_item(cleric_id, "Wand of Web", ActionCost::BonusAction, wand)
+            .unwrap_err()
+            .to_string()
+            .contains("bonus action"));
+        assert_eq!(
+            session
+                .use_item(cleric_id, "Wand of Web", ActionCost::Action, wand)
+                .unwrap(),
+            5
+        );
+        assert!(session
+            .use_item(cleric_id, potion, ActionCost::Free, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_session_clock_expires_effects_and_recharges_at_dawn() {
+        use crate::clock::{Charges, ClockEvent, HOUR_SECONDS};
+
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Camp".to_string());
+        let mut wizard = Actor::new("Wizard".to_string(), ActorType::Player);
+        wizard.charges.insert(
+            "Wand of Magic Missiles".to_string(),
+            Charges::new(7, Recharge::Dawn),
+        );
+        let wizard_id = wizard.id;
+        let goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        let goblin_id = goblin.id;
+        session.add_actor_to_scene(scene_id, wizard).unwrap();
+        session.add_actor_to_scene(scene_id, goblin).unwrap();
+        session
+            .start_combat_with_initiative(vec![(wizard_id, 15), (goblin_id, 10)], &[])
+            .unwrap();
+
+        // Stunned for one round: it ends when the next round starts
+        let effect = Effect::new(
+            "Stunned".to_string(),
+            EffectType::Condition("stunned".to_string()),
+            goblin_id,
+            Some(1),
+        );
+        let effect_id = effect.id;
+        session.apply_effect(effect);
+        let start = session.clock.now();
+        assert_eq!(session.next_turn().unwrap(), Some(wizard_id));
+        assert_eq!(session.clock.now(), start + ROUND_SECONDS);
+        assert!(session.effects.is_empty());
+        assert_eq!(
+            session.take_clock_events(),
+            vec![ClockEvent::EffectExpired {
+                effect_id,
+                target_id: goblin_id
+            }]
+        );
+
+        let actor = session.get_actor_mut(wizard_id).unwrap();
+        assert_eq!(actor.use_charge("wand of magic missiles"), Some(6));
+        session.advance_time(12 * HOUR_SECONDS);
+        let actor = session.get_actor(wizard_id).unwrap();
+        assert_eq!(actor.charges["Wand of Magic Missiles"].current, 6);
+        assert!(session
+            .advance_time(12 * HOUR_SECONDS)
+            .contains(&ClockEvent::Dawn { day: 1 }));
+        let actor = session.get_actor(wizard_id).unwrap();
+        assert_eq!(actor.charges["Wand of Magic Missiles"].current, 7);
+    }
+
+    #[test]
+    fn test_session_party_moves_between_scenes_and_splits() {
+        let mut session = GameSession::new("Test".to_string());
+        let hall = session.create_scene("Hall".to_string());
+        let crypt = session.create_scene("Crypt".to_string());
+        let mut grid = Grid::new(5, 5);
+        grid.set_terrain(GridPos::new(0, 1), crate::grid::Terrain::Wall)
+            .unwrap();
+        let scene = session.scenes.get_mut(&crypt).unwrap();
+        scene.set_grid(grid);
+        scene.entrance = Some(GridPos::new(0, 0));
+
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        let skeleton = Actor::new("Skeleton".to_string(), ActorType::Monster);
+        let (fighter_id, rogue_id, skeleton_id) = (fighter.id, rogue.id, skeleton.id);
+        session.add_actor_to_scene(hall, fighter).unwrap();
+        session.add_actor_to_scene(hall, rogue).unwrap();
+        session.add_actor_to_scene(crypt, skeleton).unwrap();
+        session.get_actor_mut(rogue_id).unwrap().take_damage(30);
+
+        // The rogue scouts ahead alone, keeping its wounds
+        let transition = session.transition(crypt, &[rogue_id]).unwrap();
+        assert_eq!(transition.from, vec![hall]);
+        assert_eq!(session.current_scene, Some(crypt));
+        assert_eq!(session.get_actor(rogue_id).unwrap().hp, 70);
+        assert_eq!(
+            session.get_actor(rogue_id).unwrap().grid_position(),
+            GridPos::new(1, 0)
+        );
+        assert_eq!(session.party_locations().len(), 2);
+        assert_eq!(session.scene_actors().len(), 2);
+
+        // No leaving mid-fight
+        session.start_combat().unwrap();
+        assert!(session.transition_party(hall).is_err());
+        session.get_current_scene_mut().unwrap().end_combat();
+
+        session.transition_party(hall).unwrap();
+        assert_eq!(session.party_locations()[&hall], vec![fighter_id, rogue_id]);
+        assert_eq!(session.actors_in(crypt).len(), 1);
+        assert_eq!(session.take_scene_transitions().len(), 2);
+        assert!(session.scenes[&crypt].visited_at.is_some());
+        assert_eq!(
+            session.get_actor(skeleton_id).unwrap().scene_id,
+            Some(crypt)
+        );
+    }
+
+    #[test]
+    fn test_session_controlled_creatures_share_initiative_and_are_dismissed() {
+        use crate::actor::ControlKind;
+
+        let mut session = GameSession::new("Test".to_string());
+        let scene_id = session.create_scene("Forest".to_string());
+        let ranger = Actor::new("Ranger".to_string(), ActorType::Player);
+        let wolf = Actor::new("Wolf".to_string(), ActorType::Npc);
+        let druid = Actor::new("Druid".to_string(), ActorType::Player);
+        let bear = Actor::new("Bear".to_string(), ActorType::Npc);
+        let orc = Actor::new("Orc".to_string(), ActorType::Monster);
+        let (ranger_id, wolf_id, druid_id, bear_id, orc_id) =
+            (ranger.id, wolf.id, druid.id, bear.id, orc.id);
+        for actor in [ranger, wolf, druid, bear, orc] {
+            session.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+
+        session
+            .set_control(wolf_id, Control::new(ranger_id, ControlKind::Companion))
+            .unwrap();
+        assert!(session
+            .set_control(ranger_id, Control::new(wolf_id, ControlKind::Charmed))
+            .is_err());
+        assert_eq!(session.party(), vec![druid_id, ranger_id, wolf_id]);
+
+        // Conjure Animals: the bear has its own initiative and lasts while the druid concentrates
+        let conjure = Effect::new(
+            "Conjure Animals".to_string(),
+            EffectType::Condition("conjured".to_string()),
+            bear_id,
+            Some(600),
+        )
+        .with_concentration(druid_id);
+        let conjure_id = conjure.id;
+        session.apply_effect(conjure);
+        session
+            .set_control(
+                bear_id,
+                Control::new(druid_id, ControlKind::Summon).with_effect(conjure_id),
+            )
+            .unwrap();
+
+        session
+            .start_combat_with_initiative(
+                vec![
+                    (ranger_id, 18),
+                    (orc_id, 15),
+                    (bear_id, 12),
+                    (druid_id, 9),
+                    (wolf_id, 3),
+                ],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            session.turn_order.all_actors(),
+            vec![ranger_id, wolf_id, orc_id, bear_id, druid_id]
+        );
+        assert_eq!(session.get_actor(wolf_id).unwrap().initiative, Some(18));
+
+        // Concentrating on a new spell ends Conjure Animals and the bear with it
+        session.apply_effect(
+            Effect::new(
+                "Entangle".to_string(),
+                EffectType::Condition("restrained".to_string()),
+                orc_id,
+                Some(10),
+            )
+            .with_concentration(druid_id),
+        );
+        assert!(session.get_actor(bear_id).is_none());
+        assert!(!session.turn_order.all_actors().contains(&bear_id));
+        let dismissals = session.take_dismissals();
+        assert_eq!(dismissals.len(), 1);
+        assert!(dismissals[0].removed);
+
+        // A companion stays when released, it just stops following the ranger's turn
+        let dismissal = session.dismiss(wolf_id).unwrap();
+        assert!(!dismissal.removed);
+        assert!(session.get_actor(wolf_id).is_some());
+        assert!(session.controlled_by(ranger_id).is_empty());
+    }
+
+    #[test]
+    fn test_drain_buffers_stay_out_of_the_saved_state() {
+        let mut session = GameSession::new("Test".to_string());
+        session.advance_time(crate::clock::DAY_SECONDS);
+        let camp = session.create_scene("Camp".to_string());
+        let road = session.create_scene("Road".to_string());
+        let scout = Actor::new("Scout".to_string(), ActorType::Player);
+        let scout_id = scout.id;
+        session.add_actor_to_scene(camp, scout).unwrap();
+        session.transition(road, &[scout_id]).unwrap();
+        let mut hawk = Actor::new("Hawk".to_string(), ActorType::Monster);
+        hawk.controlled_by = Some(Control::new(scout_id, crate::actor::ControlKind::Companion));
+        let hawk_id = hawk.id;
+        session.add_actor_to_scene(road, hawk).unwrap();
+        session.dismiss(hawk_id).unwrap();
+
+        let saved = serde_json::to_value(&session).unwrap();
+        for buffer in ["clock_events", "transitions", "dismissals"] {
+            assert!(saved.get(buffer).is_none(), "{} was saved", buffer);
+        }
+        assert!(!session.take_clock_events().is_empty());
+        assert_eq!(session.take_scene_transitions().len(), 1);
+        assert_eq!(session.take_dismissals().len(), 1);
+    }
 }
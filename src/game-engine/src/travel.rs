@@ -0,0 +1,518 @@
+use crate::error::{GameError, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use uuid::Uuid;
+
+/// Hours in one watch; navigation, foraging and encounters are checked once per watch
+pub const WATCH_HOURS: u32 = 4;
+/// Hours a party can travel in a day before it becomes a forced march
+pub const TRAVEL_HOURS_PER_DAY: u32 = 8;
+/// A d20 roll of this or higher on the encounter check means a random encounter
+pub const ENCOUNTER_THRESHOLD: u32 = 18;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TravelPace {
+    Fast,
+    #[default]
+    Normal,
+    Slow,
+}
+
+impl TravelPace {
+    pub fn miles_per_hour(&self) -> u32 {
+        match self {
+            TravelPace::Fast => 4,
+            TravelPace::Normal => 3,
+            TravelPace::Slow => 2,
+        }
+    }
+
+    /// Fast travel costs -5 to passive Wisdom (Perception)
+    pub fn passive_perception_modifier(&self) -> i32 {
+        match self {
+            TravelPace::Fast => -5,
+            _ => 0,
+        }
+    }
+
+    /// Only a slow pace lets the party move stealthily
+    pub fn allows_stealth(&self) -> bool {
+        *self == TravelPace::Slow
+    }
+
+    /// The party can forage while traveling at a normal or slow pace
+    pub fn allows_foraging(&self) -> bool {
+        *self != TravelPace::Fast
+    }
+
+    /// Bonus to the navigator's Wisdom (Survival) check
+    pub fn navigation_modifier(&self) -> i32 {
+        match self {
+            TravelPace::Fast => -5,
+            TravelPace::Normal => 0,
+            TravelPace::Slow => 5,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "fast" => Some(TravelPace::Fast),
+            "normal" => Some(TravelPace::Normal),
+            "slow" => Some(TravelPace::Slow),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TravelTerrain {
+    Road,
+    Grassland,
+    Forest,
+    Hills,
+    Mountains,
+    Swamp,
+    Desert,
+    Arctic,
+}
+
+impl TravelTerrain {
+    /// Wisdom (Survival) DC to avoid getting lost; roads can't be lost
+    pub fn navigation_dc(&self) -> Option<i32> {
+        match self {
+            TravelTerrain::Road => None,
+            TravelTerrain::Grassland => Some(5),
+            TravelTerrain::Hills | TravelTerrain::Desert | TravelTerrain::Arctic => Some(10),
+            TravelTerrain::Forest | TravelTerrain::Mountains | TravelTerrain::Swamp => Some(15),
+        }
+    }
+
+    /// Wisdom (Survival) DC to find food and water
+    pub fn forage_dc(&self) -> i32 {
+        match self {
+            TravelTerrain::Road | TravelTerrain::Grassland | TravelTerrain::Forest => 10,
+            TravelTerrain::Hills | TravelTerrain::Mountains | TravelTerrain::Swamp => 15,
+            TravelTerrain::Desert | TravelTerrain::Arctic => 20,
+        }
+    }
+
+    /// Dense forest, swamp, mountains and ice halve travel speed
+    pub fn is_difficult(&self) -> bool {
+        matches!(
+            self,
+            TravelTerrain::Forest
+                | TravelTerrain::Mountains
+                | TravelTerrain::Swamp
+                | TravelTerrain::Arctic
+        )
+    }
+}
+
+/// A place on the overland map
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A two-way path between two locations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    pub from: String,
+    pub to: String,
+    pub miles: u32,
+    pub terrain: TravelTerrain,
+}
+
+/// One step of a journey
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leg {
+    pub to: String,
+    pub miles: u32,
+    pub terrain: TravelTerrain,
+}
+
+/// Point-crawl map: named locations joined by routes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TravelMap {
+    #[serde(default)]
+    pub locations: Vec<Location>,
+    #[serde(default)]
+    pub routes: Vec<Route>,
+}
+
+impl TravelMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_location(&mut self, name: &str, description: &str) {
+        if self.location(name).is_none() {
+            self.locations.push(Location {
+                name: name.to_string(),
+                description: description.to_string(),
+            });
+        }
+    }
+
+    /// Location by name (case-insensitive)
+    pub fn location(&self, name: &str) -> Option<&Location> {
+        self.locations
+            .iter()
+            .find(|l| l.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn connect(&mut self, from: &str, to: &str, miles: u32, terrain: TravelTerrain) {
+        self.routes.push(Route {
+            from: from.to_string(),
+            to: to.to_string(),
+            miles,
+            terrain,
+        });
+    }
+
+    fn neighbours<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Leg> + 'a {
+        self.routes.iter().filter_map(move |r| {
+            let to = if r.from.eq_ignore_ascii_case(name) {
+                &r.to
+            } else if r.to.eq_ignore_ascii_case(name) {
+                &r.from
+            } else {
+                return None;
+            };
+            Some(Leg {
+                to: to.clone(),
+                miles: r.miles,
+                terrain: r.terrain,
+            })
+        })
+    }
+
+    /// Shortest way from `from` to `to` by distance
+    pub fn route(&self, from: &str, to: &str) -> Result<Vec<Leg>> {
+        let start = self
+            .location(from)
+            .ok_or_else(|| GameError::State(format!("Unknown location: {}", from)))?;
+        let goal = self
+            .location(to)
+            .ok_or_else(|| GameError::State(format!("Unknown location: {}", to)))?;
+
+        let mut best: HashMap<String, u32> = HashMap::from([(start.name.clone(), 0)]);
+        let mut came_from: HashMap<String, (String, Leg)> = HashMap::new();
+        let mut open = BinaryHeap::from([Reverse((0, start.name.clone()))]);
+        while let Some(Reverse((miles, name))) = open.pop() {
+            if name == goal.name {
+                let mut legs = Vec::new();
+                let mut at = name;
+                while let Some((previous, leg)) = came_from.get(&at) {
+                    legs.push(leg.clone());
+                    at = previous.clone();
+                }
+                legs.reverse();
+                return Ok(legs);
+            }
+            if best.get(&name).is_some_and(|b| *b < miles) {
+                continue;
+            }
+            for mut leg in self.neighbours(&name) {
+                let Some(next) = self.location(&leg.to) else {
+                    continue;
+                };
+                leg.to = next.name.clone();
+                let total = miles + leg.miles;
+                if best.get(&next.name).map_or(true, |b| total < *b) {
+                    best.insert(next.name.clone(), total);
+                    came_from.insert(next.name.clone(), (name.clone(), leg));
+                    open.push(Reverse((total, next.name.clone())));
+                }
+            }
+        }
+        Err(GameError::State(format!(
+            "No route from {} to {}",
+            start.name, goal.name
+        )))
+    }
+}
+
+/// A trip in progress
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journey {
+    pub destination: String,
+    pub pace: TravelPace,
+    pub legs: Vec<Leg>,
+    /// Index of the leg being traveled
+    pub leg: usize,
+    /// Miles covered on the current leg
+    pub miles_into_leg: u32,
+    /// The party lost its way and makes no progress until it finds it again
+    #[serde(default)]
+    pub lost: bool,
+}
+
+impl Journey {
+    pub fn current_leg(&self) -> Option<&Leg> {
+        self.legs.get(self.leg)
+    }
+
+    pub fn miles_remaining(&self) -> u32 {
+        self.legs[self.leg.min(self.legs.len())..]
+            .iter()
+            .map(|l| l.miles)
+            .sum::<u32>()
+            .saturating_sub(self.miles_into_leg)
+    }
+}
+
+/// Checks rolled for one watch of travel (d20 plus modifiers)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchRolls {
+    /// Navigator's Wisdom (Survival) total, before the pace modifier
+    #[serde(default)]
+    pub navigation: Option<i32>,
+    /// Forager's Wisdom (Survival) total
+    #[serde(default)]
+    pub forage: Option<i32>,
+    /// Plain d20 for the random encounter check
+    pub encounter: u32,
+    /// Constitution saving throw totals, one per hour of the watch, used for the hours
+    /// past the day's travel
+    #[serde(default)]
+    pub forced_march: Vec<(Uuid, Vec<i32>)>,
+}
+
+/// What happened during one watch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchReport {
+    pub miles: u32,
+    /// Location reached during the watch, if any
+    pub reached: Option<String>,
+    pub arrived: bool,
+    pub lost: bool,
+    pub foraged: bool,
+    pub encounter: bool,
+    /// Creatures that gained a level of exhaustion from a forced march, once per failed save
+    pub exhausted: Vec<Uuid>,
+    /// DC of the watch's last forced march save, when it went past the day's travel hours
+    pub forced_march_dc: Option<i32>,
+}
+
+/// Overland travel: the map, where the party is and the trip underway
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TravelState {
+    #[serde(default)]
+    pub map: TravelMap,
+    /// Location the party is at, or last passed
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub journey: Option<Journey>,
+    /// Hours traveled on `travel_day`
+    #[serde(default)]
+    pub hours_today: u32,
+    #[serde(default)]
+    pub travel_day: u64,
+    #[serde(default)]
+    pub last_watch: Option<WatchReport>,
+}
+
+impl TravelState {
+    /// Plan a journey from the party's location to `destination`. Heading for the same
+    /// destination again only changes the pace.
+    pub fn start_journey(&mut self, destination: &str, pace: TravelPace) -> Result<()> {
+        if let Some(journey) = self
+            .journey
+            .as_mut()
+            .filter(|j| j.destination.eq_ignore_ascii_case(destination))
+        {
+            journey.pace = pace;
+            return Ok(());
+        }
+        let from = self
+            .location
+            .clone()
+            .ok_or_else(|| GameError::State("The party is not on the map".to_string()))?;
+        let legs = self.map.route(&from, destination)?;
+        let destination = legs
+            .last()
+            .map(|l| l.to.clone())
+            .ok_or_else(|| GameError::State(format!("Already at {}", destination)))?;
+        self.journey = Some(Journey {
+            destination,
+            pace,
+            legs,
+            leg: 0,
+            miles_into_leg: 0,
+            lost: false,
+        });
+        Ok(())
+    }
+
+    /// Hours already traveled on `day`
+    pub fn hours_traveled_on(&self, day: u64) -> u32 {
+        if self.travel_day == day {
+            self.hours_today
+        } else {
+            0
+        }
+    }
+
+    /// DC of the forced march save for the hour ending `hours` into the travel day
+    pub fn forced_march_dc(hours: u32) -> Option<i32> {
+        hours
+            .checked_sub(TRAVEL_HOURS_PER_DAY)
+            .filter(|extra| *extra > 0)
+            .map(|extra| 10 + extra as i32)
+    }
+
+    /// Travel one watch on `day` using the rolled checks
+    pub fn travel_watch(&mut self, day: u64, rolls: &WatchRolls) -> Result<WatchReport> {
+        if self.travel_day != day {
+            self.travel_day = day;
+            self.hours_today = 0;
+        }
+        let journey = self
+            .journey
+            .as_mut()
+            .ok_or_else(|| GameError::State("No journey underway".to_string()))?;
+        let terrain = journey
+            .current_leg()
+            .map(|l| l.terrain)
+            .unwrap_or(TravelTerrain::Road);
+        let mut report = WatchReport {
+            encounter: rolls.encounter >= ENCOUNTER_THRESHOLD,
+            ..Default::default()
+        };
+
+        // Navigation: a failed check loses the way; a later success finds it again
+        if let Some(dc) = terrain.navigation_dc() {
+            if let Some(total) = rolls.navigation {
+                journey.lost = total + journey.pace.navigation_modifier() < dc;
+            }
+        } else {
+            journey.lost = false;
+        }
+        report.lost = journey.lost;
+
+        if journey.pace.allows_foraging() {
+            report.foraged = rolls
+                .forage
+                .is_some_and(|total| total >= terrain.forage_dc());
+        }
+
+        if !journey.lost {
+            let mut miles = journey.pace.miles_per_hour() * WATCH_HOURS;
+            if terrain.is_difficult() {
+                miles /= 2;
+            }
+            while let Some(leg) = journey.legs.get(journey.leg) {
+                let step = miles.min(leg.miles - journey.miles_into_leg);
+                journey.miles_into_leg += step;
+                report.miles += step;
+                miles -= step;
+                if journey.miles_into_leg < leg.miles {
+                    break;
+                }
+                report.reached = Some(leg.to.clone());
+                journey.leg += 1;
+                journey.miles_into_leg = 0;
+            }
+        }
+
+        // Each hour past the day's travel takes its own save, one DC higher than the last
+        let hours_before = self.hours_today;
+        self.hours_today += WATCH_HOURS;
+        for (index, hours) in (hours_before + 1..=self.hours_today).enumerate() {
+            let Some(dc) = Self::forced_march_dc(hours) else {
+                continue;
+            };
+            report.forced_march_dc = Some(dc);
+            for (id, totals) in &rolls.forced_march {
+                if totals.get(index).is_some_and(|total| *total < dc) {
+                    report.exhausted.push(*id);
+                }
+            }
+        }
+
+        if let Some(reached) = &report.reached {
+            self.location = Some(reached.clone());
+        }
+        if journey.leg >= journey.legs.len() {
+            report.arrived = true;
+            self.journey = None;
+        }
+        self.last_watch = Some(report.clone());
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sword_coast() -> TravelState {
+        let mut map = TravelMap::new();
+        for name in ["Waterdeep", "Daggerford", "Baldur's Gate", "Misty Forest"] {
+            map.add_location(name, "");
+        }
+        map.connect("Waterdeep", "Daggerford", 10, TravelTerrain::Road);
+        map.connect("Daggerford", "Baldur's Gate", 20, TravelTerrain::Grassland);
+        map.connect("Waterdeep", "Misty Forest", 8, TravelTerrain::Forest);
+        map.connect("Misty Forest", "Baldur's Gate", 100, TravelTerrain::Forest);
+        TravelState {
+            map,
+            location: Some("Waterdeep".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_route_takes_shortest_path() {
+        let travel = sword_coast();
+        let legs = travel.map.route("waterdeep", "baldur's gate").unwrap();
+        let stops: Vec<&str> = legs.iter().map(|l| l.to.as_str()).collect();
+        assert_eq!(stops, vec!["Daggerford", "Baldur's Gate"]);
+        assert!(travel.map.route("Waterdeep", "Neverwinter").is_err());
+    }
+
+    #[test]
+    fn test_watches_navigation_and_forced_march() {
+        let mut travel = sword_coast();
+        travel
+            .start_journey("Baldur's Gate", TravelPace::Normal)
+            .unwrap();
+        let hero = Uuid::new_v4();
+        let rolls = |navigation, encounter| WatchRolls {
+            navigation: Some(navigation),
+            forage: Some(12),
+            encounter,
+            forced_march: vec![(hero, vec![11, 14, 12, 13])],
+        };
+
+        // 12 miles a watch: the road to Daggerford, then onto the grassland
+        let report = travel.travel_watch(0, &rolls(1, 3)).unwrap();
+        assert_eq!(report.miles, 12);
+        assert_eq!(report.reached.as_deref(), Some("Daggerford"));
+        assert!(report.foraged && !report.encounter && !report.lost);
+        assert_eq!(travel.location.as_deref(), Some("Daggerford"));
+
+        // Lost on the grassland: no progress until the navigator finds the way
+        let report = travel.travel_watch(0, &rolls(4, 19)).unwrap();
+        assert!(report.lost && report.encounter);
+        assert_eq!(report.miles, 0);
+
+        // Third watch of the day is a forced march: saves against DC 11 to 14, hour by hour
+        let report = travel.travel_watch(0, &rolls(15, 1)).unwrap();
+        assert_eq!(report.forced_march_dc, Some(14));
+        assert_eq!(report.exhausted, vec![hero, hero]);
+        assert_eq!(travel.journey.as_ref().unwrap().miles_remaining(), 6);
+
+        let report = travel.travel_watch(1, &rolls(15, 1)).unwrap();
+        assert!(report.arrived && report.exhausted.is_empty());
+        assert_eq!(travel.location.as_deref(), Some("Baldur's Gate"));
+        assert!(travel.journey.is_none());
+    }
+}
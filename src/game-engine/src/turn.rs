@@ -1,13 +1,76 @@
 use crate::error::{GameError, Result};
+use crate::legendary::LAIR_INITIATIVE;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use uuid::Uuid;
 
+/// Why a creature's turn was passed over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkipReason {
+    Surprised,
+    Dead,
+    Incapacitated,
+}
+
+/// Turn lifecycle events, drained by the caller with `take_events`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnEvent {
+    TurnStarted {
+        actor_id: Uuid,
+        round: u32,
+    },
+    TurnEnded {
+        actor_id: Uuid,
+        round: u32,
+    },
+    TurnSkipped {
+        actor_id: Uuid,
+        round: u32,
+        reason: SkipReason,
+    },
+    TurnDelayed {
+        actor_id: Uuid,
+        round: u32,
+    },
+    /// A legendary creature may spend a legendary action now
+    LegendaryWindow {
+        actor_id: Uuid,
+        after_actor_id: Uuid,
+        round: u32,
+    },
+    /// Initiative count 20: the lair owner may take a lair action
+    LairAction {
+        actor_id: Uuid,
+        round: u32,
+    },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TurnOrder {
     actors: VecDeque<Uuid>,
     current_index: usize,
     round: u32,
+    /// Creatures that lose their first turn to surprise
+    #[serde(default)]
+    surprised: HashSet<Uuid>,
+    /// Initiative score of each creature in the order
+    #[serde(default)]
+    initiatives: HashMap<Uuid, i32>,
+    /// Creatures holding their turn, out of the rotation until they resume
+    #[serde(default)]
+    delayed: Vec<Uuid>,
+    /// The current creature left the order mid-turn; `current_index` already
+    /// points at the creature that goes next
+    #[serde(default)]
+    turn_vacated: bool,
+    /// Creature whose lair acts on initiative count 20
+    #[serde(default)]
+    lair_owner: Option<Uuid>,
+    /// Last round the lair acted in (0 if never)
+    #[serde(default)]
+    lair_round: u32,
+    #[serde(skip)]
+    events: Vec<TurnEvent>,
 }
 
 impl Default for TurnOrder {
@@ -22,6 +85,13 @@ impl TurnOrder {
             actors: VecDeque::new(),
             current_index: 0,
             round: 1,
+            surprised: HashSet::new(),
+            initiatives: HashMap::new(),
+            delayed: Vec::new(),
+            turn_vacated: false,
+            lair_owner: None,
+            lair_round: 0,
+            events: Vec::new(),
         }
     }
 
@@ -31,10 +101,67 @@ impl TurnOrder {
         }
     }
 
+    /// Insert a creature joining mid-combat at its initiative position.
+    /// Newcomers lose ties; if its slot already passed this round it first acts next round.
+    pub fn insert_actor(&mut self, actor_id: Uuid, initiative: i32) {
+        if self.actors.contains(&actor_id) {
+            return;
+        }
+        let position = self
+            .actors
+            .iter()
+            .position(|id| self.initiative_of(*id).unwrap_or(i32::MIN) < initiative)
+            .unwrap_or(self.actors.len());
+        self.insert_at(position, actor_id);
+        self.initiatives.insert(actor_id, initiative);
+    }
+
+    /// Insert a creature right after `after_id`, sharing its initiative (e.g. a summon
+    /// that acts immediately after its caster)
+    pub fn insert_after(&mut self, actor_id: Uuid, after_id: Uuid) -> Result<()> {
+        if self.actors.contains(&actor_id) {
+            return Ok(());
+        }
+        let index = self
+            .actors
+            .iter()
+            .position(|&id| id == after_id)
+            .ok_or_else(|| GameError::State(format!("Actor not in combat: {}", after_id)))?;
+        self.insert_at(index + 1, actor_id);
+        if let Some(initiative) = self.initiative_of(after_id) {
+            self.initiatives.insert(actor_id, initiative);
+        }
+        Ok(())
+    }
+
+    /// Remove a creature from combat. If it is the current creature its turn ends
+    /// and the next call to `next_turn` starts the creature that followed it.
     pub fn remove_actor(&mut self, actor_id: Uuid) {
-        self.actors.retain(|&id| id != actor_id);
-        if self.current_index >= self.actors.len() && !self.actors.is_empty() {
+        self.delayed.retain(|&id| id != actor_id);
+        self.surprised.remove(&actor_id);
+        self.initiatives.remove(&actor_id);
+        if self.lair_owner == Some(actor_id) {
+            self.lair_owner = None;
+        }
+
+        let Some(index) = self.actors.iter().position(|&id| id == actor_id) else {
+            return;
+        };
+        self.actors.remove(index);
+
+        if index < self.current_index {
+            self.current_index -= 1;
+        } else if index == self.current_index && !self.turn_vacated {
+            self.events.push(TurnEvent::TurnEnded {
+                actor_id,
+                round: self.round,
+            });
+            self.turn_vacated = true;
+        }
+
+        if self.actors.is_empty() {
             self.current_index = 0;
+            self.turn_vacated = false;
         }
     }
 
@@ -47,24 +174,282 @@ impl TurnOrder {
         self.actors = VecDeque::from(actor_ids);
         self.current_index = 0;
         self.round = 1;
+        self.surprised.clear();
+        self.initiatives.clear();
+        self.delayed.clear();
+        self.turn_vacated = false;
+        self.lair_owner = None;
+        self.lair_round = 0;
+        self.events.clear();
         Ok(())
     }
 
+    /// Record initiative scores (used to place creatures that join later)
+    pub fn set_initiative_scores(&mut self, scores: impl IntoIterator<Item = (Uuid, i32)>) {
+        self.initiatives.extend(scores);
+    }
+
+    pub fn initiative_of(&self, actor_id: Uuid) -> Option<i32> {
+        self.initiatives.get(&actor_id).copied()
+    }
+
+    /// Give `owner_id`'s lair a turn on initiative count 20 (losing ties)
+    pub fn set_lair(&mut self, owner_id: Option<Uuid>) {
+        self.lair_owner = owner_id;
+    }
+
+    pub fn lair_owner(&self) -> Option<Uuid> {
+        self.lair_owner
+    }
+
+    /// Mark creatures as surprised: they skip their turn in round 1
+    pub fn set_surprised(&mut self, actor_ids: impl IntoIterator<Item = Uuid>) {
+        if self.round > 1 {
+            return;
+        }
+        self.surprised = actor_ids
+            .into_iter()
+            .filter(|id| self.actors.contains(id))
+            .collect();
+    }
+
+    pub fn is_surprised(&self, actor_id: Uuid) -> bool {
+        self.surprised.contains(&actor_id)
+    }
+
+    /// Start the first turn of combat, passing over creatures that can't act
+    pub fn start(&mut self, skip: impl Fn(Uuid) -> Option<SkipReason>) -> Option<Uuid> {
+        if self.actors.is_empty() {
+            return None;
+        }
+        self.turn_vacated = true;
+        self.next_turn_skipping(skip)
+    }
+
     pub fn current_actor(&self) -> Option<Uuid> {
         self.actors.get(self.current_index).copied()
     }
 
     pub fn next_turn(&mut self) -> Option<Uuid> {
+        self.next_turn_skipping(|_| None)
+    }
+
+    /// End the current turn and start the next one, passing over creatures for which
+    /// `skip` returns a reason (dead, incapacitated) and surprised creatures in round 1
+    pub fn next_turn_skipping(
+        &mut self,
+        skip: impl Fn(Uuid) -> Option<SkipReason>,
+    ) -> Option<Uuid> {
+        self.next_turn_with(skip, |_| false)
+    }
+
+    /// Like `next_turn_skipping`; when the current turn ends, every other creature for
+    /// which `legendary` returns true gets a legendary action window
+    pub fn next_turn_with(
+        &mut self,
+        skip: impl Fn(Uuid) -> Option<SkipReason>,
+        legendary: impl Fn(Uuid) -> bool,
+    ) -> Option<Uuid> {
         if self.actors.is_empty() {
             return None;
         }
 
+        // A lair whose owner can't act (dead, incapacitated) takes no lair actions
+        let lair_ready = self
+            .lair_owner
+            .is_some_and(|owner| self.actors.contains(&owner) && skip(owner).is_none());
+
+        if self.turn_vacated {
+            self.turn_vacated = false;
+            if self.current_index >= self.actors.len() {
+                self.current_index = 0;
+                self.end_round(lair_ready);
+            }
+        } else {
+            if let Some(actor_id) = self.current_actor() {
+                let round = self.round;
+                self.events.push(TurnEvent::TurnEnded { actor_id, round });
+                for &other in &self.actors {
+                    if other != actor_id && legendary(other) {
+                        self.events.push(TurnEvent::LegendaryWindow {
+                            actor_id: other,
+                            after_actor_id: actor_id,
+                            round,
+                        });
+                    }
+                }
+            }
+            self.advance(lair_ready);
+        }
+
+        for _ in 0..self.actors.len() {
+            let actor_id = self.current_actor()?;
+            if lair_ready
+                && self.lair_round < self.round
+                && self.initiative_of(actor_id).unwrap_or(i32::MIN) < LAIR_INITIATIVE
+            {
+                self.lair_action();
+            }
+
+            let reason = if self.surprised.remove(&actor_id) {
+                Some(SkipReason::Surprised)
+            } else {
+                skip(actor_id)
+            };
+
+            match reason {
+                Some(reason) => {
+                    self.events.push(TurnEvent::TurnSkipped {
+                        actor_id,
+                        round: self.round,
+                        reason,
+                    });
+                    self.advance(lair_ready);
+                }
+                None => {
+                    self.events.push(TurnEvent::TurnStarted {
+                        actor_id,
+                        round: self.round,
+                    });
+                    return Some(actor_id);
+                }
+            }
+        }
+
+        // Nobody in the order can act
+        None
+    }
+
+    fn advance(&mut self, lair_ready: bool) {
         self.current_index = (self.current_index + 1) % self.actors.len();
         if self.current_index == 0 {
-            self.round += 1;
+            self.end_round(lair_ready);
+        }
+    }
+
+    fn end_round(&mut self, lair_ready: bool) {
+        // Everyone rolled 20 or higher: the lair acts last
+        if lair_ready && self.lair_round < self.round {
+            self.lair_action();
+        }
+        self.round += 1;
+    }
+
+    fn lair_action(&mut self) {
+        if let Some(actor_id) = self.lair_owner {
+            self.lair_round = self.round;
+            self.events.push(TurnEvent::LairAction {
+                actor_id,
+                round: self.round,
+            });
+        }
+    }
+
+    fn insert_at(&mut self, position: usize, actor_id: Uuid) {
+        let position = position.min(self.actors.len());
+        self.actors.insert(position, actor_id);
+        if self.actors.len() > 1
+            && (position < self.current_index
+                || (position == self.current_index && !self.turn_vacated))
+        {
+            self.current_index += 1;
+        }
+    }
+
+    /// The current creature holds its turn and leaves the rotation until it resumes
+    pub fn delay_turn(&mut self, actor_id: Uuid) -> Result<()> {
+        if self.turn_vacated || self.current_actor() != Some(actor_id) {
+            return Err(GameError::State(format!(
+                "Only the current actor can delay its turn: {}",
+                actor_id
+            )));
+        }
+
+        let index = self.current_index;
+        self.actors.remove(index);
+        self.initiatives.remove(&actor_id);
+        self.delayed.push(actor_id);
+        self.turn_vacated = true;
+        self.events.push(TurnEvent::TurnDelayed {
+            actor_id,
+            round: self.round,
+        });
+
+        if self.actors.is_empty() {
+            self.current_index = 0;
+        }
+        Ok(())
+    }
+
+    /// A delayed creature re-enters the order and acts right after the current turn.
+    /// Its initiative becomes that of the creature it follows.
+    pub fn resume_delayed(&mut self, actor_id: Uuid) -> Result<()> {
+        let index = self
+            .delayed
+            .iter()
+            .position(|&id| id == actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor is not delaying: {}", actor_id)))?;
+        self.delayed.remove(index);
+
+        let position = if self.turn_vacated || self.actors.is_empty() {
+            self.current_index
+        } else {
+            self.current_index + 1
+        };
+        let initiative = position
+            .checked_sub(1)
+            .and_then(|i| self.actors.get(i))
+            .or_else(|| self.actors.get(position))
+            .and_then(|id| self.initiative_of(*id));
+
+        let was_empty = self.actors.is_empty();
+        self.insert_at(position, actor_id);
+        if let Some(initiative) = initiative {
+            self.initiatives.insert(actor_id, initiative);
+        }
+        if was_empty {
+            self.turn_vacated = true;
         }
+        Ok(())
+    }
 
-        self.current_actor()
+    pub fn delayed_actors(&self) -> &[Uuid] {
+        &self.delayed
+    }
+
+    /// Move a creature to another position in the order (DM override).
+    /// The current creature keeps its turn; it can't be moved mid-turn (delay instead).
+    pub fn move_actor(&mut self, actor_id: Uuid, new_index: usize) -> Result<()> {
+        let index = self
+            .actors
+            .iter()
+            .position(|&id| id == actor_id)
+            .ok_or_else(|| GameError::State(format!("Actor not in turn order: {}", actor_id)))?;
+        if !self.turn_vacated && index == self.current_index {
+            return Err(GameError::State(format!(
+                "Cannot move the current actor mid-turn: {}",
+                actor_id
+            )));
+        }
+
+        let anchor = self.current_actor();
+        self.actors.remove(index);
+        let new_index = new_index.min(self.actors.len());
+        self.actors.insert(new_index, actor_id);
+        self.current_index = anchor
+            .and_then(|id| self.actors.iter().position(|&a| a == id))
+            .unwrap_or(self.actors.len());
+        Ok(())
+    }
+
+    /// Drain the turn events emitted since the last call
+    pub fn take_events(&mut self) -> Vec<TurnEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Position of the current turn in the order
+    pub fn current_index(&self) -> usize {
+        self.current_index
     }
 
     pub fn round(&self) -> u32 {
@@ -84,6 +469,10 @@ impl TurnOrder {
 mod tests {
     use super::*;
 
+    fn ids(count: usize) -> Vec<Uuid> {
+        (0..count).map(|_| Uuid::new_v4()).collect()
+    }
+
     #[test]
     fn test_turn_order_creation() {
         let order = TurnOrder::new();
@@ -116,4 +505,204 @@ mod tests {
         assert_eq!(order.next_turn(), Some(id1));
         assert_eq!(order.round(), 2);
     }
+
+    #[test]
+    fn test_turn_order_surprised_skip_round_one() {
+        let mut order = TurnOrder::new();
+        let id1 = Uuid::new_v4();
+        let id2 = Uuid::new_v4();
+        let id3 = Uuid::new_v4();
+
+        order.set_initiative_order(vec![id1, id2, id3]).unwrap();
+        order.set_surprised(vec![id1, id3]);
+
+        // id1 loses its first turn, so round 1 starts with id2
+        assert_eq!(order.start(|_| None), Some(id2));
+        assert!(!order.is_surprised(id1));
+        assert!(order.is_surprised(id3));
+
+        // id3 is skipped too; round 2 everyone acts
+        assert_eq!(order.next_turn(), Some(id1));
+        assert_eq!(order.round(), 2);
+        assert_eq!(order.next_turn(), Some(id2));
+        assert_eq!(order.next_turn(), Some(id3));
+    }
+
+    #[test]
+    fn test_turn_order_insert_by_initiative() {
+        let mut order = TurnOrder::new();
+        let [a, b, c, late, early] = ids(5)[..] else {
+            unreachable!()
+        };
+        order.set_initiative_order(vec![a, b, c]).unwrap();
+        order.set_initiative_scores(vec![(a, 20), (b, 12), (c, 5)]);
+
+        assert_eq!(order.next_turn(), Some(b));
+
+        // Joins after b: acts this round once b is done
+        order.insert_actor(late, 8);
+        // Joins before b: its slot has passed, first acts next round
+        order.insert_actor(early, 15);
+        assert_eq!(order.all_actors(), vec![a, early, b, late, c]);
+        assert_eq!(order.current_actor(), Some(b));
+
+        assert_eq!(order.next_turn(), Some(late));
+        assert_eq!(order.next_turn(), Some(c));
+        assert_eq!(order.next_turn(), Some(a));
+        assert_eq!(order.next_turn(), Some(early));
+        assert_eq!(order.round(), 2);
+    }
+
+    #[test]
+    fn test_turn_order_remove_current_keeps_next_and_round() {
+        let mut order = TurnOrder::new();
+        let [a, b, c] = ids(3)[..] else {
+            unreachable!()
+        };
+        order.set_initiative_order(vec![a, b, c]).unwrap();
+
+        // b dies on its own turn: c is next, still round 1
+        order.next_turn();
+        order.remove_actor(b);
+        assert_eq!(order.next_turn(), Some(c));
+        assert_eq!(order.round(), 1);
+
+        // c (last in the round) is removed mid-turn: a starts round 2
+        order.remove_actor(c);
+        assert_eq!(order.next_turn(), Some(a));
+        assert_eq!(order.round(), 2);
+
+        // Removing someone earlier in the order keeps the current actor
+        let d = Uuid::new_v4();
+        order.add_actor(d);
+        order.next_turn();
+        order.remove_actor(a);
+        assert_eq!(order.current_actor(), Some(d));
+        assert_eq!(order.next_turn(), Some(d));
+        assert_eq!(order.round(), 3);
+    }
+
+    #[test]
+    fn test_turn_order_delay_and_resume() {
+        let mut order = TurnOrder::new();
+        let [a, b, c] = ids(3)[..] else {
+            unreachable!()
+        };
+        order.set_initiative_order(vec![a, b, c]).unwrap();
+        order.set_initiative_scores(vec![(a, 18), (b, 10), (c, 4)]);
+
+        assert!(order.delay_turn(b).is_err());
+        order.delay_turn(a).unwrap();
+        assert_eq!(order.delayed_actors(), &[a]);
+        assert_eq!(order.next_turn(), Some(b));
+
+        // a steps in right after b
+        order.resume_delayed(a).unwrap();
+        assert_eq!(order.next_turn(), Some(a));
+        assert_eq!(order.initiative_of(a), Some(10));
+        assert_eq!(order.next_turn(), Some(c));
+        assert_eq!(order.next_turn(), Some(b));
+        assert_eq!(order.round(), 2);
+    }
+
+    #[test]
+    fn test_turn_order_move_and_skip() {
+        let mut order = TurnOrder::new();
+        let [a, b, c] = ids(3)[..] else {
+            unreachable!()
+        };
+        order.set_initiative_order(vec![a, b, c]).unwrap();
+
+        assert!(order.move_actor(a, 2).is_err());
+        order.move_actor(c, 1).unwrap();
+        assert_eq!(order.all_actors(), vec![a, c, b]);
+        assert_eq!(order.current_actor(), Some(a));
+
+        // c is dead: its turn is skipped
+        order.take_events();
+        assert_eq!(
+            order.next_turn_skipping(|id| (id == c).then_some(SkipReason::Dead)),
+            Some(b)
+        );
+        assert_eq!(
+            order.take_events(),
+            vec![
+                TurnEvent::TurnEnded {
+                    actor_id: a,
+                    round: 1
+                },
+                TurnEvent::TurnSkipped {
+                    actor_id: c,
+                    round: 1,
+                    reason: SkipReason::Dead
+                },
+                TurnEvent::TurnStarted {
+                    actor_id: b,
+                    round: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_turn_order_lair_action_and_legendary_windows() {
+        let mut order = TurnOrder::new();
+        let [rogue, dragon, fighter] = ids(3)[..] else {
+            unreachable!()
+        };
+        order
+            .set_initiative_order(vec![rogue, dragon, fighter])
+            .unwrap();
+        order.set_initiative_scores(vec![(rogue, 20), (dragon, 15), (fighter, 8)]);
+        order.set_lair(Some(dragon));
+        order.take_events();
+
+        // The rogue wins the tie with the lair; the dragon gets a window when its turn ends
+        let is_dragon = |id| id == dragon;
+        assert_eq!(order.next_turn_with(|_| None, is_dragon), Some(dragon));
+        assert_eq!(
+            order.take_events(),
+            vec![
+                TurnEvent::TurnEnded {
+                    actor_id: rogue,
+                    round: 1
+                },
+                TurnEvent::LegendaryWindow {
+                    actor_id: dragon,
+                    after_actor_id: rogue,
+                    round: 1
+                },
+                TurnEvent::LairAction {
+                    actor_id: dragon,
+                    round: 1
+                },
+                TurnEvent::TurnStarted {
+                    actor_id: dragon,
+                    round: 1
+                },
+            ]
+        );
+
+        // No window after its own turn, and one lair action per round
+        order.next_turn_with(|_| None, is_dragon);
+        order.next_turn_with(|_| None, is_dragon);
+        let events = order.take_events();
+        assert!(!events.iter().any(|e| matches!(
+            e,
+            TurnEvent::LegendaryWindow {
+                after_actor_id, ..
+            } if *after_actor_id == dragon
+        )));
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, TurnEvent::LairAction { .. })));
+        assert_eq!(order.current_actor(), Some(rogue));
+        assert_eq!(order.round(), 2);
+
+        order.next_turn_with(|_| None, is_dragon);
+        assert!(order.take_events().contains(&TurnEvent::LairAction {
+            actor_id: dragon,
+            round: 2
+        }));
+    }
 }
@@ -0,0 +1,420 @@
+use crate::grid::{Grid, GridPos};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Cover between an attacker and its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Cover {
+    None,
+    Half,
+    ThreeQuarters,
+    /// Can't be targeted directly
+    Total,
+}
+
+impl Cover {
+    /// Bonus to AC and DEX saves, or `None` for total cover
+    pub fn ac_bonus(&self) -> Option<i32> {
+        match self {
+            Cover::None => Some(0),
+            Cover::Half => Some(2),
+            Cover::ThreeQuarters => Some(5),
+            Cover::Total => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LightLevel {
+    Darkness,
+    Dim,
+    #[default]
+    Bright,
+}
+
+/// A torch, lantern, Light cantrip, ...
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightSource {
+    pub position: GridPos,
+    /// Radius of bright light in feet
+    pub bright_ft: i32,
+    /// Dim light extending beyond the bright radius, in feet
+    pub dim_ft: i32,
+}
+
+/// Special senses, by range in feet (0 if the creature lacks the sense)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Senses {
+    #[serde(default)]
+    pub darkvision_ft: i32,
+    #[serde(default)]
+    pub blindsight_ft: i32,
+    #[serde(default)]
+    pub truesight_ft: i32,
+}
+
+/// How well an observer perceives a square
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sight {
+    Clear,
+    /// Dim light: disadvantage on Perception checks relying on sight
+    LightlyObscured,
+    /// Darkness: effectively blinded
+    HeavilyObscured,
+    /// No line of sight
+    Blocked,
+}
+
+impl Sight {
+    pub fn can_see(&self) -> bool {
+        matches!(self, Sight::Clear | Sight::LightlyObscured)
+    }
+}
+
+/// Visibility facts that change an attack roll
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackVisibility {
+    pub cover: Cover,
+    pub attacker_sees_target: bool,
+    pub target_sees_attacker: bool,
+}
+
+impl AttackVisibility {
+    /// Unseen attackers have advantage; attacking what you can't see has disadvantage.
+    /// `Some(true)` for advantage, `Some(false)` for disadvantage.
+    pub fn roll_mode(&self) -> Option<bool> {
+        match (self.attacker_sees_target, self.target_sees_attacker) {
+            (false, true) => Some(false),
+            (true, false) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Whether the segment a-b passes through the interior of square `square`
+/// (grazing an edge or corner doesn't count)
+fn crosses_square(a: (f64, f64), b: (f64, f64), square: GridPos) -> bool {
+    let (min_x, min_y) = (square.x as f64 + EPSILON, square.y as f64 + EPSILON);
+    let (max_x, max_y) = (
+        square.x as f64 + 1.0 - EPSILON,
+        square.y as f64 + 1.0 - EPSILON,
+    );
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+
+    // Liang-Barsky clipping against the (slightly shrunk) square
+    for (p, q) in [
+        (-dx, a.0 - min_x),
+        (dx, max_x - a.0),
+        (-dy, a.1 - min_y),
+        (dy, max_y - a.1),
+    ] {
+        if p.abs() < EPSILON {
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    t1 - t0 > EPSILON
+}
+
+/// Whether any square matching `blocks` (other than those in `ignore`) lies across a-b.
+/// A line running along the seam between two blocking squares is blocked too.
+fn segment_blocked(
+    a: (f64, f64),
+    b: (f64, f64),
+    ignore: &[GridPos],
+    blocks: impl Fn(GridPos) -> bool,
+) -> bool {
+    let blocking = |square: GridPos| !ignore.contains(&square) && blocks(square);
+    let (x0, x1) = (a.0.min(b.0).floor() as i32, a.0.max(b.0).ceil() as i32);
+    let (y0, y1) = (a.1.min(b.1).floor() as i32, a.1.max(b.1).ceil() as i32);
+    let crossed = (y0..y1).any(|y| {
+        (x0..x1).any(|x| {
+            let square = GridPos::new(x, y);
+            blocking(square) && crosses_square(a, b, square)
+        })
+    });
+    if crossed {
+        return true;
+    }
+
+    // Horizontal or vertical line on a grid line: check the squares on both sides
+    let on_line = |v: f64| (v - v.round()).abs() < EPSILON;
+    if (a.1 - b.1).abs() < EPSILON && on_line(a.1) {
+        let y = a.1.round() as i32;
+        return (x0..x1).any(|x| blocking(GridPos::new(x, y - 1)) && blocking(GridPos::new(x, y)));
+    }
+    if (a.0 - b.0).abs() < EPSILON && on_line(a.0) {
+        let x = a.0.round() as i32;
+        return (y0..y1).any(|y| blocking(GridPos::new(x - 1, y)) && blocking(GridPos::new(x, y)));
+    }
+    false
+}
+
+fn center(pos: GridPos) -> (f64, f64) {
+    (pos.x as f64 + 0.5, pos.y as f64 + 0.5)
+}
+
+fn corners(pos: GridPos) -> [(f64, f64); 4] {
+    let (x, y) = (pos.x as f64, pos.y as f64);
+    [(x, y), (x + 1.0, y), (x, y + 1.0), (x + 1.0, y + 1.0)]
+}
+
+fn blocks_sight(grid: &Grid, pos: GridPos) -> bool {
+    grid.cell(pos).is_some_and(|c| c.blocks_sight())
+}
+
+/// Center-to-center line of sight, blocked by walls and closed doors
+pub fn line_of_sight(grid: &Grid, from: GridPos, to: GridPos) -> bool {
+    !segment_blocked(center(from), center(to), &[from, to], |pos| {
+        blocks_sight(grid, pos)
+    })
+}
+
+/// Cover of `target` against `attacker`: from the attacker corner with the best view,
+/// count the lines to the target's corners that are blocked. Creatures in between
+/// (`creatures`) give at most half cover. Total cover needs every line blocked, the
+/// center-to-center one included; otherwise even four blocked lines (an arrow slit)
+/// leave three-quarters cover.
+pub fn cover(
+    grid: &Grid,
+    attacker: GridPos,
+    target: GridPos,
+    creatures: &HashSet<GridPos>,
+) -> Cover {
+    let ignore = [attacker, target];
+    let views: Vec<(usize, bool)> = corners(attacker)
+        .iter()
+        .map(|&from| {
+            let mut walls = 0;
+            let mut creature = false;
+            for to in corners(target) {
+                if segment_blocked(from, to, &ignore, |pos| blocks_sight(grid, pos)) {
+                    walls += 1;
+                } else if segment_blocked(from, to, &ignore, |pos| creatures.contains(&pos)) {
+                    creature = true;
+                }
+            }
+            (walls, creature)
+        })
+        .collect();
+    if views.iter().all(|(walls, _)| *walls == 4) && !line_of_sight(grid, attacker, target) {
+        return Cover::Total;
+    }
+
+    views
+        .into_iter()
+        .map(|(walls, creature)| {
+            let cover = match walls {
+                0 => Cover::None,
+                1 | 2 => Cover::Half,
+                _ => Cover::ThreeQuarters,
+            };
+            if creature {
+                cover.max(Cover::Half)
+            } else {
+                cover
+            }
+        })
+        .min()
+        .unwrap_or(Cover::None)
+}
+
+/// Light in a square from the ambient level and every source that can shine on it
+pub fn light_at(
+    grid: &Grid,
+    pos: GridPos,
+    ambient: LightLevel,
+    lights: &[LightSource],
+) -> LightLevel {
+    lights
+        .iter()
+        .filter(|light| line_of_sight(grid, light.position, pos))
+        .map(|light| {
+            let distance = light.position.distance_ft(pos);
+            if distance <= light.bright_ft {
+                LightLevel::Bright
+            } else if distance <= light.bright_ft + light.dim_ft {
+                LightLevel::Dim
+            } else {
+                LightLevel::Darkness
+            }
+        })
+        .fold(ambient, LightLevel::max)
+}
+
+/// How well a creature with `senses` at `observer` perceives `target` lit at `light`
+pub fn sight(
+    grid: &Grid,
+    observer: GridPos,
+    senses: &Senses,
+    target: GridPos,
+    light: LightLevel,
+) -> Sight {
+    if !line_of_sight(grid, observer, target) {
+        return Sight::Blocked;
+    }
+    let distance = observer.distance_ft(target);
+    let within = |range_ft: i32| range_ft > 0 && distance <= range_ft;
+    if within(senses.blindsight_ft) || within(senses.truesight_ft) {
+        return Sight::Clear;
+    }
+    // Darkvision: dim light counts as bright, darkness as dim
+    let light = if within(senses.darkvision_ft) {
+        match light {
+            LightLevel::Darkness => LightLevel::Dim,
+            _ => LightLevel::Bright,
+        }
+    } else {
+        light
+    };
+    match light {
+        LightLevel::Bright => Sight::Clear,
+        LightLevel::Dim => Sight::LightlyObscured,
+        LightLevel::Darkness => Sight::HeavilyObscured,
+    }
+}
+
+/// Squares a creature can see, for fog of war
+pub fn visible_cells(
+    grid: &Grid,
+    observer: GridPos,
+    senses: &Senses,
+    ambient: LightLevel,
+    lights: &[LightSource],
+) -> HashSet<GridPos> {
+    (0..grid.height())
+        .flat_map(|y| (0..grid.width()).map(move |x| GridPos::new(x, y)))
+        .filter(|&pos| {
+            let light = light_at(grid, pos, ambient, lights);
+            sight(grid, observer, senses, pos, light).can_see()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{DoorState, Terrain};
+
+    #[test]
+    fn test_line_of_sight_walls_and_doors() {
+        let mut grid = Grid::new(5, 5);
+        grid.set_terrain(GridPos::new(2, 2), Terrain::Wall).unwrap();
+        grid.set_door(GridPos::new(2, 0), Some(DoorState::Closed))
+            .unwrap();
+
+        assert!(!line_of_sight(
+            &grid,
+            GridPos::new(0, 2),
+            GridPos::new(4, 2)
+        ));
+        assert!(!line_of_sight(
+            &grid,
+            GridPos::new(0, 0),
+            GridPos::new(4, 0)
+        ));
+        assert!(line_of_sight(&grid, GridPos::new(0, 4), GridPos::new(4, 4)));
+        assert!(!line_of_sight(
+            &grid,
+            GridPos::new(1, 1),
+            GridPos::new(3, 3)
+        ));
+        // Grazing the wall's corner doesn't block
+        assert!(line_of_sight(&grid, GridPos::new(0, 3), GridPos::new(3, 0)));
+    }
+
+    #[test]
+    fn test_cover_from_walls_and_creatures() {
+        let mut grid = Grid::new(6, 6);
+        let archer = GridPos::new(0, 2);
+        let target = GridPos::new(4, 2);
+        assert_eq!(cover(&grid, archer, target, &HashSet::new()), Cover::None);
+
+        let ally = HashSet::from([GridPos::new(2, 2)]);
+        assert_eq!(cover(&grid, archer, target, &ally), Cover::Half);
+
+        // A pillar right in front of the target: grazing lines still get through
+        grid.set_terrain(GridPos::new(3, 2), Terrain::Wall).unwrap();
+        assert_eq!(cover(&grid, archer, target, &HashSet::new()), Cover::Half);
+
+        for y in 0..6 {
+            grid.set_terrain(GridPos::new(3, y), Terrain::Wall).unwrap();
+        }
+        assert_eq!(cover(&grid, archer, target, &HashSet::new()), Cover::Total);
+        assert_eq!(Cover::ThreeQuarters.ac_bonus(), Some(5));
+
+        // Two walls meeting at a corner make an arrow slit: every corner line is
+        // blocked, but the shot through the middle still gets in
+        let mut grid = Grid::new(12, 12);
+        grid.set_terrain(GridPos::new(8, 7), Terrain::Wall).unwrap();
+        grid.set_terrain(GridPos::new(9, 8), Terrain::Wall).unwrap();
+        let (archer, target) = (GridPos::new(11, 0), GridPos::new(8, 9));
+        assert!(line_of_sight(&grid, archer, target));
+        assert_eq!(
+            cover(&grid, archer, target, &HashSet::new()),
+            Cover::ThreeQuarters
+        );
+    }
+
+    #[test]
+    fn test_light_and_darkvision() {
+        let grid = Grid::new(20, 1);
+        let torch = LightSource {
+            position: GridPos::new(0, 0),
+            bright_ft: 20,
+            dim_ft: 20,
+        };
+        let lights = [torch];
+        let light = |x| light_at(&grid, GridPos::new(x, 0), LightLevel::Darkness, &lights);
+        assert_eq!(light(4), LightLevel::Bright);
+        assert_eq!(light(8), LightLevel::Dim);
+        assert_eq!(light(12), LightLevel::Darkness);
+
+        let human = Senses::default();
+        let elf = Senses {
+            darkvision_ft: 60,
+            ..Senses::default()
+        };
+        let observer = GridPos::new(0, 0);
+        let target = GridPos::new(12, 0);
+        assert_eq!(
+            sight(&grid, observer, &human, target, light(12)),
+            Sight::HeavilyObscured
+        );
+        assert_eq!(
+            sight(&grid, observer, &elf, target, light(12)),
+            Sight::LightlyObscured
+        );
+
+        let fog = visible_cells(&grid, observer, &human, LightLevel::Darkness, &lights);
+        assert!(fog.contains(&GridPos::new(8, 0)));
+        assert!(!fog.contains(&GridPos::new(9, 0)));
+    }
+
+    #[test]
+    fn test_attack_roll_mode() {
+        let unseen_attacker = AttackVisibility {
+            cover: Cover::None,
+            attacker_sees_target: true,
+            target_sees_attacker: false,
+        };
+        assert_eq!(unseen_attacker.roll_mode(), Some(true));
+        let both_blind = AttackVisibility {
+            attacker_sees_target: false,
+            ..unseen_attacker
+        };
+        assert_eq!(both_blind.roll_mode(), None);
+    }
+}
@@ -0,0 +1,352 @@
+//! Join tokens and what each role may send
+//!
+//! The DM hands out a token per session and role (shown to players as a join URL or QR
+//! code). A connection joins a room with its token, which fixes its role and player id;
+//! every message it sends afterwards is checked against them.
+
+use crate::communication::{ClientRole, JoinRoom, PlayerAction, RollResult, RoomMember};
+use crate::error::{OrchestratorError, Result};
+use crate::intent::executor::{find_actor_id, owning_player};
+use crate::session::GameSession;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// UI intents that edit game state or rewind it; only the DM may send them
+pub const DM_ONLY_UI_INTENTS: &[&str] = &["undo", "redo", "advance_time"];
+
+/// What a join token lets its holder join as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinGrant {
+    pub session_id: String,
+    pub role: ClientRole,
+    /// The player a player token belongs to
+    pub player_id: Option<String>,
+}
+
+/// Join tokens handed out, by token
+#[derive(Debug, Default)]
+pub struct JoinTokens {
+    grants: HashMap<String, JoinGrant>,
+}
+
+impl JoinTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hand out a new token for `grant`
+    pub fn issue(&mut self, grant: JoinGrant) -> String {
+        let token = Uuid::new_v4().simple().to_string();
+        self.grants.insert(token.clone(), grant);
+        token
+    }
+
+    pub fn grant(&self, token: &str) -> Option<&JoinGrant> {
+        self.grants.get(token)
+    }
+
+    /// Stop a token from being used for new joins
+    pub fn revoke(&mut self, token: &str) -> bool {
+        self.grants.remove(token).is_some()
+    }
+
+    /// Revoke every token of a session; returns how many there were
+    pub fn revoke_session(&mut self, session_id: &str) -> usize {
+        let before = self.grants.len();
+        self.grants.retain(|_, g| g.session_id != session_id);
+        before - self.grants.len()
+    }
+}
+
+/// URL a client opens (or a QR code encodes) to join with `token`
+pub fn join_url(base_url: &str, token: &str) -> String {
+    format!("{}/ws?token={}", base_url.trim_end_matches('/'), token)
+}
+
+/// Check a join against the grant of the token it presented
+pub fn authorize_join(grant: Option<&JoinGrant>, join: &JoinRoom) -> Result<JoinGrant> {
+    let grant = grant.ok_or_else(|| {
+        OrchestratorError::Unauthorized("A valid join token is required".to_string())
+    })?;
+    if grant.session_id != join.session_id {
+        return Err(OrchestratorError::Unauthorized(format!(
+            "Token is not for session {}",
+            join.session_id
+        )));
+    }
+    if grant.role != join.role {
+        return Err(OrchestratorError::Unauthorized(format!(
+            "Token does not allow joining as {:?}",
+            join.role
+        )));
+    }
+    if join.player_id.is_some() && join.player_id != grant.player_id {
+        return Err(OrchestratorError::Unauthorized(format!(
+            "Token does not belong to player {}",
+            join.player_id.as_deref().unwrap_or_default()
+        )));
+    }
+    Ok(grant.clone())
+}
+
+/// The joined member that sent a message for `session_id`, if it may act in it
+fn acting_member<'a>(member: Option<&'a RoomMember>, session_id: &str) -> Result<&'a RoomMember> {
+    let member = member.ok_or_else(|| {
+        OrchestratorError::Unauthorized("Join a session before sending actions".to_string())
+    })?;
+    if member.session_id != session_id {
+        return Err(OrchestratorError::Forbidden(format!(
+            "Joined session {}, not {}",
+            member.session_id, session_id
+        )));
+    }
+    if member.role == ClientRole::Spectator {
+        return Err(OrchestratorError::Forbidden(
+            "Spectators cannot act".to_string(),
+        ));
+    }
+    Ok(member)
+}
+
+/// Whether `player_id`'s character is `actor` or controls it
+pub(crate) fn plays(session: &GameSession, player_id: &str, actor: &str) -> bool {
+    let Some(engine) = session.engine_session() else {
+        return false;
+    };
+    let player = find_actor_id(engine, player_id);
+    player.is_some()
+        && find_actor_id(engine, actor).and_then(|id| owning_player(engine, id)) == player
+}
+
+/// Whether `member` is the player who rolls for `actor`
+pub fn rolls_for(member: &RoomMember, actor: &str, session: &GameSession) -> bool {
+    member.role == ClientRole::Player
+        && member
+            .player_id
+            .as_deref()
+            .is_some_and(|player_id| plays(session, player_id, actor))
+}
+
+/// Check a player action: players act only as themselves, spectators not at all, and
+/// state edits are for the DM
+pub fn authorize_action(
+    member: Option<&RoomMember>,
+    action: &PlayerAction,
+    session: &GameSession,
+) -> Result<()> {
+    let member = acting_member(member, &action.session_id)?;
+    if member.role == ClientRole::Dm {
+        return Ok(());
+    }
+    let player_id = member.player_id.as_deref().unwrap_or_default();
+    if action.player_id != player_id {
+        return Err(OrchestratorError::Forbidden(format!(
+            "{} cannot act as {}",
+            player_id, action.player_id
+        )));
+    }
+
+    let Some(ui_intent) = action.ui_intent.as_deref() else {
+        return Ok(());
+    };
+    if DM_ONLY_UI_INTENTS.contains(&ui_intent) {
+        return Err(OrchestratorError::Forbidden(format!(
+            "Only the DM can {}",
+            ui_intent
+        )));
+    }
+    // Turn controls only work on the player's own characters
+    let actor = match ui_intent {
+        "end_turn" => session
+            .engine_session()
+            .and_then(|e| e.turn_order.current_actor())
+            .map(|id| id.to_string()),
+        "delay_turn" | "resume_turn" => Some(
+            action
+                .target_id
+                .clone()
+                .unwrap_or_else(|| action.player_id.clone()),
+        ),
+        _ => None,
+    };
+    match actor {
+        Some(actor) if !plays(session, player_id, &actor) => Err(OrchestratorError::Forbidden(
+            format!("{} cannot {} for {}", player_id, ui_intent, actor),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Check a roll result: players only roll for their own characters
+pub fn authorize_roll(
+    member: Option<&RoomMember>,
+    result: &RollResult,
+    session: &GameSession,
+) -> Result<()> {
+    let member = acting_member(member, &result.session_id)?;
+    let player_id = member.player_id.as_deref().unwrap_or_default();
+    if member.role == ClientRole::Player && !plays(session, player_id, &result.actor_id) {
+        return Err(OrchestratorError::Forbidden(format!(
+            "{} cannot roll for {}",
+            player_id, result.actor_id
+        )));
+    }
+    Ok(())
+}
+
+/// Check a DM console command: only the session's DM may send them
+pub fn authorize_dm_command<'a>(
+    member: Option<&'a RoomMember>,
+    session_id: &str,
+) -> Result<&'a RoomMember> {
+    let member = acting_member(member, session_id)?;
+    if member.role != ClientRole::Dm {
+        return Err(OrchestratorError::Forbidden(
+            "Only the DM can use the DM console".to_string(),
+        ));
+    }
+    Ok(member)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::ActionKind;
+    use game_engine::{Actor, ActorType, Control, ControlKind, GameEvent};
+
+    fn member(role: ClientRole, player_id: Option<&str>) -> RoomMember {
+        RoomMember {
+            session_id: "table".to_string(),
+            role,
+            player_id: player_id.map(String::from),
+        }
+    }
+
+    fn ui(player_id: &str, ui_intent: &str) -> PlayerAction {
+        PlayerAction {
+            session_id: "table".to_string(),
+            player_id: player_id.to_string(),
+            kind: ActionKind::Ui,
+            text: None,
+            ui_intent: Some(ui_intent.to_string()),
+            target_id: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_join_needs_a_matching_token() {
+        let mut tokens = JoinTokens::new();
+        let token = tokens.issue(JoinGrant {
+            session_id: "table".to_string(),
+            role: ClientRole::Player,
+            player_id: Some("Aria".to_string()),
+        });
+        assert_eq!(
+            join_url("http://192.168.1.5:7000/", &token),
+            format!("http://192.168.1.5:7000/ws?token={}", token)
+        );
+        let join = |role, player_id: Option<&str>| JoinRoom {
+            session_id: "table".to_string(),
+            role,
+            player_id: player_id.map(String::from),
+            last_seen_seq: None,
+            token: Some(token.clone()),
+        };
+
+        let grant = authorize_join(tokens.grant(&token), &join(ClientRole::Player, None)).unwrap();
+        assert_eq!(grant.player_id.as_deref(), Some("Aria"));
+        for (role, player_id) in [(ClientRole::Dm, None), (ClientRole::Player, Some("Borin"))] {
+            assert!(matches!(
+                authorize_join(tokens.grant(&token), &join(role, player_id)),
+                Err(OrchestratorError::Unauthorized(_))
+            ));
+        }
+        assert!(authorize_join(None, &join(ClientRole::Player, None)).is_err());
+
+        assert!(tokens.revoke(&token));
+        assert!(tokens.grant(&token).is_none());
+    }
+
+    #[test]
+    fn test_players_act_only_for_their_characters() {
+        let mut session = GameSession::new();
+        session.session_id = "table".to_string();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Crypt".to_string());
+        let aria = Actor::new("Aria".to_string(), ActorType::Player);
+        let borin = Actor::new("Borin".to_string(), ActorType::Player);
+        let mut hawk = Actor::new("Hawk".to_string(), ActorType::Monster);
+        hawk.controlled_by = Some(Control::new(aria.id, ControlKind::Companion));
+        let (aria_id, borin_id) = (aria.id, borin.id);
+        for actor in [aria, borin, hawk] {
+            engine.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+        session
+            .dispatch(GameEvent::CombatStarted {
+                order: vec![(borin_id, 15), (aria_id, 10)],
+                surprised: vec![],
+            })
+            .unwrap();
+
+        let aria = member(ClientRole::Player, Some("Aria"));
+        let dm = member(ClientRole::Dm, None);
+        let spectator = member(ClientRole::Spectator, None);
+        let forbidden = |r: Result<()>| matches!(r, Err(OrchestratorError::Forbidden(_)));
+
+        assert!(matches!(
+            authorize_action(None, &ui("Aria", "end_turn"), &session),
+            Err(OrchestratorError::Unauthorized(_))
+        ));
+        assert!(forbidden(authorize_action(
+            Some(&spectator),
+            &ui("Aria", "end_turn"),
+            &session
+        )));
+        assert!(forbidden(authorize_action(
+            Some(&aria),
+            &ui("Borin", "delay_turn"),
+            &session
+        )));
+        assert!(forbidden(authorize_action(
+            Some(&aria),
+            &ui("Aria", "undo"),
+            &session
+        )));
+        // It is Borin's turn, so Aria can't end it but the DM can
+        assert!(forbidden(authorize_action(
+            Some(&aria),
+            &ui("Aria", "end_turn"),
+            &session
+        )));
+        assert!(authorize_action(Some(&dm), &ui("dm", "end_turn"), &session).is_ok());
+        assert!(authorize_action(Some(&aria), &ui("Aria", "delay_turn"), &session).is_ok());
+
+        let roll = |actor: &str| RollResult {
+            session_id: "table".to_string(),
+            request_id: "r1".to_string(),
+            actor_id: actor.to_string(),
+            total: 12,
+            natural: 12,
+            breakdown: serde_json::json!({}),
+            client_seed: None,
+            timestamp: 0,
+        };
+        assert!(authorize_roll(Some(&aria), &roll("Hawk"), &session).is_ok());
+        assert!(forbidden(authorize_roll(
+            Some(&aria),
+            &roll("Borin"),
+            &session
+        )));
+        assert!(authorize_roll(Some(&dm), &roll("Borin"), &session).is_ok());
+        // Only the rolling player's client acks a roll request
+        assert!(rolls_for(&aria, "Hawk", &session));
+        assert!(!rolls_for(&aria, "Borin", &session));
+        assert!(!rolls_for(&dm, "Borin", &session));
+
+        assert!(forbidden(
+            authorize_dm_command(Some(&aria), "table").map(|_| ())
+        ));
+        assert!(authorize_dm_command(Some(&dm), "table").is_ok());
+    }
+}
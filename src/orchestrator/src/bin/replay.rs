@@ -0,0 +1,46 @@
+//! Replay a recorded session and check it rebuilds the same game state
+//!
+//! Usage: replay <recording.json> [--rules5e-url <url>]
+//!
+//! LLM output comes from the recording, so only the rules5e service is needed.
+
+use orchestrator::services::{MemoryClient, Rules5eClient};
+use orchestrator::session::replay::{replay, verify, SessionRecording};
+use orchestrator::IntentExecutor;
+use std::sync::Arc;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let mut path = None;
+    let mut rules5e_url = "http://localhost:3001".to_string();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules5e-url" => {
+                rules5e_url = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--rules5e-url needs a value"))?;
+            }
+            _ => path = Some(arg),
+        }
+    }
+    let path = path
+        .ok_or_else(|| anyhow::anyhow!("Usage: replay <recording.json> [--rules5e-url <url>]"))?;
+
+    let recording: SessionRecording = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    let executor = IntentExecutor::with_clients(
+        Arc::new(Rules5eClient::new(rules5e_url)),
+        Arc::new(MemoryClient::default()),
+    );
+
+    let replayed = replay(&recording, &executor).await?;
+    verify(&recording, &replayed)?;
+    println!(
+        "Replayed {} inputs of session {}: state matches",
+        recording.inputs.len(),
+        recording.session_id
+    );
+    Ok(())
+}
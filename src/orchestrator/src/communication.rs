@@ -2,23 +2,31 @@
 //!
 //! Handles IPC/WebSocket communication with Electron client
 
+use crate::auth::{self, JoinGrant, JoinTokens};
+use crate::dm_console::{DmCommand, DM_AUTHOR};
 use crate::error::{OrchestratorError, Result};
 use crate::orchestrator::Orchestrator;
-use crate::session::SessionManager;
+use crate::outbox::Outbox;
+use crate::rolls::{ExpiredRoll, RollPolicy, RollResume, RollStatus, RollTracker, SettledRoll};
+use crate::session::{GameSession, SessionManager};
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        Query, State, WebSocketUpgrade,
     },
     response::Response,
     routing::get,
     Router,
 };
 use futures_util::{SinkExt, StreamExt};
+use game_engine::{ActorType, WorldTime};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
 
 /// Player Action from UI
@@ -69,6 +77,76 @@ pub struct SceneUpdate {
     pub summary: String,
     pub active_speaker_id: Option<String>,
     pub participants: Vec<Participant>,
+    /// In-game date, time of day and moon phase
+    #[serde(default)]
+    pub world_time: Option<WorldTime>,
+    /// Scene the DM is running
+    #[serde(default)]
+    pub scene_id: Option<String>,
+    #[serde(default)]
+    pub scene_name: Option<String>,
+    /// Where each group of a (possibly split) party is
+    #[serde(default)]
+    pub party_locations: Vec<PartyLocation>,
+}
+
+impl SceneUpdate {
+    /// Build a scene update from the session's state and current scene
+    pub fn from_session(session_id: &str, session: &GameSession) -> Self {
+        let engine = session.engine_session();
+        let scene = engine.and_then(|e| e.get_current_scene());
+        let participants = engine
+            .map(|e| {
+                let mut actors = e.scene_actors();
+                actors.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+                actors
+                    .into_iter()
+                    .map(|actor| Participant {
+                        id: actor.id.to_string(),
+                        name: actor.name.clone(),
+                        portrait_url: None,
+                        is_npc: actor.actor_type != ActorType::Player,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let party_locations = engine
+            .map(|e| {
+                e.party_locations()
+                    .into_iter()
+                    .map(|(scene_id, actor_ids)| PartyLocation {
+                        scene_id: scene_id.to_string(),
+                        scene_name: e
+                            .scenes
+                            .get(&scene_id)
+                            .map(|s| s.name.clone())
+                            .unwrap_or_default(),
+                        actor_ids: actor_ids.iter().map(|id| id.to_string()).collect(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            session_id: session_id.to_string(),
+            scene_state: session.current_state().name().to_string(),
+            summary: format!("Scene in {:?} state", session.current_state()),
+            active_speaker_id: None,
+            participants,
+            world_time: engine.map(|e| e.clock.world_time()),
+            scene_id: scene.map(|s| s.id.to_string()),
+            scene_name: scene.map(|s| s.name.clone()),
+            party_locations,
+        }
+    }
+}
+
+/// Party members standing in one scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyLocation {
+    pub scene_id: String,
+    pub scene_name: String,
+    pub actor_ids: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -96,10 +174,103 @@ pub struct InitiativeEntry {
     pub current_hp: i32,
     pub max_hp: i32,
     pub is_active: bool,
+    #[serde(default)]
+    pub initiative: Option<i32>,
+    /// Creature loses its round 1 turn to surprise
+    #[serde(default)]
+    pub surprised: bool,
 }
 
-/// Roll Request to UI
+impl CombatUpdate {
+    /// Build a combat update from the session's current scene and turn order
+    pub fn from_session(session: &GameSession) -> Self {
+        let engine = session.engine_session();
+        let scene = engine.and_then(|e| e.get_current_scene());
+        let in_combat = scene.map(|s| s.combat_active).unwrap_or(false);
+
+        let (round, initiative_order, active_creature_id) = match (engine, scene) {
+            (Some(engine), Some(_)) if in_combat => {
+                let current = engine.turn_order.current_actor();
+                let entries = engine
+                    .turn_order
+                    .all_actors()
+                    .into_iter()
+                    .filter_map(|id| engine.get_actor(id))
+                    .map(|actor| InitiativeEntry {
+                        creature_id: actor.id.to_string(),
+                        name: actor.name.clone(),
+                        current_hp: actor.hp,
+                        max_hp: actor.max_hp,
+                        is_active: current == Some(actor.id),
+                        initiative: actor.initiative,
+                        surprised: engine.turn_order.is_surprised(actor.id),
+                    })
+                    .collect();
+                (
+                    engine.get_round(),
+                    entries,
+                    current.map(|id| id.to_string()),
+                )
+            }
+            _ => (0, Vec::new(), None),
+        };
+
+        Self {
+            session_id: session.session_id.clone(),
+            in_combat,
+            round,
+            initiative_order,
+            active_creature_id,
+        }
+    }
+}
+
+/// Squares a player character can see, for fog of war in the UI
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FogOfWarUpdate {
+    pub session_id: String,
+    pub actor_id: String,
+    /// (x, y) grid squares in view
+    pub visible_cells: Vec<(i32, i32)>,
+}
+
+impl FogOfWarUpdate {
+    /// One update per player character on the current scene's grid
+    pub fn from_session(session: &GameSession) -> Vec<Self> {
+        let Some(engine) = session.engine_session() else {
+            return Vec::new();
+        };
+        if engine
+            .get_current_scene()
+            .map_or(true, |s| s.grid.is_none())
+        {
+            return Vec::new();
+        }
+
+        engine
+            .scene_actors()
+            .into_iter()
+            .filter(|a| a.actor_type == ActorType::Player)
+            .filter_map(|actor| {
+                let mut cells: Vec<_> = engine
+                    .visible_cells(actor.id)
+                    .ok()?
+                    .into_iter()
+                    .map(|pos| (pos.x, pos.y))
+                    .collect();
+                cells.sort_unstable();
+                Some(Self {
+                    session_id: session.session_id.clone(),
+                    actor_id: actor.id.to_string(),
+                    visible_cells: cells,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Roll Request to UI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RollRequest {
     pub session_id: String,
     pub request_id: String,
@@ -110,6 +281,19 @@ pub struct RollRequest {
     pub dc: Option<i32>,
     pub formula_hint: Option<String>,
     pub reason: String,
+    /// Only the DM learns the result
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Tells the UI a roll request is no longer waiting for the player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollClosed {
+    pub session_id: String,
+    pub request_id: String,
+    pub status: RollStatus,
+    /// The roll that settled it, when there is one
+    pub total: Option<i32>,
 }
 
 /// Narration to UI/TTS
@@ -122,6 +306,24 @@ pub struct Narration {
     pub tagged_for_tts: bool,
 }
 
+/// Command from the DM console
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DmCommandMessage {
+    pub session_id: String,
+    pub command: DmCommand,
+}
+
+/// LLM output held for the DM to approve or veto
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingIntents {
+    pub session_id: String,
+    pub batch_id: u64,
+    /// Player whose action the output answers
+    pub issuer: Option<String>,
+    /// Narration and INTENTs, as the LLM wrote them
+    pub text: String,
+}
+
 /// IPC Message wrapper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -134,10 +336,30 @@ pub enum IpcMessage {
     SceneUpdate(SceneUpdate),
     #[serde(rename = "combat-update")]
     CombatUpdate(CombatUpdate),
+    #[serde(rename = "fog-of-war")]
+    FogOfWar(FogOfWarUpdate),
     #[serde(rename = "roll-request")]
     RollRequest(RollRequest),
+    #[serde(rename = "roll-closed")]
+    RollClosed(RollClosed),
     #[serde(rename = "narration")]
     Narration(Narration),
+    #[serde(rename = "dm-command")]
+    DmCommand(DmCommandMessage),
+    #[serde(rename = "pending-intents")]
+    PendingIntents(PendingIntents),
+    #[serde(rename = "join")]
+    Join(JoinRoom),
+    #[serde(rename = "leave")]
+    Leave(LeaveRoom),
+    #[serde(rename = "joined")]
+    Joined(RoomJoined),
+    #[serde(rename = "issue-token")]
+    IssueToken(IssueJoinToken),
+    #[serde(rename = "token-issued")]
+    TokenIssued(JoinTokenIssued),
+    #[serde(rename = "ack")]
+    Ack(Ack),
     #[serde(rename = "error")]
     Error(IpcError),
     #[serde(rename = "ping")]
@@ -146,6 +368,184 @@ pub enum IpcMessage {
     Pong,
 }
 
+impl IpcMessage {
+    /// Session the message belongs to, if any
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            IpcMessage::PlayerAction(m) => Some(&m.session_id),
+            IpcMessage::RollResult(m) => Some(&m.session_id),
+            IpcMessage::SceneUpdate(m) => Some(&m.session_id),
+            IpcMessage::CombatUpdate(m) => Some(&m.session_id),
+            IpcMessage::FogOfWar(m) => Some(&m.session_id),
+            IpcMessage::RollRequest(m) => Some(&m.session_id),
+            IpcMessage::RollClosed(m) => Some(&m.session_id),
+            IpcMessage::Narration(m) => Some(&m.session_id),
+            IpcMessage::DmCommand(m) => Some(&m.session_id),
+            IpcMessage::PendingIntents(m) => Some(&m.session_id),
+            IpcMessage::Join(m) => Some(&m.session_id),
+            IpcMessage::Leave(m) => Some(&m.session_id),
+            IpcMessage::Joined(m) => Some(&m.session_id),
+            IpcMessage::IssueToken(m) => Some(&m.session_id),
+            IpcMessage::TokenIssued(m) => Some(&m.session_id),
+            IpcMessage::Ack(m) => Some(&m.session_id),
+            IpcMessage::Error(_) | IpcMessage::Ping | IpcMessage::Pong => None,
+        }
+    }
+
+    /// Critical messages are sent again until a client in the room acks them
+    pub fn needs_ack(&self) -> bool {
+        matches!(self, IpcMessage::RollRequest(_))
+    }
+}
+
+/// Who is on the other end of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRole {
+    Player,
+    Dm,
+    Spectator,
+}
+
+/// Join a session's room; players say which player they are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinRoom {
+    pub session_id: String,
+    pub role: ClientRole,
+    #[serde(default)]
+    pub player_id: Option<String>,
+    /// A reconnecting client's last seen `seq`; what it missed is replayed
+    #[serde(default)]
+    pub last_seen_seq: Option<u64>,
+    /// Join token handed out by the DM for this session and role
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Leave the session's room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaveRoom {
+    pub session_id: String,
+}
+
+/// The DM asks for a join token to hand out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueJoinToken {
+    pub session_id: String,
+    pub role: ClientRole,
+    /// The player a player token is for
+    #[serde(default)]
+    pub player_id: Option<String>,
+}
+
+/// A join token for the DM to hand out, sent only to the DM's connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinTokenIssued {
+    pub session_id: String,
+    pub role: ClientRole,
+    pub player_id: Option<String>,
+    pub token: String,
+    /// Join URL relative to the server (`/ws?token=...`); the client puts its host in
+    /// front of it for the link or QR code
+    pub url: String,
+}
+
+/// Confirms a join to the connection that asked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomJoined {
+    pub session_id: String,
+    pub connection_id: u64,
+    pub role: ClientRole,
+    pub player_id: Option<String>,
+    /// The session's latest `seq`
+    #[serde(default)]
+    pub seq: u64,
+}
+
+/// Client has received a session message that needed an ack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ack {
+    pub session_id: String,
+    pub seq: u64,
+}
+
+/// A connection's place in a session's room
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomMember {
+    pub session_id: String,
+    pub role: ClientRole,
+    pub player_id: Option<String>,
+}
+
+/// Which connections a message goes to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Audience {
+    /// Every connection, in a room or not
+    Everyone,
+    /// Everyone in the session's room
+    Session(String),
+    /// The session's DMs
+    Dm(String),
+    /// One player in the session
+    Player {
+        session_id: String,
+        player_id: String,
+    },
+    /// A single connection
+    Connection(u64),
+}
+
+impl Audience {
+    /// Whether connection `connection_id`, in room `member` (if any), receives the message
+    pub fn includes(&self, connection_id: u64, member: Option<&RoomMember>) -> bool {
+        let in_session = |session_id: &str| member.filter(|m| m.session_id == session_id);
+        match self {
+            Audience::Everyone => true,
+            Audience::Session(session_id) => in_session(session_id).is_some(),
+            Audience::Dm(session_id) => {
+                in_session(session_id).is_some_and(|m| m.role == ClientRole::Dm)
+            }
+            Audience::Player {
+                session_id,
+                player_id,
+            } => in_session(session_id).is_some_and(|m| {
+                m.role == ClientRole::Player && m.player_id.as_deref() == Some(player_id.as_str())
+            }),
+            Audience::Connection(id) => *id == connection_id,
+        }
+    }
+
+    /// Session whose room the audience is in
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Audience::Session(session_id) | Audience::Dm(session_id) => Some(session_id),
+            Audience::Player { session_id, .. } => Some(session_id),
+            Audience::Everyone | Audience::Connection(_) => None,
+        }
+    }
+}
+
+/// A message on its way to its audience
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub audience: Audience,
+    pub message: IpcMessage,
+    /// Position in the session's outbox; messages outside a room have none
+    pub seq: Option<u64>,
+}
+
+/// A message as written to the socket, with its `seq` next to the `type`
+///
+/// Clients keep the highest `seq` they've seen and drop anything at or below it, since
+/// replays and resends can repeat a message.
+#[derive(Serialize)]
+struct WireMessage<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seq: Option<u64>,
+    #[serde(flatten)]
+    message: &'a IpcMessage,
+}
+
 /// IPC Error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IpcError {
@@ -158,10 +558,19 @@ pub struct IpcError {
 #[derive(Clone)]
 pub struct CommunicationState {
     session_manager: Arc<RwLock<SessionManager>>,
-    /// Broadcast channel for sending messages to all connected clients
-    tx: broadcast::Sender<IpcMessage>,
-    /// Pending roll requests (request_id -> RollRequest)
-    pending_rolls: Arc<RwLock<HashMap<String, RollRequest>>>,
+    /// Channel every connection listens on; each keeps the deliveries meant for it
+    tx: broadcast::Sender<Delivery>,
+    /// Room each connection has joined (connection id -> member)
+    rooms: Arc<RwLock<HashMap<u64, RoomMember>>>,
+    /// Join tokens the DM has handed out
+    join_tokens: Arc<RwLock<JoinTokens>>,
+    /// Token each connection opened its socket with (the join URL's `?token=`)
+    connection_tokens: Arc<RwLock<HashMap<u64, String>>>,
+    /// Sequenced history of each session's messages, for replay and resends
+    outbox: Arc<Mutex<Outbox>>,
+    next_connection_id: Arc<AtomicU64>,
+    /// Roll requests and their lifecycle, with each table's roll policy
+    rolls: Arc<RwLock<RollTracker>>,
     /// Orchestrator reference for processing actions
     orchestrator: Option<Arc<Orchestrator>>,
 }
@@ -172,7 +581,12 @@ impl CommunicationState {
         Self {
             session_manager,
             tx,
-            pending_rolls: Arc::new(RwLock::new(HashMap::new())),
+            rooms: Arc::new(RwLock::new(HashMap::new())),
+            join_tokens: Arc::new(RwLock::new(JoinTokens::new())),
+            connection_tokens: Arc::new(RwLock::new(HashMap::new())),
+            outbox: Arc::new(Mutex::new(Outbox::default())),
+            next_connection_id: Arc::new(AtomicU64::new(1)),
+            rolls: Arc::new(RwLock::new(RollTracker::new())),
             orchestrator: None,
         }
     }
@@ -182,31 +596,436 @@ impl CommunicationState {
         self
     }
 
-    /// Send message to all connected clients
+    /// Send a message to its session's room, or to every client if it has no session
     pub fn broadcast(&self, message: IpcMessage) -> Result<()> {
-        self.tx.send(message).map_err(|e| {
+        let audience = message
+            .session_id()
+            .map_or(Audience::Everyone, |id| Audience::Session(id.to_string()));
+        self.send(audience, message)
+    }
+
+    /// Send a message to the connections in `audience`
+    ///
+    /// Messages for a session's room are numbered and kept in its outbox, so they count
+    /// as sent even with nobody connected; a client that joins later can replay them.
+    pub fn send(&self, audience: Audience, message: IpcMessage) -> Result<()> {
+        let mut delivery = Delivery {
+            audience,
+            message,
+            seq: None,
+        };
+        let Some(session_id) = delivery.audience.session_id().map(String::from) else {
+            return self.deliver(delivery);
+        };
+        // Numbering and sending under one lock keeps the channel in seq order
+        let mut outbox = self.outbox.lock().unwrap();
+        outbox.record(&session_id, &mut delivery, Instant::now());
+        let _ = self.tx.send(delivery);
+        Ok(())
+    }
+
+    /// Put a delivery on the channel as it is
+    fn deliver(&self, delivery: Delivery) -> Result<()> {
+        self.tx.send(delivery).map_err(|e| {
             OrchestratorError::CommunicationError(format!("Broadcast failed: {}", e))
         })?;
         Ok(())
     }
 
     /// Send message to specific session
-    pub async fn send_to_session(&self, _session_id: &str, message: IpcMessage) -> Result<()> {
-        // In a real implementation, we'd track which clients are connected to which sessions
-        // For now, we broadcast to all and let clients filter
-        self.broadcast(message)
+    pub fn send_to_session(&self, session_id: &str, message: IpcMessage) -> Result<()> {
+        self.send(Audience::Session(session_id.to_string()), message)
+    }
+
+    /// Send a message only the session's DMs see (e.g. a secret roll)
+    pub fn send_to_dm(&self, session_id: &str, message: IpcMessage) -> Result<()> {
+        self.send(Audience::Dm(session_id.to_string()), message)
+    }
+
+    /// Send a private message to one player in the session
+    pub fn send_to_player(
+        &self,
+        session_id: &str,
+        player_id: &str,
+        message: IpcMessage,
+    ) -> Result<()> {
+        self.send(
+            Audience::Player {
+                session_id: session_id.to_string(),
+                player_id: player_id.to_string(),
+            },
+            message,
+        )
+    }
+
+    /// Send each player character's visible squares to the players in the room who play
+    /// it and to the DM (gridded scenes only)
+    pub async fn send_fog_of_war(&self, session: &GameSession) -> Result<()> {
+        let members = self.room_members(&session.session_id).await;
+        for update in FogOfWarUpdate::from_session(session) {
+            for (_, member) in &members {
+                let Some(player_id) = member.player_id.as_deref() else {
+                    continue;
+                };
+                if member.role == ClientRole::Player
+                    && auth::plays(session, player_id, &update.actor_id)
+                {
+                    self.send_to_player(
+                        &session.session_id,
+                        player_id,
+                        IpcMessage::FogOfWar(update.clone()),
+                    )?;
+                }
+            }
+            self.send_to_dm(&session.session_id, IpcMessage::FogOfWar(update))?;
+        }
+        Ok(())
+    }
+
+    pub fn send_to_connection(&self, connection_id: u64, message: IpcMessage) -> Result<()> {
+        self.send(Audience::Connection(connection_id), message)
+    }
+
+    /// Subscribe to the messages sent to clients, with their audience
+    pub fn subscribe(&self) -> broadcast::Receiver<Delivery> {
+        self.tx.subscribe()
+    }
+
+    /// Id for a new connection
+    pub fn next_connection_id(&self) -> u64 {
+        self.next_connection_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Put a connection in a session's room, leaving any room it was in
+    ///
+    /// The join token decides the role and player the connection acts as. A join
+    /// without one uses the token from the connection's join URL.
+    pub async fn join(&self, connection_id: u64, join: JoinRoom) -> Result<RoomMember> {
+        let grant = {
+            let token = match &join.token {
+                Some(token) => Some(token.clone()),
+                None => self
+                    .connection_tokens
+                    .read()
+                    .await
+                    .get(&connection_id)
+                    .cloned(),
+            };
+            let tokens = self.join_tokens.read().await;
+            let grant = token.as_deref().and_then(|t| tokens.grant(t));
+            auth::authorize_join(grant, &join)?
+        };
+        if self
+            .session_manager
+            .read()
+            .await
+            .get_session(&grant.session_id)
+            .is_none()
+        {
+            return Err(OrchestratorError::SessionError(format!(
+                "Session not found: {}",
+                grant.session_id
+            )));
+        }
+
+        let member = RoomMember {
+            session_id: grant.session_id,
+            role: grant.role,
+            player_id: grant.player_id,
+        };
+        self.rooms
+            .write()
+            .await
+            .insert(connection_id, member.clone());
+        Ok(member)
+    }
+
+    /// Hand out a join token for a session; player tokens belong to one player
+    ///
+    /// The host gives the DM their token when it opens the session; the DM asks for the
+    /// others with an `issue-token` message.
+    pub async fn issue_join_token(
+        &self,
+        session_id: &str,
+        role: ClientRole,
+        player_id: Option<&str>,
+    ) -> Result<String> {
+        if self
+            .session_manager
+            .read()
+            .await
+            .get_session(session_id)
+            .is_none()
+        {
+            return Err(OrchestratorError::SessionError(format!(
+                "Session not found: {}",
+                session_id
+            )));
+        }
+        if role == ClientRole::Player && player_id.is_none() {
+            return Err(OrchestratorError::CommunicationError(
+                "Player tokens need a player_id".to_string(),
+            ));
+        }
+        Ok(self.join_tokens.write().await.issue(JoinGrant {
+            session_id: session_id.to_string(),
+            role,
+            player_id: player_id.map(String::from),
+        }))
+    }
+
+    /// Stop a join token from admitting new connections
+    pub async fn revoke_join_token(&self, token: &str) -> bool {
+        self.join_tokens.write().await.revoke(token)
+    }
+
+    /// Check a player action against the sender's room and role; returns the sender
+    async fn authorize_action(
+        &self,
+        connection_id: u64,
+        action: &PlayerAction,
+    ) -> Result<RoomMember> {
+        let member = self.room_member(connection_id).await;
+        let session_manager = self.session_manager.read().await;
+        let session = session_manager
+            .get_session(&action.session_id)
+            .ok_or_else(|| {
+                OrchestratorError::SessionError(format!("Session not found: {}", action.session_id))
+            })?;
+        auth::authorize_action(member.as_ref(), action, session)?;
+        Ok(member.expect("authorized senders have joined"))
+    }
+
+    /// Check a roll result against the sender's room and role
+    async fn authorize_roll(&self, connection_id: u64, result: &RollResult) -> Result<()> {
+        let member = self.room_member(connection_id).await;
+        let session_manager = self.session_manager.read().await;
+        let session = session_manager
+            .get_session(&result.session_id)
+            .ok_or_else(|| {
+                OrchestratorError::SessionError(format!("Session not found: {}", result.session_id))
+            })?;
+        auth::authorize_roll(member.as_ref(), result, session)
+    }
+
+    /// Check that a DM console command comes from the session's DM
+    async fn authorize_dm_command(
+        &self,
+        connection_id: u64,
+        session_id: &str,
+    ) -> Result<RoomMember> {
+        let member = self.room_member(connection_id).await;
+        auth::authorize_dm_command(member.as_ref(), session_id).cloned()
     }
 
-    /// Store pending roll request
-    pub async fn store_roll_request(&self, request: RollRequest) {
-        let mut pending = self.pending_rolls.write().await;
-        pending.insert(request.request_id.clone(), request);
+    /// Take a connection out of its room
+    pub async fn leave(&self, connection_id: u64) -> Option<RoomMember> {
+        self.rooms.write().await.remove(&connection_id)
     }
 
-    /// Get and remove pending roll request
-    pub async fn get_roll_request(&self, request_id: &str) -> Option<RollRequest> {
-        let mut pending = self.pending_rolls.write().await;
-        pending.remove(request_id)
+    /// Remember the token a connection's join URL carried, for its joins
+    pub async fn bind_token(&self, connection_id: u64, token: String) {
+        self.connection_tokens
+            .write()
+            .await
+            .insert(connection_id, token);
+    }
+
+    /// Forget a closed connection: its room and the token it connected with
+    pub async fn disconnect(&self, connection_id: u64) {
+        self.leave(connection_id).await;
+        self.connection_tokens.write().await.remove(&connection_id);
+    }
+
+    pub async fn room_member(&self, connection_id: u64) -> Option<RoomMember> {
+        self.rooms.read().await.get(&connection_id).cloned()
+    }
+
+    /// Connections in a session's room
+    pub async fn room_members(&self, session_id: &str) -> Vec<(u64, RoomMember)> {
+        let mut members: Vec<_> = self
+            .rooms
+            .read()
+            .await
+            .iter()
+            .filter(|(_, m)| m.session_id == session_id)
+            .map(|(id, m)| (*id, m.clone()))
+            .collect();
+        members.sort_by_key(|(id, _)| *id);
+        members
+    }
+
+    /// Latest `seq` sent to a session's room
+    pub fn last_seq(&self, session_id: &str) -> u64 {
+        self.outbox.lock().unwrap().last_seq(session_id)
+    }
+
+    /// Send a (re)joining connection what it missed after `last_seen`
+    ///
+    /// When the outbox no longer holds all of it, the current scene and combat state go
+    /// first so the client can rebuild from there. Returns how many messages were replayed.
+    pub async fn replay(
+        &self,
+        connection_id: u64,
+        member: &RoomMember,
+        last_seen: u64,
+    ) -> Result<usize> {
+        let (missed, gap) = {
+            let outbox = self.outbox.lock().unwrap();
+            match outbox.since(&member.session_id, last_seen) {
+                Some(missed) => (missed, false),
+                None => (outbox.retained(&member.session_id), true),
+            }
+        };
+
+        if gap {
+            let session_manager = self.session_manager.read().await;
+            if let Some(session) = session_manager.get_session(&member.session_id) {
+                for message in [
+                    IpcMessage::SceneUpdate(SceneUpdate::from_session(&member.session_id, session)),
+                    IpcMessage::CombatUpdate(CombatUpdate::from_session(session)),
+                ] {
+                    self.send_to_connection(connection_id, message)?;
+                }
+            }
+        }
+
+        let mut replayed = 0;
+        for delivery in missed
+            .into_iter()
+            .filter(|d| d.audience.includes(connection_id, Some(member)))
+        {
+            self.deliver(Delivery {
+                audience: Audience::Connection(connection_id),
+                ..delivery
+            })?;
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    /// Record a client's ack for a message of the session it joined. Only connections
+    /// the message was meant for can ack it, and a roll request only the rolling player.
+    pub async fn ack(&self, connection_id: u64, ack: &Ack) -> bool {
+        let Some(member) = self
+            .room_member(connection_id)
+            .await
+            .filter(|m| m.session_id == ack.session_id)
+        else {
+            return false;
+        };
+        let delivery = self
+            .outbox
+            .lock()
+            .unwrap()
+            .get(&ack.session_id, ack.seq)
+            .cloned();
+        let Some(delivery) = delivery else {
+            return false;
+        };
+        if !delivery.audience.includes(connection_id, Some(&member)) {
+            return false;
+        }
+        if let IpcMessage::RollRequest(request) = &delivery.message {
+            let session_manager = self.session_manager.read().await;
+            let rolls = session_manager
+                .get_session(&ack.session_id)
+                .is_some_and(|session| auth::rolls_for(&member, &request.actor_id, session));
+            if !rolls {
+                return false;
+            }
+        }
+        self.outbox.lock().unwrap().ack(&ack.session_id, ack.seq)
+    }
+
+    /// Send again the critical messages nobody has acked for `after`
+    pub fn resend_unacked(&self, after: Duration) -> usize {
+        let mut outbox = self.outbox.lock().unwrap();
+        let due = outbox.due_for_resend(Instant::now(), after);
+        let count = due.len();
+        for delivery in due {
+            let _ = self.tx.send(delivery);
+        }
+        count
+    }
+
+    /// Resend unacked messages every `interval`
+    pub fn spawn_resends(&self, interval: Duration) -> JoinHandle<()> {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let resent = state.resend_unacked(interval);
+                if resent > 0 {
+                    info!("Resent {} unacked messages", resent);
+                }
+            }
+        })
+    }
+
+    /// Whether a delivery is meant for the connection
+    async fn delivers_to(&self, connection_id: u64, audience: &Audience) -> bool {
+        match audience {
+            Audience::Everyone => true,
+            Audience::Connection(id) => *id == connection_id,
+            _ => audience.includes(connection_id, self.rooms.read().await.get(&connection_id)),
+        }
+    }
+
+    /// Set a table's roll timeout and auto-roll fallback
+    pub async fn set_roll_policy(&self, session_id: &str, policy: RollPolicy) {
+        self.rolls.write().await.set_policy(session_id, policy);
+    }
+
+    /// Start waiting for the player's roll; `resume` says what to do with it
+    pub async fn store_roll_request(&self, request: RollRequest, resume: RollResume) {
+        self.rolls
+            .write()
+            .await
+            .issue(request, resume, Instant::now());
+    }
+
+    pub async fn roll_status(&self, request_id: &str) -> Option<RollStatus> {
+        self.rolls.read().await.status(request_id)
+    }
+
+    /// Settle a pending roll request with the player's result
+    pub async fn fulfil_roll(&self, result: &RollResult) -> Result<SettledRoll> {
+        self.rolls
+            .write()
+            .await
+            .fulfil(result, Instant::now())
+            .map_err(OrchestratorError::CommunicationError)
+    }
+
+    /// Withdraw a pending roll request
+    pub async fn cancel_roll(&self, request_id: &str) -> Result<RollRequest> {
+        self.rolls
+            .write()
+            .await
+            .cancel(request_id, Instant::now())
+            .map_err(OrchestratorError::CommunicationError)
+    }
+
+    /// Settle a pending roll request with the DM's numbers
+    pub async fn override_roll(
+        &self,
+        session_id: &str,
+        request_id: &str,
+        total: i32,
+        natural: Option<i32>,
+    ) -> Result<SettledRoll> {
+        self.rolls
+            .write()
+            .await
+            .override_roll(session_id, request_id, total, natural, Instant::now())
+            .map_err(OrchestratorError::CommunicationError)
+    }
+
+    /// Expire roll requests whose table timeout has passed
+    pub async fn expire_rolls(&self) -> Vec<ExpiredRoll> {
+        self.rolls.write().await.expire(Instant::now())
     }
 }
 
@@ -217,20 +1036,31 @@ pub fn create_router(state: CommunicationState) -> Router {
         .with_state(state)
 }
 
+/// Query of the join URL a client connects with
+#[derive(Debug, Deserialize)]
+struct JoinQuery {
+    token: Option<String>,
+}
+
 /// WebSocket upgrade handler
 async fn websocket_handler(
     ws: WebSocketUpgrade,
+    Query(query): Query<JoinQuery>,
     State(state): State<CommunicationState>,
 ) -> Response {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    ws.on_upgrade(|socket| handle_websocket(socket, state, query.token))
 }
 
 /// Handle WebSocket connection
-async fn handle_websocket(socket: WebSocket, state: CommunicationState) {
+async fn handle_websocket(socket: WebSocket, state: CommunicationState, token: Option<String>) {
     let (mut sender, mut receiver) = socket.split();
     let rx = state.tx.subscribe();
+    let connection_id = state.next_connection_id();
+    if let Some(token) = token {
+        state.bind_token(connection_id, token).await;
+    }
 
-    info!("New WebSocket connection established");
+    info!("New WebSocket connection {} established", connection_id);
 
     // Send welcome message
     let welcome = IpcMessage::Pong;
@@ -245,17 +1075,25 @@ async fn handle_websocket(socket: WebSocket, state: CommunicationState) {
     // Create a channel for ping/pong handling
     let (tx_pong, rx_pong) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
 
-    // Spawn task to handle outgoing messages (broadcasts)
+    // Spawn task to handle outgoing messages meant for this connection
     let mut send_task = {
         let mut sender_clone = sender;
         let mut rx_clone = rx;
         let mut rx_pong_clone = rx_pong;
+        let state_clone = state.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
-                    // Handle broadcasts
-                    Ok(msg) = rx_clone.recv() => {
-                        let text = match serde_json::to_string(&msg) {
+                    // Handle deliveries, skipping the ones for other rooms and clients
+                    Ok(delivery) = rx_clone.recv() => {
+                        if !state_clone.delivers_to(connection_id, &delivery.audience).await {
+                            continue;
+                        }
+                        let wire = WireMessage {
+                            seq: delivery.seq,
+                            message: &delivery.message,
+                        };
+                        let text = match serde_json::to_string(&wire) {
                             Ok(t) => t,
                             Err(e) => {
                                 warn!("Failed to serialize message: {}", e);
@@ -285,15 +1123,13 @@ async fn handle_websocket(socket: WebSocket, state: CommunicationState) {
             while let Some(msg) = receiver.next().await {
                 match msg {
                     Ok(Message::Text(text)) => {
-                        if let Err(e) = handle_incoming_message(&state_clone, &text).await {
-                            error!("Error handling message: {}", e);
-                            // Send error response
-                            let error_msg = IpcMessage::Error(IpcError {
-                                code: "processing_error".to_string(),
-                                message: e.to_string(),
-                                request_id: None,
-                            });
-                            let _ = state_clone.broadcast(error_msg);
+                        if let Err(e) =
+                            handle_incoming_message(&state_clone, connection_id, &text).await
+                        {
+                            error!("Error handling message: {}", e.message);
+                            // Only the connection that sent the message hears about it
+                            let _ =
+                                state_clone.send_to_connection(connection_id, IpcMessage::Error(e));
                         }
                     }
                     Ok(Message::Close(_)) => {
@@ -325,30 +1161,109 @@ async fn handle_websocket(socket: WebSocket, state: CommunicationState) {
         }
     }
 
-    info!("WebSocket connection closed");
+    state.disconnect(connection_id).await;
+    info!("WebSocket connection {} closed", connection_id);
+}
+
+/// IPC error code for an orchestrator error, `fallback` unless it's a permission problem
+fn error_code(error: &OrchestratorError, fallback: &'static str) -> &'static str {
+    match error {
+        OrchestratorError::Unauthorized(_) => "unauthorized",
+        OrchestratorError::Forbidden(_) => "forbidden",
+        _ => fallback,
+    }
+}
+
+/// Error reply for the connection whose message failed
+fn ipc_error(code: &str, error: impl std::fmt::Display, request_id: Option<String>) -> IpcError {
+    IpcError {
+        code: code.to_string(),
+        message: error.to_string(),
+        request_id,
+    }
 }
 
 /// Handle incoming message from client
-async fn handle_incoming_message(state: &CommunicationState, text: &str) -> Result<()> {
+async fn handle_incoming_message(
+    state: &CommunicationState,
+    connection_id: u64,
+    text: &str,
+) -> std::result::Result<(), IpcError> {
     let message: IpcMessage = serde_json::from_str(text).map_err(|e| {
-        OrchestratorError::CommunicationError(format!("Failed to parse message: {}", e))
+        ipc_error(
+            "invalid_message",
+            format!("Failed to parse message: {}", e),
+            None,
+        )
     })?;
 
     match message {
+        IpcMessage::Join(join) => {
+            let last_seen = join.last_seen_seq;
+            let member = state
+                .join(connection_id, join)
+                .await
+                .map_err(|e| ipc_error(error_code(&e, "join_failed"), e, None))?;
+            info!(
+                "Connection {} joined session {} as {:?}",
+                connection_id, member.session_id, member.role
+            );
+            let _ = state.send_to_connection(
+                connection_id,
+                IpcMessage::Joined(RoomJoined {
+                    session_id: member.session_id.clone(),
+                    connection_id,
+                    role: member.role,
+                    player_id: member.player_id.clone(),
+                    seq: state.last_seq(&member.session_id),
+                }),
+            );
+            if let Some(last_seen) = last_seen {
+                let replayed = state
+                    .replay(connection_id, &member, last_seen)
+                    .await
+                    .map_err(|e| ipc_error("replay_failed", e, None))?;
+                info!(
+                    "Replayed {} messages to connection {} after seq {}",
+                    replayed, connection_id, last_seen
+                );
+            }
+        }
+        IpcMessage::Ack(ack) => {
+            if !state.ack(connection_id, &ack).await {
+                warn!(
+                    "Connection {} acked unknown seq {} in session {}",
+                    connection_id, ack.seq, ack.session_id
+                );
+            }
+        }
+        IpcMessage::Leave(leave) => {
+            if state.leave(connection_id).await.is_some() {
+                info!(
+                    "Connection {} left session {}",
+                    connection_id, leave.session_id
+                );
+            }
+        }
         IpcMessage::PlayerAction(action) => {
             info!(
                 "Received PlayerAction: {} from {}",
                 action.kind, action.player_id
             );
-            // Process player action through orchestrator
+            let member = state
+                .authorize_action(connection_id, &action)
+                .await
+                .map_err(|e| ipc_error(error_code(&e, "processing_error"), e, None))?;
+            // Process player action through orchestrator; the DM acts for anyone
             if let Some(ref orchestrator) = state.orchestrator {
-                if let Err(e) = orchestrator.process_player_action(action).await {
+                let outcome = if member.role == ClientRole::Dm {
+                    orchestrator.process_dm_action(action).await
+                } else {
+                    orchestrator.process_player_action(action).await
+                };
+                if let Err(e) = outcome {
                     error!("Failed to process PlayerAction: {}", e);
-                    state.broadcast(IpcMessage::Error(crate::communication::IpcError {
-                        code: "processing_error".to_string(),
-                        message: e.to_string(),
-                        request_id: None,
-                    }))?;
+                    return Err(ipc_error(error_code(&e, "processing_error"), e, None));
                 }
             } else {
                 warn!("Orchestrator not available, cannot process PlayerAction");
@@ -360,23 +1275,75 @@ async fn handle_incoming_message(state: &CommunicationState, text: &str) -> Resu
                 result.request_id, result.total
             );
 
+            let request_id = result.request_id.clone();
+            state
+                .authorize_roll(connection_id, &result)
+                .await
+                .map_err(|e| {
+                    ipc_error(error_code(&e, "roll_rejected"), e, Some(request_id.clone()))
+                })?;
             // Process roll result through orchestrator
             if let Some(ref orchestrator) = state.orchestrator {
                 if let Err(e) = orchestrator.process_roll_result(result).await {
                     error!("Failed to process RollResult: {}", e);
-                    state.broadcast(IpcMessage::Error(crate::communication::IpcError {
-                        code: "processing_error".to_string(),
-                        message: e.to_string(),
-                        request_id: None,
-                    }))?;
+                    return Err(ipc_error("roll_rejected", e, Some(request_id)));
                 }
             } else {
                 warn!("Orchestrator not available, cannot process RollResult");
             }
         }
+        IpcMessage::DmCommand(message) => {
+            info!(
+                "Received DmCommand for session {}: {:?}",
+                message.session_id, message.command
+            );
+            let member = state
+                .authorize_dm_command(connection_id, &message.session_id)
+                .await
+                .map_err(|e| ipc_error(error_code(&e, "command_rejected"), e, None))?;
+            if let Some(ref orchestrator) = state.orchestrator {
+                let author = member.player_id.as_deref().unwrap_or(DM_AUTHOR);
+                if let Err(e) = orchestrator.process_dm_command(message, author).await {
+                    error!("Failed to process DmCommand: {}", e);
+                    return Err(ipc_error(error_code(&e, "command_rejected"), e, None));
+                }
+            } else {
+                warn!("Orchestrator not available, cannot process DmCommand");
+            }
+        }
+        IpcMessage::IssueToken(request) => {
+            let rejected =
+                |e: OrchestratorError| ipc_error(error_code(&e, "token_rejected"), e, None);
+            state
+                .authorize_dm_command(connection_id, &request.session_id)
+                .await
+                .map_err(rejected)?;
+            let token = state
+                .issue_join_token(
+                    &request.session_id,
+                    request.role,
+                    request.player_id.as_deref(),
+                )
+                .await
+                .map_err(rejected)?;
+            info!(
+                "Issued a {:?} join token for session {}",
+                request.role, request.session_id
+            );
+            let _ = state.send_to_connection(
+                connection_id,
+                IpcMessage::TokenIssued(JoinTokenIssued {
+                    session_id: request.session_id,
+                    role: request.role,
+                    player_id: request.player_id,
+                    url: auth::join_url("", &token),
+                    token,
+                }),
+            );
+        }
         IpcMessage::Ping => {
             // Respond with pong
-            state.broadcast(IpcMessage::Pong)?;
+            let _ = state.send_to_connection(connection_id, IpcMessage::Pong);
         }
         _ => {
             warn!("Unexpected message type from client");
@@ -403,3 +1370,389 @@ pub async fn start_server(state: CommunicationState, port: u16) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Join request carrying a freshly issued token
+    async fn join(
+        state: &CommunicationState,
+        session_id: &str,
+        role: ClientRole,
+        player_id: Option<&str>,
+    ) -> JoinRoom {
+        JoinRoom {
+            session_id: session_id.to_string(),
+            role,
+            player_id: player_id.map(String::from),
+            last_seen_seq: None,
+            token: Some(
+                state
+                    .issue_join_token(session_id, role, player_id)
+                    .await
+                    .unwrap(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rooms_scope_deliveries_by_session_and_identity() {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let (table, other) = {
+            let mut sm = session_manager.write().await;
+            (sm.create_session(), sm.create_session())
+        };
+        let state = CommunicationState::new(session_manager);
+
+        assert!(state
+            .issue_join_token("missing", ClientRole::Dm, None)
+            .await
+            .is_err());
+        assert!(state
+            .issue_join_token(&table, ClientRole::Player, None)
+            .await
+            .is_err());
+        for (id, session_id, role, player_id) in [
+            (1, &table, ClientRole::Dm, None),
+            (2, &table, ClientRole::Player, Some("alice")),
+            (3, &table, ClientRole::Spectator, None),
+            (4, &other, ClientRole::Player, Some("alice")),
+        ] {
+            let request = join(&state, session_id, role, player_id).await;
+            state.join(id, request).await.unwrap();
+        }
+
+        // Tokens are for one session, and stop working once revoked
+        let mut request = join(&state, &other, ClientRole::Dm, None).await;
+        request.session_id = table.clone();
+        assert!(state.join(6, request.clone()).await.is_err());
+        request.session_id = other.clone();
+        assert!(
+            state
+                .revoke_join_token(request.token.as_deref().unwrap())
+                .await
+        );
+        assert!(state.join(6, request).await.is_err());
+        assert_eq!(state.room_members(&table).await.len(), 3);
+
+        let reached = |audience: Audience| {
+            let state = state.clone();
+            async move {
+                let mut ids = Vec::new();
+                for id in 1..=5 {
+                    if state.delivers_to(id, &audience).await {
+                        ids.push(id);
+                    }
+                }
+                ids
+            }
+        };
+        assert_eq!(reached(Audience::Everyone).await, vec![1, 2, 3, 4, 5]);
+        assert_eq!(
+            reached(Audience::Session(table.clone())).await,
+            vec![1, 2, 3]
+        );
+        assert_eq!(reached(Audience::Dm(table.clone())).await, vec![1]);
+        assert_eq!(
+            reached(Audience::Player {
+                session_id: table.clone(),
+                player_id: "alice".to_string(),
+            })
+            .await,
+            vec![2]
+        );
+        assert_eq!(reached(Audience::Connection(5)).await, vec![5]);
+
+        // Leaving the room stops session messages
+        state.leave(2).await;
+        assert_eq!(reached(Audience::Session(table)).await, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_fog_of_war_goes_to_its_player_and_the_dm() {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_id = session_manager.write().await.create_session();
+        let state = CommunicationState::new(session_manager.clone());
+        for (id, role, player_id) in [
+            (1, ClientRole::Dm, None),
+            (2, ClientRole::Player, Some("Aria")),
+            (3, ClientRole::Player, Some("Borin")),
+            (4, ClientRole::Spectator, None),
+        ] {
+            let request = join(&state, &session_id, role, player_id).await;
+            state.join(id, request).await.unwrap();
+        }
+        let mut manager = session_manager.write().await;
+        let session = manager.get_session_mut(&session_id).unwrap();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Crypt".to_string());
+        engine
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(game_engine::Grid::new(6, 6));
+        let aria = game_engine::Actor::new("Aria".to_string(), ActorType::Player);
+        let aria_id = aria.id.to_string();
+        engine.add_actor_to_scene(scene_id, aria).unwrap();
+
+        let mut rx = state.subscribe();
+        state.send_fog_of_war(session).await.unwrap();
+        let mut reached = Vec::new();
+        while let Ok(delivery) = rx.try_recv() {
+            match &delivery.message {
+                IpcMessage::FogOfWar(update) => assert_eq!(update.actor_id, aria_id),
+                other => panic!("Expected fog of war, got {:?}", other),
+            }
+            for id in 1..=4 {
+                if state.delivers_to(id, &delivery.audience).await {
+                    reached.push(id);
+                }
+            }
+        }
+        reached.sort_unstable();
+        assert_eq!(reached, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_dm_issues_a_token_that_joins_through_its_url() {
+        use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_id = session_manager.write().await.create_session();
+        let state = CommunicationState::new(session_manager);
+        let dm_token = state
+            .issue_join_token(&session_id, ClientRole::Dm, None)
+            .await
+            .unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, create_router(state)).await });
+
+        let connect = |url: String| async move {
+            let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+            // Welcome
+            socket.next().await.unwrap().unwrap();
+            socket
+        };
+        let ask = |message: serde_json::Value| WsMessage::Text(message.to_string());
+        macro_rules! reply {
+            ($socket:expr) => {
+                match $socket.next().await.unwrap().unwrap() {
+                    WsMessage::Text(text) => serde_json::from_str::<IpcMessage>(&text).unwrap(),
+                    other => panic!("Expected text, got {:?}", other),
+                }
+            };
+        }
+        let join = |role: &str| {
+            ask(serde_json::json!({"type": "join", "session_id": session_id, "role": role}))
+        };
+        let issue = ask(serde_json::json!({
+            "type": "issue-token",
+            "session_id": session_id,
+            "role": "player",
+            "player_id": "Aria",
+        }));
+
+        // The DM connects with the URL the host gave them and joins without the token
+        let mut dm = connect(auth::join_url(&base, &dm_token)).await;
+        dm.send(join("dm")).await.unwrap();
+        assert!(matches!(reply!(dm), IpcMessage::Joined(j) if j.role == ClientRole::Dm));
+
+        dm.send(issue.clone()).await.unwrap();
+        let IpcMessage::TokenIssued(issued) = reply!(dm) else {
+            panic!("Expected a token");
+        };
+        assert_eq!(issued.url, format!("/ws?token={}", issued.token));
+
+        let mut aria = connect(format!("{}{}", base, issued.url)).await;
+        aria.send(join("player")).await.unwrap();
+        let IpcMessage::Joined(joined) = reply!(aria) else {
+            panic!("Expected to join");
+        };
+        assert_eq!(joined.player_id.as_deref(), Some("Aria"));
+
+        // Only the DM hands out tokens, and a bare connection can't join
+        aria.send(issue).await.unwrap();
+        assert!(matches!(reply!(aria), IpcMessage::Error(e) if e.code == "forbidden"));
+        let mut stranger = connect(format!("{}/ws", base)).await;
+        stranger.send(join("player")).await.unwrap();
+        assert!(matches!(reply!(stranger), IpcMessage::Error(e) if e.code == "unauthorized"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_go_back_to_the_sender_with_the_request_id() {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_id = session_manager.write().await.create_session();
+        let state = CommunicationState::new(session_manager.clone());
+        let orchestrator = Arc::new(Orchestrator::new(session_manager, Arc::new(state.clone())));
+        let state = state.with_orchestrator(orchestrator);
+        let mut rx = state.subscribe();
+
+        let token = state
+            .issue_join_token(&session_id, ClientRole::Dm, None)
+            .await
+            .unwrap();
+        let joined = serde_json::json!({
+            "type": "join",
+            "session_id": session_id,
+            "role": "dm",
+            "token": token,
+        });
+        let error =
+            handle_incoming_message(&state, 7, &joined.to_string().replace(&token, "guess"))
+                .await
+                .unwrap_err();
+        assert_eq!(error.code, "unauthorized");
+        handle_incoming_message(&state, 7, &joined.to_string())
+            .await
+            .unwrap();
+        let delivery = rx.recv().await.unwrap();
+        assert_eq!(delivery.audience, Audience::Connection(7));
+        assert!(matches!(delivery.message, IpcMessage::Joined(ref j) if j.connection_id == 7));
+
+        let orphan = serde_json::json!({
+            "type": "roll-result",
+            "session_id": session_id,
+            "request_id": "nonexistent",
+            "actor_id": "alice",
+            "total": 12,
+            "natural": 12,
+            "breakdown": {},
+            "client_seed": null,
+            "timestamp": 0,
+        });
+        let error = handle_incoming_message(&state, 7, &orphan.to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "roll_rejected");
+        assert_eq!(error.request_id.as_deref(), Some("nonexistent"));
+
+        // Connections that haven't joined, or may only watch, can't act
+        let error = handle_incoming_message(&state, 8, &orphan.to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "unauthorized");
+        let spectator = join(&state, &session_id, ClientRole::Spectator, None).await;
+        state.join(8, spectator).await.unwrap();
+        let error = handle_incoming_message(&state, 8, &orphan.to_string())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "forbidden");
+
+        let error = handle_incoming_message(&state, 7, "not json")
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, "invalid_message");
+    }
+
+    #[tokio::test]
+    async fn test_rejoining_replays_missed_messages_and_acks_stop_resends() {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let session_id = session_manager.write().await.create_session();
+        {
+            let mut manager = session_manager.write().await;
+            let engine = manager
+                .get_session_mut(&session_id)
+                .unwrap()
+                .engine_session_mut()
+                .unwrap();
+            let scene_id = engine.create_scene("Vault".to_string());
+            engine
+                .add_actor_to_scene(
+                    scene_id,
+                    game_engine::Actor::new("alice".to_string(), ActorType::Player),
+                )
+                .unwrap();
+        }
+        let state = CommunicationState::new(session_manager);
+        let narration = |text: &str| {
+            IpcMessage::Narration(Narration {
+                session_id: session_id.clone(),
+                speaker_id: "dm".to_string(),
+                text: text.to_string(),
+                emotion: None,
+                tagged_for_tts: true,
+            })
+        };
+
+        // Sent while nobody is connected, still numbered and kept
+        state.broadcast(narration("The door creaks")).unwrap();
+        state
+            .send_to_dm(&session_id, narration("The mimic is hungry"))
+            .unwrap();
+        state
+            .broadcast(IpcMessage::RollRequest(RollRequest {
+                session_id: session_id.clone(),
+                request_id: "r1".to_string(),
+                actor_id: "alice".to_string(),
+                roll_kind: "skill_check".to_string(),
+                skill: Some("perception".to_string()),
+                ability: None,
+                dc: Some(12),
+                formula_hint: Some("1d20".to_string()),
+                reason: "hearing the mimic".to_string(),
+                secret: false,
+            }))
+            .unwrap();
+        assert_eq!(state.last_seq(&session_id), 3);
+
+        let mut rx = state.subscribe();
+        let mut rejoin = join(&state, &session_id, ClientRole::Player, Some("alice")).await;
+        rejoin.last_seen_seq = Some(1);
+        handle_incoming_message(
+            &state,
+            9,
+            &serde_json::to_string(&IpcMessage::Join(rejoin)).unwrap(),
+        )
+        .await
+        .unwrap();
+        match rx.recv().await.unwrap().message {
+            IpcMessage::Joined(joined) => assert_eq!(joined.seq, 3),
+            other => panic!("Expected joined, got {:?}", other),
+        }
+        // The DM's private message is not replayed to a player
+        let replayed = rx.recv().await.unwrap();
+        assert_eq!(replayed.audience, Audience::Connection(9));
+        assert_eq!(replayed.seq, Some(3));
+        assert!(matches!(replayed.message, IpcMessage::RollRequest(_)));
+        let wire = serde_json::to_value(WireMessage {
+            seq: replayed.seq,
+            message: &replayed.message,
+        })
+        .unwrap();
+        assert_eq!(wire["type"], "roll-request");
+        assert_eq!(wire["seq"], 3);
+
+        // A client from before a restart can't catch up and gets the current state
+        let member = state.room_member(9).await.unwrap();
+        assert_eq!(state.replay(9, &member, 7).await.unwrap(), 2);
+        assert!(matches!(
+            rx.recv().await.unwrap().message,
+            IpcMessage::SceneUpdate(_)
+        ));
+        assert!(matches!(
+            rx.recv().await.unwrap().message,
+            IpcMessage::CombatUpdate(_)
+        ));
+
+        // The roll request is resent until acked
+        assert_eq!(state.resend_unacked(Duration::ZERO), 1);
+        let ack = Ack {
+            session_id: session_id.clone(),
+            seq: 3,
+        };
+        assert!(!state.ack(10, &ack).await);
+        // Only Alice rolls, so neither another player nor the DM can ack it for her
+        for (connection_id, role, player_id) in [
+            (11, ClientRole::Player, Some("bob")),
+            (12, ClientRole::Dm, None),
+        ] {
+            let request = join(&state, &session_id, role, player_id).await;
+            state.join(connection_id, request).await.unwrap();
+            assert!(!state.ack(connection_id, &ack).await);
+        }
+        assert!(state.ack(9, &ack).await);
+        assert_eq!(state.resend_unacked(Duration::ZERO), 0);
+    }
+}
@@ -0,0 +1,333 @@
+//! The human DM's controls over the AI pipeline
+//!
+//! With review on, LLM output (narration and INTENTs) is held until the DM approves or
+//! vetoes it. With the LLM paused nothing is sent to it: players' words are only recorded
+//! and engine prompts go to the DM instead. The DM can also settle rolls, edit hit
+//! points, conditions and positions, force a scene state and narrate directly. Every
+//! command is recorded in the session's history with the DM as its author, and so are
+//! the engine events it causes.
+
+use crate::error::{OrchestratorError, Result};
+use crate::fsm::SceneState;
+use crate::intent::executor::resolve_actor_id;
+use crate::session::GameSession;
+use game_engine::{EffectType, GameEvent, GridPos};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Author of console commands from a DM connection without a player id
+pub const DM_AUTHOR: &str = "dm";
+
+/// A command from the DM console
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DmCommand {
+    /// Hold LLM output for approval, or stop holding it
+    SetReview {
+        enabled: bool,
+    },
+    /// Narrate and execute held output
+    Approve {
+        batch_id: u64,
+    },
+    /// Drop held output without narrating or executing it
+    Veto {
+        batch_id: u64,
+    },
+    /// Settle a pending roll request with the DM's numbers
+    OverrideRoll {
+        request_id: String,
+        total: i32,
+        #[serde(default)]
+        natural: Option<i32>,
+    },
+    SetHp {
+        actor: String,
+        hp: i32,
+    },
+    AddCondition {
+        actor: String,
+        condition: String,
+        #[serde(default)]
+        duration_rounds: Option<u32>,
+    },
+    RemoveCondition {
+        actor: String,
+        condition: String,
+    },
+    /// Put a token on a square without moving it there
+    Place {
+        actor: String,
+        x: i32,
+        y: i32,
+    },
+    /// Change the scene state even where the state machine wouldn't allow it
+    ForceTransition {
+        state: SceneState,
+    },
+    /// Speak as the DM
+    Narrate {
+        text: String,
+    },
+    PauseLlm,
+    ResumeLlm,
+}
+
+/// LLM output waiting for the DM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeldOutput {
+    pub text: String,
+    /// Player whose action the output answers
+    pub issuer: Option<String>,
+}
+
+/// A session's console switches and the output held for review
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DmConsole {
+    pub llm_paused: bool,
+    pub review: bool,
+    held: BTreeMap<u64, HeldOutput>,
+    next_batch: u64,
+}
+
+impl DmConsole {
+    /// Hold output until the DM approves or vetoes it; returns its batch id
+    pub fn hold(&mut self, text: &str, issuer: Option<&str>) -> u64 {
+        self.next_batch += 1;
+        self.held.insert(
+            self.next_batch,
+            HeldOutput {
+                text: text.to_string(),
+                issuer: issuer.map(String::from),
+            },
+        );
+        self.next_batch
+    }
+
+    pub fn held(&self, batch_id: u64) -> Option<&HeldOutput> {
+        self.held.get(&batch_id)
+    }
+
+    /// Take held output out for approval or veto
+    pub fn release(&mut self, batch_id: u64) -> Result<HeldOutput> {
+        self.held
+            .remove(&batch_id)
+            .ok_or_else(|| OrchestratorError::SessionError(format!("No held output {}", batch_id)))
+    }
+}
+
+fn actor_id(session: &GameSession, actor: &str) -> Result<Uuid> {
+    resolve_actor_id(session, actor)
+        .ok_or_else(|| OrchestratorError::SessionError(format!("Actor not found: {}", actor)))
+}
+
+/// Apply the part of a command that lives in the session: state edits, the scene state
+/// and the console switches. Rolls, held output and narration are up to the
+/// orchestrator; replaying the history only needs this. The engine events it causes are
+/// recorded with `author` as their author.
+pub fn apply(session: &mut GameSession, command: &DmCommand, author: &str) -> Result<()> {
+    session.authored_by(author, |session| apply_command(session, command))
+}
+
+fn apply_command(session: &mut GameSession, command: &DmCommand) -> Result<()> {
+    let event = match command {
+        DmCommand::SetHp { actor, hp } => GameEvent::HitPointsSet {
+            actor_id: actor_id(session, actor)?,
+            hp: *hp,
+        },
+        DmCommand::AddCondition {
+            actor,
+            condition,
+            duration_rounds,
+        } => {
+            let target_id = actor_id(session, actor)?;
+            GameEvent::EffectApplied {
+                effect: session.new_effect(
+                    condition.clone(),
+                    EffectType::Condition(condition.clone()),
+                    target_id,
+                    *duration_rounds,
+                ),
+            }
+        }
+        DmCommand::RemoveCondition { actor, condition } => GameEvent::ConditionRemoved {
+            actor_id: actor_id(session, actor)?,
+            condition: condition.clone(),
+        },
+        DmCommand::Place { actor, x, y } => GameEvent::Placed {
+            actor_id: actor_id(session, actor)?,
+            position: GridPos { x: *x, y: *y },
+        },
+        DmCommand::ForceTransition { state } => return session.force_transition(*state),
+        DmCommand::SetReview { enabled } => {
+            session.console.review = *enabled;
+            return Ok(());
+        }
+        DmCommand::PauseLlm | DmCommand::ResumeLlm => {
+            session.console.llm_paused = matches!(command, DmCommand::PauseLlm);
+            return Ok(());
+        }
+        DmCommand::Approve { .. }
+        | DmCommand::Veto { .. }
+        | DmCommand::OverrideRoll { .. }
+        | DmCommand::Narrate { .. } => return Ok(()),
+    };
+    session.dispatch(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_engine::{Actor, ActorType, EventQuery};
+
+    #[test]
+    fn test_edits_and_switches() {
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Crypt".to_string());
+        let ghoul = Actor::with_stats("Ghoul".to_string(), ActorType::Monster, 22, 12);
+        let ghoul_id = ghoul.id;
+        engine.add_actor_to_scene(scene_id, ghoul).unwrap();
+
+        let ghoul = |session: &GameSession| {
+            session
+                .engine_session()
+                .unwrap()
+                .get_actor(ghoul_id)
+                .cloned()
+                .unwrap()
+        };
+        apply(
+            &mut session,
+            &DmCommand::SetHp {
+                actor: "Ghoul".to_string(),
+                hp: 5,
+            },
+            DM_AUTHOR,
+        )
+        .unwrap();
+        assert_eq!(ghoul(&session).hp, 5);
+        apply(
+            &mut session,
+            &DmCommand::Place {
+                actor: "Ghoul".to_string(),
+                x: 3,
+                y: 1,
+            },
+            DM_AUTHOR,
+        )
+        .unwrap();
+        assert_eq!(ghoul(&session).grid_position(), GridPos { x: 3, y: 1 });
+        let paralyzed = |actor: &str| DmCommand::AddCondition {
+            actor: actor.to_string(),
+            condition: "paralyzed".to_string(),
+            duration_rounds: None,
+        };
+        apply(&mut session, &paralyzed("Ghoul"), DM_AUTHOR).unwrap();
+        assert!(apply(&mut session, &paralyzed("Lich"), DM_AUTHOR).is_err());
+        apply(
+            &mut session,
+            &DmCommand::RemoveCondition {
+                actor: "Ghoul".to_string(),
+                condition: "paralyzed".to_string(),
+            },
+            DM_AUTHOR,
+        )
+        .unwrap();
+        assert!(session.engine_session().unwrap().effects.is_empty());
+
+        // The history tells the DM's edits apart from rules results, even after undo
+        session
+            .dispatch(GameEvent::Damaged {
+                actor_id: ghoul_id,
+                amount: 1,
+                damage_type: None,
+            })
+            .unwrap();
+        session.undo().unwrap();
+        session.redo().unwrap();
+        let log = session.engine_log().unwrap();
+        let by_dm = log.query(EventQuery {
+            authored: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(by_dm.len(), 4);
+        assert!(by_dm.iter().all(|e| e.author.as_deref() == Some(DM_AUTHOR)));
+        assert_eq!(log.events().last().unwrap().author, None);
+
+        // Downtime can't go straight to combat, but the DM can force it
+        session
+            .transition_to(SceneState::DowntimePreparation)
+            .unwrap();
+        assert!(session.transition_to(SceneState::CombatTurnBased).is_err());
+        apply(
+            &mut session,
+            &DmCommand::ForceTransition {
+                state: SceneState::CombatTurnBased,
+            },
+            DM_AUTHOR,
+        )
+        .unwrap();
+        assert_eq!(session.current_state(), SceneState::CombatTurnBased);
+
+        apply(&mut session, &DmCommand::PauseLlm, DM_AUTHOR).unwrap();
+        apply(
+            &mut session,
+            &DmCommand::SetReview { enabled: true },
+            DM_AUTHOR,
+        )
+        .unwrap();
+        assert!(session.console.llm_paused && session.console.review);
+        apply(&mut session, &DmCommand::ResumeLlm, DM_AUTHOR).unwrap();
+        assert!(!session.console.llm_paused);
+    }
+
+    #[test]
+    fn test_held_output_is_released_once() {
+        let mut console = DmConsole::default();
+        let first = console.hold("The door creaks open.", Some("Aria"));
+        let second = console.hold("The goblin flees.", None);
+        assert_ne!(first, second);
+        assert_eq!(console.held(first).unwrap().issuer.as_deref(), Some("Aria"));
+        assert_eq!(console.release(second).unwrap().text, "The goblin flees.");
+        assert!(console.release(second).is_err());
+        assert!(console.held(first).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_conditions_match_the_recording() {
+        use crate::intent::IntentExecutor;
+        use crate::session::replay::{replay, verify};
+        use crate::session::{RecordedInput, SessionRecording};
+
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Crypt".to_string());
+        engine
+            .add_actor_to_scene(
+                scene_id,
+                Actor::with_stats("Ghoul".to_string(), ActorType::Monster, 22, 12),
+            )
+            .unwrap();
+        let recording = SessionRecording {
+            session_id: session.session_id.clone(),
+            initial_state: session.engine_session().unwrap().clone(),
+            inputs: vec![RecordedInput::DmOverride {
+                author: DM_AUTHOR.to_string(),
+                command: DmCommand::AddCondition {
+                    actor: "Ghoul".to_string(),
+                    condition: "frightened".to_string(),
+                    duration_rounds: Some(10),
+                },
+            }],
+            final_state: None,
+        };
+
+        let executor = IntentExecutor::new();
+        let first = replay(&recording, &executor).await.unwrap();
+        assert_eq!(first.engine_session().unwrap().effects.len(), 1);
+        let recorded = first.recording().unwrap();
+        verify(&recorded, &replay(&recorded, &executor).await.unwrap()).unwrap();
+    }
+}
@@ -23,12 +23,24 @@ pub enum OrchestratorError {
     #[error("Communication error: {0}")]
     CommunicationError(String),
 
+    #[error("Not authorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Service integration error: {0}")]
     ServiceError(String),
 
     #[error("Game engine error: {0}")]
     GameEngineError(String),
 
+    #[error("Game event rejected: {0}")]
+    EventRejected(String),
+
+    #[error("Nothing to {0}")]
+    HistoryEmpty(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
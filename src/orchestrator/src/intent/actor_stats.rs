@@ -12,14 +12,14 @@ pub struct ActorStats {
     pub hp: i32,
     pub max_hp: i32,
     pub ac: i32,
-    // Ability scores (defaults for now, will be extended when Character system is implemented)
+    // Ability scores
     pub strength: i32,
     pub dexterity: i32,
     pub constitution: i32,
     pub intelligence: i32,
     pub wisdom: i32,
     pub charisma: i32,
-    // Proficiency bonus (calculated from level, default for now)
+    // Proficiency bonus
     pub proficiency_bonus: i32,
     // Level (default for now)
     pub level: i32,
@@ -37,7 +37,7 @@ impl ActorStats {
             "charisma" | "cha" => self.charisma,
             _ => 10, // Default
         };
-        (score - 10) / 2
+        (score - 10).div_euclid(2)
     }
 
     /// Calculate attack bonus (STR or DEX modifier + proficiency)
@@ -50,6 +50,25 @@ impl ActorStats {
         ability_mod + self.proficiency_bonus
     }
 
+    /// Spellcasting ability modifier, taken as the best of INT, WIS and CHA
+    pub fn spellcasting_modifier(&self) -> i32 {
+        ["int", "wis", "cha"]
+            .iter()
+            .map(|ability| self.ability_modifier(ability))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Spell attack bonus (spellcasting modifier + proficiency)
+    pub fn spell_attack_bonus(&self) -> i32 {
+        self.spellcasting_modifier() + self.proficiency_bonus
+    }
+
+    /// Spell save DC (8 + spellcasting modifier + proficiency)
+    pub fn spell_save_dc(&self) -> i32 {
+        8 + self.spell_attack_bonus()
+    }
+
     /// Check if actor has proficiency in a skill
     /// TODO: This will be replaced with actual proficiency tracking
     pub fn has_proficiency(&self, _skill: &str) -> bool {
@@ -65,36 +84,32 @@ pub fn get_actor_stats(game_session: &GameSession, actor_id: &str) -> Result<Opt
     let actor_uuid = Uuid::parse_str(actor_id).ok();
 
     if let Some(engine) = game_session.engine_session() {
-        if let Some(scene) = engine.get_current_scene() {
+        if engine.get_current_scene().is_some() {
             // Find actor by UUID or name
             let actor = if let Some(uuid) = actor_uuid {
-                scene.get_actor(uuid)
+                engine.get_actor(uuid)
             } else {
-                scene
-                    .all_actors()
-                    .iter()
+                engine
+                    .scene_actors()
+                    .into_iter()
                     .find(|a| a.name == actor_id)
-                    .map(|a| a.id)
-                    .and_then(|id| scene.get_actor(id))
             };
 
             if let Some(actor) = actor {
-                // For now, use default ability scores (10 = +0 modifier)
-                // TODO: When Character system is implemented, get real scores
                 let stats = ActorStats {
                     actor_id: actor.id,
                     name: actor.name.clone(),
                     hp: actor.hp,
                     max_hp: actor.max_hp,
                     ac: actor.ac,
-                    strength: 10,
-                    dexterity: 10,
-                    constitution: 10,
-                    intelligence: 10,
-                    wisdom: 10,
-                    charisma: 10,
-                    proficiency_bonus: 2, // Default for level 1-4
-                    level: 1,             // Default
+                    strength: actor.abilities.strength,
+                    dexterity: actor.abilities.dexterity,
+                    constitution: actor.abilities.constitution,
+                    intelligence: actor.abilities.intelligence,
+                    wisdom: actor.abilities.wisdom,
+                    charisma: actor.abilities.charisma,
+                    proficiency_bonus: actor.proficiency_bonus,
+                    level: 1, // Default
                 };
                 return Ok(Some(stats));
             }
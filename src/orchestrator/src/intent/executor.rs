@@ -3,21 +3,49 @@
 //! Executes parsed INTENTs by calling appropriate services
 
 use super::actor_stats::{get_actor_stats, skill_ability_modifier};
+use super::parser::IntentParser;
+use super::resolver::{resolve_intent, resolve_weapon};
 use super::types::Intent;
-use crate::error::Result;
+use super::validation::{self, IntentError};
+use crate::error::{OrchestratorError, Result};
+use crate::rolls::{RollResume, SettledRoll};
+use crate::services::rules5e::{DamageResponse, SavingThrowRequest};
 use crate::services::{MemoryClient, Rules5eClient};
 use crate::session::GameSession;
-use rules5e_service::{DamageType, DiceExpression, WeaponDatabase};
+use game_engine::pathfinding::footprint_distance;
+use game_engine::travel::{TRAVEL_HOURS_PER_DAY, WATCH_HOURS};
+use game_engine::{
+    AbilityScores, ActionCost, ActorType, AdvantageScope, AdvantageToken, AttackVisibility,
+    Charges, EffectType, GameEvent, GameSession as EngineGameSession, Recharge, TravelPace,
+    WatchRolls, SQUARE_FEET,
+};
+use rules5e_service::{
+    DamageType, DiceExpression, DiceRoller, InitiativeBonus, InitiativeCombatant,
+    InitiativeRequest, InitiativeRoller, Item, ItemAction, ItemDatabase, ItemEffect, ItemRecharge,
+    RangeBand, RollMode, Spell, SpellAttackType, SpellCastRequest, SpellDatabase, SpellDuration,
+    SpellRange, TableContext, TableLibrary, TableRoll, WeaponDatabase, WeaponProperty,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Table rolled when a watch of travel turns up a random encounter
+const WILDERNESS_ENCOUNTERS: &str = "wilderness_encounters";
+
 /// INTENT Executor
 pub struct IntentExecutor {
     /// Rules5e service client for combat and dice resolution
     rules5e_client: Arc<Rules5eClient>,
     /// Memory service client for lore and rule queries
     memory_client: Arc<MemoryClient>,
+    /// Identical monsters (same stat block) share one initiative roll
+    group_initiative: bool,
+    /// Random encounter, rumor, trinket and weather tables
+    tables: Arc<TableLibrary>,
+    /// Spells that SPELL_CAST intents are checked against
+    spells: Arc<SpellDatabase>,
+    /// Creatures without slot data may cast leveled spells anyway
+    untracked_slots: bool,
 }
 
 impl IntentExecutor {
@@ -26,6 +54,10 @@ impl IntentExecutor {
         Self {
             rules5e_client: Arc::new(Rules5eClient::default()),
             memory_client: Arc::new(MemoryClient::default()),
+            group_initiative: false,
+            tables: Arc::new(TableLibrary::builtin()),
+            spells: Arc::new(SpellDatabase::new()),
+            untracked_slots: false,
         }
     }
 
@@ -37,9 +69,407 @@ impl IntentExecutor {
         Self {
             rules5e_client,
             memory_client,
+            group_initiative: false,
+            tables: Arc::new(TableLibrary::builtin()),
+            spells: Arc::new(SpellDatabase::new()),
+            untracked_slots: false,
         }
     }
 
+    /// Enable group initiative for identical monsters
+    pub fn with_group_initiative(mut self, enabled: bool) -> Self {
+        self.group_initiative = enabled;
+        self
+    }
+
+    /// Use a custom set of random tables
+    pub fn with_tables(mut self, tables: Arc<TableLibrary>) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    /// Check spells in SPELL_CAST intents against this database
+    pub fn with_spells(mut self, spells: Arc<SpellDatabase>) -> Self {
+        self.spells = spells;
+        self
+    }
+
+    /// Let creatures without slot data (e.g. monsters whose stat block lists none) cast
+    /// leveled spells; by default they're rejected
+    pub fn with_untracked_slots(mut self, enabled: bool) -> Self {
+        self.untracked_slots = enabled;
+        self
+    }
+
+    /// Roll on a random table with the party's region, time of day and level, and
+    /// queue the result for the DM to narrate
+    fn roll_table(
+        &self,
+        game_session: &mut GameSession,
+        table: &str,
+        region: Option<String>,
+        party_level: Option<u32>,
+    ) -> Result<TableRoll> {
+        let mut context = game_session
+            .engine_session()
+            .map(table_context)
+            .unwrap_or_default();
+        context.region = region.or(context.region);
+        context.party_level = party_level.or(context.party_level);
+
+        let seed = get_deterministic_seed(game_session);
+        let roll = self
+            .tables
+            .roll(table, &context, seed)
+            .map_err(|e| OrchestratorError::IntentExecutionError(e.to_string()))?;
+        tracing::info!(
+            "Rolled {} on {} (d{}): {}",
+            roll.roll,
+            roll.table,
+            roll.die,
+            roll.describe()
+        );
+        game_session.push_narration_prompt(format!(
+            "The {} table rolled {}: {}. Narrate this result without changing it.",
+            roll.table.replace('_', " "),
+            roll.roll,
+            roll.describe()
+        ));
+        Ok(roll)
+    }
+
+    /// Cast a spell: spend the caster's slot (or the item it comes from), roll its effect
+    /// through rules5e, resolve the attack or saving throw per target, apply damage,
+    /// healing, conditions and concentration as game events, and queue the outcome for
+    /// the DM to narrate
+    async fn cast_spell(
+        &self,
+        game_session: &mut GameSession,
+        actor: &str,
+        spell_name: &str,
+        source: SpellSource,
+        targets: &[String],
+    ) -> Result<()> {
+        let spell = self.spells.get_spell(spell_name).cloned().ok_or_else(|| {
+            OrchestratorError::IntentExecutionError(format!("Unknown spell: {}", spell_name))
+        })?;
+        let caster_id = resolve_actor_id(game_session, actor).ok_or_else(|| {
+            OrchestratorError::IntentExecutionError(format!("Caster {} not found in scene", actor))
+        })?;
+        let level = match &source {
+            SpellSource::Slot(level) => *level,
+            SpellSource::Item { level, .. } => level.unwrap_or(0),
+        };
+        let slot = (!spell.is_cantrip()).then(|| level.max(spell.level.value()));
+        let target_ids = spell_targets(game_session, &spell, caster_id, targets)?;
+
+        // Check the slot before rolling the spell; the engine spends it afterwards
+        let caster = game_session
+            .engine_session()
+            .and_then(|e| e.get_actor(caster_id));
+        if let (SpellSource::Slot(_), Some(caster), Some(level)) = (&source, caster, slot) {
+            if let Some(problem) = validation::slot_problem(caster, level, self.untracked_slots) {
+                return Err(OrchestratorError::IntentExecutionError(problem));
+            }
+        }
+
+        let stats = get_actor_stats(game_session, actor).ok().flatten();
+        let (attack_bonus, save_dc) = stats
+            .as_ref()
+            .map(|s| (s.spell_attack_bonus(), s.spell_save_dc()))
+            .unwrap_or((5, 13));
+        let request = SpellCastRequest {
+            spell_name: spell.name.clone(),
+            slot_level: slot,
+            caster_level: caster_level_for(slot, stats.as_ref().map_or(1, |s| s.level)),
+            spell_ability_modifier: stats.as_ref().map_or(0, |s| s.spellcasting_modifier()),
+            spell_save_dc: save_dc,
+            spell_attack_bonus: attack_bonus,
+            seed: get_deterministic_seed(game_session),
+        };
+        let cast = self.rules5e_client.cast_spell(&request).await?;
+
+        // Creatures with tracked slots spend one
+        let tracks_slots = game_session
+            .engine_session()
+            .and_then(|e| e.get_actor(caster_id))
+            .is_some_and(|a| !a.spell_slots.is_empty());
+        let source_text = match source {
+            SpellSource::Slot(_) => {
+                if let (Some(level), true) = (slot, tracks_slots) {
+                    game_session.dispatch(GameEvent::SpellSlotUsed {
+                        actor_id: caster_id,
+                        level,
+                    })?;
+                }
+                slot.map_or(String::new(), |level| {
+                    format!(" with a level {} slot", level)
+                })
+            }
+            SpellSource::Item { name, event, .. } => {
+                let charged = matches!(
+                    event,
+                    GameEvent::ItemUsed {
+                        charges: Some(_),
+                        ..
+                    }
+                );
+                game_session.dispatch(event)?;
+                let left = item_uses_left(game_session, caster_id, &name, charged);
+                format!(" from {} ({} left)", name, left)
+            }
+        };
+        // A caster concentrates on one spell at a time, recasting included
+        let concentrating = game_session.engine_session().is_some_and(|e| {
+            e.effects
+                .iter()
+                .any(|e| e.concentration_of == Some(caster_id))
+        });
+        if spell.concentration && concentrating {
+            game_session.dispatch(GameEvent::ConcentrationEnded {
+                actor_id: caster_id,
+            })?;
+        }
+
+        let mut outcomes = Vec::new();
+        for target_id in target_ids {
+            let target = target_id.to_string();
+            let target_name = actor_name(game_session, target_id);
+            let (share, outcome) = if spell.attack_type != SpellAttackType::None {
+                let visibility = attack_visibility(game_session, actor, &target)?;
+                let target_ac = game_session
+                    .engine_session()
+                    .and_then(|e| e.get_actor(target_id))
+                    .map_or(15, |a| a.ac)
+                    + cover_bonus(visibility);
+                let advantage = combine_roll_modes(
+                    check_advantage_conditions(game_session, actor, true),
+                    visibility.and_then(|v| v.roll_mode()),
+                );
+                let seed = get_deterministic_seed(game_session);
+                let attack = self
+                    .rules5e_client
+                    .resolve_attack(
+                        attack_bonus,
+                        target_ac,
+                        advantage.filter(|adv| *adv),
+                        advantage.filter(|adv| !*adv).map(|_| true),
+                        seed,
+                    )
+                    .await?;
+                if attack.hit {
+                    (SpellShare::Full, format!("hit ({})", attack.attack_roll))
+                } else {
+                    (SpellShare::None, format!("missed ({})", attack.attack_roll))
+                }
+            } else if let Some(save) = &spell.saving_throw {
+                let target_stats = get_actor_stats(game_session, &target).ok().flatten();
+                let request = SavingThrowRequest {
+                    ability: save.ability.clone(),
+                    ability_modifier: target_stats
+                        .as_ref()
+                        .map_or(0, |s| s.ability_modifier(&save.ability)),
+                    proficiency_bonus: target_stats.as_ref().map_or(2, |s| s.proficiency_bonus),
+                    has_proficiency: false,
+                    dc: save_dc,
+                    advantage: None,
+                    disadvantage: None,
+                    seed: get_deterministic_seed(game_session),
+                };
+                let result = self.rules5e_client.saving_throw(&request).await?;
+                // A legendary creature can choose to succeed on a failed save instead
+                let resisted = !result.success
+                    && game_session
+                        .engine_session()
+                        .and_then(|e| e.get_actor(target_id))
+                        .and_then(|a| a.legendary.as_ref())
+                        .is_some_and(|l| l.resistances_remaining > 0);
+                if resisted {
+                    game_session.dispatch(GameEvent::LegendaryResistanceUsed {
+                        actor_id: target_id,
+                    })?;
+                }
+                let saved = if resisted {
+                    format!(
+                        "failed the save ({}) but used Legendary Resistance",
+                        result.total
+                    )
+                } else {
+                    format!("saved ({})", result.total)
+                };
+                if !result.success && !resisted {
+                    (
+                        SpellShare::Full,
+                        format!("failed the save ({})", result.total),
+                    )
+                } else if save.success.to_lowercase().contains("half") {
+                    (SpellShare::Half, saved)
+                } else {
+                    (SpellShare::None, saved)
+                }
+            } else {
+                (SpellShare::Full, "affected".to_string())
+            };
+            let mut outcome = format!("{} {}", target_name, outcome);
+
+            let damage = match share {
+                SpellShare::Full => cast.damage,
+                SpellShare::Half => cast.damage.map(|d| d / 2),
+                SpellShare::None => None,
+            };
+            if let Some(amount) = damage.filter(|d| *d > 0) {
+                let damage_type = spell
+                    .effect
+                    .damage_type
+                    .clone()
+                    .unwrap_or_else(|| "force".to_string());
+                outcome.push_str(&format!(", {} {} damage", amount, damage_type));
+                apply_damage(
+                    game_session,
+                    &target,
+                    &DamageResponse {
+                        total_damage: amount,
+                        damage_type,
+                        breakdown: String::new(),
+                    },
+                )?;
+            }
+            if share == SpellShare::Full {
+                if let Some(amount) = cast.healing {
+                    outcome.push_str(&format!(", healed {}", amount));
+                    game_session.dispatch(GameEvent::Healed {
+                        actor_id: target_id,
+                        amount,
+                    })?;
+                }
+                let rounds = spell_duration_rounds(&spell.duration);
+                let effect_type = match &spell.effect.condition {
+                    Some(condition) => {
+                        outcome.push_str(&format!(", {}", condition));
+                        Some(EffectType::Condition(condition.clone()))
+                    }
+                    // Concentration needs an effect to end even when the spell leaves no condition
+                    None if spell.concentration => Some(EffectType::Buff(spell.name.clone(), 0)),
+                    None => None,
+                };
+                if let Some(effect_type) = effect_type {
+                    let mut effect =
+                        game_session.new_effect(spell.name.clone(), effect_type, target_id, rounds);
+                    if spell.concentration {
+                        effect = effect.with_concentration(caster_id);
+                    }
+                    game_session.dispatch(GameEvent::EffectApplied { effect })?;
+                }
+            }
+            outcomes.push(outcome);
+        }
+
+        tracing::info!(
+            "{} cast {}{}: {:?}",
+            actor,
+            spell.name,
+            source_text,
+            outcomes
+        );
+        game_session.push_narration_prompt(format!(
+            "{} cast {}{}{}. Narrate this result without changing it.",
+            actor_name(game_session, caster_id),
+            spell.name,
+            source_text,
+            if outcomes.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", outcomes.join("; "))
+            }
+        ));
+        Ok(())
+    }
+
+    /// Use an item from the actor's inventory: potions heal through rules5e, scrolls
+    /// and wands cast their spell, anything else is used up. The engine checks the
+    /// action it costs and spends the charge or item; the outcome is queued for the DM
+    /// to narrate.
+    async fn use_item(
+        &self,
+        game_session: &mut GameSession,
+        actor: &str,
+        item_id: &str,
+        targets: &[String],
+    ) -> Result<()> {
+        let actor_id = resolve_actor_id(game_session, actor).ok_or_else(|| {
+            OrchestratorError::IntentExecutionError(format!("Actor {} not found in scene", actor))
+        })?;
+        // Items missing from the catalog are plain consumables for the DM to describe
+        let item = ItemDatabase::get_item(item_id)
+            .unwrap_or_else(|| Item::new(item_id, ItemAction::Action, ItemEffect::None));
+        let event = GameEvent::ItemUsed {
+            actor_id,
+            item: item_id.to_string(),
+            cost: item_cost(item.action),
+            charges: item_charges(&item),
+        };
+
+        let outcome = match item.effect {
+            ItemEffect::Spell { spell, level } => {
+                let source = SpellSource::Item {
+                    name: item.name,
+                    level,
+                    event,
+                };
+                return self
+                    .cast_spell(game_session, actor, &spell, source, targets)
+                    .await;
+            }
+            ItemEffect::Healing { dice } => {
+                let seed = get_deterministic_seed(game_session);
+                let roll = self
+                    .rules5e_client
+                    .roll_dice(&dice, seed, None, None)
+                    .await?;
+                let amount = roll.result.total.max(0);
+                game_session.dispatch(event)?;
+                game_session.dispatch(GameEvent::Healed { actor_id, amount })?;
+                format!(
+                    "regaining {} hit points ({})",
+                    amount, roll.result.breakdown
+                )
+            }
+            ItemEffect::Effect {
+                name,
+                duration_rounds,
+            } => {
+                game_session.dispatch(event)?;
+                let effect = game_session.new_effect(
+                    name.clone(),
+                    EffectType::Buff(name.clone(), 0),
+                    actor_id,
+                    duration_rounds,
+                );
+                game_session.dispatch(GameEvent::EffectApplied { effect })?;
+                format!("gaining {}", name)
+            }
+            ItemEffect::None => {
+                game_session.dispatch(event)?;
+                String::new()
+            }
+        };
+
+        let left = item_uses_left(game_session, actor_id, item_id, item.charges.is_some());
+        tracing::info!("{} used {} ({} left): {}", actor, item_id, left, outcome);
+        game_session.push_narration_prompt(format!(
+            "{} used {}{} ({} left). Narrate this result without changing it.",
+            actor_name(game_session, actor_id),
+            item_id,
+            if outcome.is_empty() {
+                String::new()
+            } else {
+                format!(", {}", outcome)
+            },
+            left
+        ));
+        Ok(())
+    }
+
     /// Execute an INTENT
     ///
     /// Executes INTENTs by calling appropriate services:
@@ -48,17 +478,38 @@ impl IntentExecutor {
     /// - Lore/Rule queries -> memory-service
     /// - Asset generation -> Art Daemon (future)
     /// - State transitions -> FSM
+    ///
+    /// Loose names ("the goblin archer", "Espada Longa") are resolved first wherever
+    /// they match exactly one creature, weapon, spell or item.
     pub async fn execute(&self, intent: &Intent, game_session: &mut GameSession) -> Result<()> {
+        let (resolved, _) = resolve_intent(intent, game_session, &self.spells);
+        let intent = &resolved;
         match intent {
             // Combat INTENTs
-            Intent::CombatStart { reason } => {
-                tracing::info!("Combat starting: {:?}", reason);
+            Intent::CombatStart { reason, surprised } => {
+                tracing::info!("Combat starting: {:?} (surprised: {:?})", reason, surprised);
                 // Transition FSM to CombatTurnBased (this will sync engine session)
                 game_session.transition_to(crate::fsm::SceneState::CombatTurnBased)?;
 
-                // Start combat in engine session
-                if let Some(engine) = game_session.engine_session_mut() {
-                    engine.start_combat()?;
+                let surprised_ids: Vec<Uuid> = surprised
+                    .iter()
+                    .filter_map(|name| {
+                        let id = resolve_actor_id(game_session, name);
+                        if id.is_none() {
+                            tracing::warn!("Surprised actor {} not found in scene", name);
+                        }
+                        id
+                    })
+                    .collect();
+                let seed = get_deterministic_seed(game_session);
+
+                // Roll initiative through rules5e and start combat in engine session
+                if let Some(engine) = game_session.engine_session() {
+                    let order = roll_initiative(engine, self.group_initiative, seed)?;
+                    game_session.dispatch(GameEvent::CombatStarted {
+                        order,
+                        surprised: surprised_ids,
+                    })?;
                 }
             }
 
@@ -68,11 +519,9 @@ impl IntentExecutor {
                 // Default to Exploration after combat (this will sync engine session)
                 game_session.transition_to(crate::fsm::SceneState::Exploration)?;
 
-                // End combat in engine session (already handled by transition_to, but explicit here)
-                if let Some(engine) = game_session.engine_session_mut() {
-                    if let Some(scene) = engine.get_current_scene_mut() {
-                        scene.end_combat();
-                    }
+                // Combat itself was ended by transition_to; pick up spent ammunition
+                if game_session.engine_session().is_some() {
+                    game_session.dispatch(GameEvent::AmmunitionRecovered)?;
                 }
             }
 
@@ -90,22 +539,25 @@ impl IntentExecutor {
                     move_required
                 );
 
+                // On a gridded scene the attacker has to be (or get) within reach
+                move_into_reach(game_session, actor, target, weapon, *move_required)?;
+
                 // Get actor and target from game session
                 let (attack_bonus, target_ac) = if let Some(engine) = game_session.engine_session()
                 {
-                    if let Some(scene) = engine.get_current_scene() {
+                    if engine.get_current_scene().is_some() {
                         // Try to parse actor IDs as UUIDs, fallback to name lookup
                         let actor_uuid = Uuid::parse_str(actor).ok().or_else(|| {
-                            scene
-                                .all_actors()
+                            engine
+                                .scene_actors()
                                 .iter()
                                 .find(|a| a.name == actor.as_str())
                                 .map(|a| a.id)
                         });
 
                         let target_uuid = Uuid::parse_str(&target).ok().or_else(|| {
-                            scene
-                                .all_actors()
+                            engine
+                                .scene_actors()
                                 .iter()
                                 .find(|a| a.name == target.as_str())
                                 .map(|a| a.id)
@@ -114,7 +566,7 @@ impl IntentExecutor {
                         if let (Some(_actor_id), Some(target_id)) = (actor_uuid, target_uuid) {
                             // Get actor stats
                             let actor_stats = get_actor_stats(game_session, actor).ok().flatten();
-                            let target_obj = scene.get_actor(target_id);
+                            let target_obj = engine.get_actor(target_id);
 
                             if let Some(target_actor) = target_obj {
                                 let attack_bonus = actor_stats
@@ -138,10 +590,19 @@ impl IntentExecutor {
 
                 // Use placeholder values if not found
                 let attack_bonus = attack_bonus.unwrap_or(5);
-                let target_ac = target_ac.unwrap_or(15);
-
-                // Check for advantage/disadvantage conditions
-                let advantage = check_advantage_conditions(game_session, actor, true);
+                let visibility = attack_visibility(game_session, actor, target)?;
+                let target_ac = target_ac.unwrap_or(15) + cover_bonus(visibility);
+
+                // Check for advantage/disadvantage conditions (including Help, cover and light)
+                let token_key = advantage_token_key(game_session, actor, Some(target));
+                let advantage = apply_advantage_token(
+                    combine_roll_modes(
+                        check_advantage_conditions(game_session, actor, true),
+                        visibility.and_then(|v| v.roll_mode()),
+                    ),
+                    game_session,
+                    token_key,
+                );
                 let disadvantage = advantage.map(|adv| !adv);
                 let seed = get_deterministic_seed(game_session);
 
@@ -162,6 +623,7 @@ impl IntentExecutor {
                     .await
                 {
                     Ok(attack_result) => {
+                        consume_advantage_token(game_session, token_key)?;
                         tracing::info!(
                             "Attack result: hit={}, critical={}, roll={}",
                             attack_result.hit,
@@ -184,7 +646,11 @@ impl IntentExecutor {
 
                             match self
                                 .rules5e_client
-                                .calculate_damage(&damage_expr, &damage_type, None)
+                                .calculate_damage(
+                                    &damage_expr,
+                                    &damage_type,
+                                    get_deterministic_seed(game_session),
+                                )
                                 .await
                             {
                                 Ok(damage_result) => {
@@ -194,31 +660,8 @@ impl IntentExecutor {
                                         damage_result.damage_type
                                     );
 
-                                    // Apply damage to target in game session
-                                    if let Some(engine) = game_session.engine_session_mut() {
-                                        if let Some(scene) = engine.get_current_scene_mut() {
-                                            // Find target by name or ID
-                                            if let Some(target_actor) = scene
-                                                .all_actors()
-                                                .iter()
-                                                .find(|a| {
-                                                    a.name == target.as_str()
-                                                        || a.id.to_string() == target.as_str()
-                                                })
-                                                .map(|a| a.id)
-                                                .and_then(|id| scene.get_actor_mut(id))
-                                            {
-                                                target_actor
-                                                    .take_damage(damage_result.total_damage);
-                                                tracing::info!(
-                                                    "Applied {} damage to {}, HP now: {}",
-                                                    damage_result.total_damage,
-                                                    target,
-                                                    target_actor.hp
-                                                );
-                                            }
-                                        }
-                                    }
+                                    // Record the damage against the target
+                                    apply_damage(game_session, target, &damage_result)?;
                                 }
                                 Err(e) => {
                                     tracing::error!("Failed to calculate damage: {}", e);
@@ -255,10 +698,26 @@ impl IntentExecutor {
                     .as_ref()
                     .map(|s| s.attack_bonus(true)) // Use DEX for ranged
                     .unwrap_or(5);
-                let target_ac = target_stats.as_ref().map(|s| s.ac).unwrap_or(15);
-
-                // Check for advantage/disadvantage conditions
-                let advantage = check_advantage_conditions(game_session, actor, true);
+                let visibility = attack_visibility(game_session, actor, target)?;
+                let target_ac =
+                    target_stats.as_ref().map(|s| s.ac).unwrap_or(15) + cover_bonus(visibility);
+                let range_mode = check_ranged_attack(game_session, actor, target, weapon)?;
+                let ammunition = ammunition_to_spend(game_session, actor, weapon)?;
+
+                // Check for advantage/disadvantage conditions (including Help, cover, light
+                // and range)
+                let token_key = advantage_token_key(game_session, actor, Some(target));
+                let advantage = apply_advantage_token(
+                    combine_roll_modes(
+                        combine_roll_modes(
+                            check_advantage_conditions(game_session, actor, true),
+                            visibility.and_then(|v| v.roll_mode()),
+                        ),
+                        range_mode,
+                    ),
+                    game_session,
+                    token_key,
+                );
                 let disadvantage = advantage.map(|adv| !adv);
                 let seed = get_deterministic_seed(game_session);
 
@@ -279,6 +738,14 @@ impl IntentExecutor {
                     .await
                 {
                     Ok(attack_result) => {
+                        consume_advantage_token(game_session, token_key)?;
+                        if let Some((actor_id, ammunition)) = ammunition {
+                            game_session.dispatch(GameEvent::AmmunitionSpent {
+                                actor_id,
+                                ammunition: ammunition.to_string(),
+                            })?;
+                            tracing::info!("{} fired a {}", actor, ammunition);
+                        }
                         tracing::info!(
                             "Ranged attack result: hit={}, critical={}, roll={}",
                             attack_result.hit,
@@ -300,7 +767,11 @@ impl IntentExecutor {
 
                             match self
                                 .rules5e_client
-                                .calculate_damage(&damage_expr, &damage_type, None)
+                                .calculate_damage(
+                                    &damage_expr,
+                                    &damage_type,
+                                    get_deterministic_seed(game_session),
+                                )
                                 .await
                             {
                                 Ok(damage_result) => {
@@ -310,31 +781,8 @@ impl IntentExecutor {
                                         damage_result.damage_type
                                     );
 
-                                    // Apply damage to target in game session
-                                    if let Some(engine) = game_session.engine_session_mut() {
-                                        if let Some(scene) = engine.get_current_scene_mut() {
-                                            // Find target by name or ID
-                                            if let Some(target_actor) = scene
-                                                .all_actors()
-                                                .iter()
-                                                .find(|a| {
-                                                    a.name == target.as_str()
-                                                        || a.id.to_string() == target.as_str()
-                                                })
-                                                .map(|a| a.id)
-                                                .and_then(|id| scene.get_actor_mut(id))
-                                            {
-                                                target_actor
-                                                    .take_damage(damage_result.total_damage);
-                                                tracing::info!(
-                                                    "Applied {} damage to {}, HP now: {}",
-                                                    damage_result.total_damage,
-                                                    target,
-                                                    target_actor.hp
-                                                );
-                                            }
-                                        }
-                                    }
+                                    // Record the damage against the target
+                                    apply_damage(game_session, target, &damage_result)?;
                                 }
                                 Err(e) => {
                                     tracing::error!("Failed to calculate ranged damage: {}", e);
@@ -364,7 +812,14 @@ impl IntentExecutor {
                     area_center,
                     targets
                 );
-                // TODO: Call rules5e-service for spell resolution
+                self.cast_spell(
+                    game_session,
+                    actor,
+                    spell,
+                    SpellSource::Slot(*slot_level),
+                    targets,
+                )
+                .await?;
             }
 
             // Skill checks
@@ -421,8 +876,13 @@ impl IntentExecutor {
                     15 // Default DC
                 };
 
-                // Check for advantage/disadvantage conditions
-                let advantage = check_advantage_conditions(game_session, actor, false);
+                // Check for advantage/disadvantage conditions (including Help)
+                let token_key = advantage_token_key(game_session, actor, None);
+                let advantage = apply_advantage_token(
+                    check_advantage_conditions(game_session, actor, false),
+                    game_session,
+                    token_key,
+                );
                 let disadvantage = advantage.map(|adv| !adv); // If advantage is Some, disadvantage is opposite
 
                 // Get deterministic seed if available
@@ -447,6 +907,7 @@ impl IntentExecutor {
                     .await
                 {
                     Ok(check_result) => {
+                        consume_advantage_token(game_session, token_key)?;
                         tracing::info!(
                             "Skill check result: success={}, roll={}, dc={}, margin={}",
                             check_result.success,
@@ -523,6 +984,92 @@ impl IntentExecutor {
                 }
             }
 
+            Intent::Travel { destination, pace } => {
+                tracing::info!("Travel to {} (pace: {:?})", destination, pace);
+                let engine = game_session.engine_session().ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError("No engine session".to_string())
+                })?;
+                if engine.in_combat() {
+                    return Err(OrchestratorError::IntentExecutionError(
+                        "Can't travel during combat".to_string(),
+                    ));
+                }
+                let journey = engine
+                    .travel
+                    .journey
+                    .as_ref()
+                    .filter(|j| j.destination.eq_ignore_ascii_case(destination));
+                let pace = match pace {
+                    Some(name) => TravelPace::parse(name).ok_or_else(|| {
+                        OrchestratorError::IntentExecutionError(format!("Unknown pace: {}", name))
+                    })?,
+                    None => journey.map(|j| j.pace).unwrap_or_default(),
+                };
+                if journey.map_or(true, |j| j.pace != pace) {
+                    game_session.dispatch(GameEvent::TravelStarted {
+                        destination: destination.clone(),
+                        pace,
+                    })?;
+                }
+
+                // Travel watch by watch until arrival, an encounter or the end of the
+                // day's travel; traveling again the same day is a forced march
+                loop {
+                    let seed = get_deterministic_seed(game_session);
+                    let rolls = match game_session.engine_session() {
+                        Some(engine) => roll_watch(engine, seed)?,
+                        None => break,
+                    };
+                    game_session.dispatch(GameEvent::WatchTraveled { rolls })?;
+
+                    let Some(engine) = game_session.engine_session() else {
+                        break;
+                    };
+                    let Some(report) = engine.travel.last_watch.as_ref() else {
+                        break;
+                    };
+                    let time = engine.clock.world_time();
+                    tracing::info!(
+                        "Watch ending {:02}:{:02}: {} miles{}{}{}",
+                        time.hour,
+                        time.minute,
+                        report.miles,
+                        if report.lost { ", lost" } else { "" },
+                        if report.foraged { ", foraged" } else { "" },
+                        report
+                            .reached
+                            .as_ref()
+                            .map(|l| format!(", reached {}", l))
+                            .unwrap_or_default()
+                    );
+                    if !report.exhausted.is_empty() {
+                        tracing::info!("Forced march: {:?} gain exhaustion", report.exhausted);
+                    }
+                    if report.encounter {
+                        tracing::info!(
+                            "Random encounter (passive Perception {:+} at {:?} pace)",
+                            pace.passive_perception_modifier(),
+                            pace
+                        );
+                    }
+                    if report.arrived
+                        || report.encounter
+                        || engine.travel.hours_traveled_on(engine.clock.day())
+                            >= TRAVEL_HOURS_PER_DAY
+                    {
+                        break;
+                    }
+                }
+
+                let encounter = game_session
+                    .engine_session()
+                    .and_then(|e| e.travel.last_watch.as_ref())
+                    .is_some_and(|r| r.encounter);
+                if encounter && self.tables.get(WILDERNESS_ENCOUNTERS).is_some() {
+                    self.roll_table(game_session, WILDERNESS_ENCOUNTERS, None, None)?;
+                }
+            }
+
             // Social INTENTs
             Intent::NpcDialogue { npc_id, text } => {
                 tracing::info!("NPC dialogue: {} says: {}", npc_id, text);
@@ -537,6 +1084,62 @@ impl IntentExecutor {
                 // TODO: Update scene state, trigger visual updates
             }
 
+            Intent::ChangeScene { scene, actors } => {
+                let engine = game_session.engine_session().ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError("No game engine session".to_string())
+                })?;
+                let existing = Uuid::parse_str(scene)
+                    .ok()
+                    .filter(|id| engine.scenes.contains_key(id))
+                    .or_else(|| {
+                        engine
+                            .scenes
+                            .values()
+                            .find(|s| s.name.eq_ignore_ascii_case(scene))
+                            .map(|s| s.id)
+                    });
+                let actor_ids = if actors.is_empty() {
+                    engine.party()
+                } else {
+                    actors
+                        .iter()
+                        .map(|actor| {
+                            find_actor_id(engine, actor).ok_or_else(|| {
+                                OrchestratorError::IntentExecutionError(format!(
+                                    "Actor not found: {}",
+                                    actor
+                                ))
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                };
+
+                let scene_id = match existing {
+                    Some(scene_id) => scene_id,
+                    None => {
+                        let scene_id = game_session.next_id();
+                        game_session.dispatch(GameEvent::SceneCreated {
+                            scene_id,
+                            name: scene.clone(),
+                        })?;
+                        scene_id
+                    }
+                };
+                game_session.dispatch(GameEvent::SceneTransitioned {
+                    scene_id,
+                    actor_ids,
+                })?;
+                tracing::info!("Moved to scene {}", scene);
+            }
+
+            Intent::RollTable {
+                table,
+                region,
+                party_level,
+            } => {
+                self.roll_table(game_session, table, region.clone(), *party_level)?;
+            }
+
             // Query INTENTs
             Intent::LoreQuery { query, scope } => {
                 tracing::info!("Lore query: {} (scope: {:?})", query, scope);
@@ -635,9 +1238,13 @@ impl IntentExecutor {
             }
 
             // Action INTENTs
-            Intent::UseItem { actor, item_id } => {
-                tracing::info!("Use item: {} uses {}", actor, item_id);
-                // TODO: Call rules5e-service for item effect
+            Intent::UseItem {
+                actor,
+                item_id,
+                targets,
+            } => {
+                tracing::info!("Use item: {} uses {} on {:?}", actor, item_id, targets);
+                self.use_item(game_session, actor, item_id, targets).await?;
             }
 
             Intent::ReadyAction { actor, action } => {
@@ -652,10 +1259,10 @@ impl IntentExecutor {
                 // This is tracked in the turn state, which will be implemented with the Turn Engine
                 // For now, we just log it
                 if let Some(engine) = game_session.engine_session() {
-                    if let Some(scene) = engine.get_current_scene() {
+                    if engine.get_current_scene().is_some() {
                         // Find actor
-                        if let Some(actor_obj) = scene
-                            .all_actors()
+                        if let Some(actor_obj) = engine
+                            .scene_actors()
                             .iter()
                             .find(|a| a.name == *actor || a.id.to_string() == *actor)
                         {
@@ -675,10 +1282,10 @@ impl IntentExecutor {
                 // Disengage prevents opportunity attacks for this turn
                 // This is tracked in the turn state
                 if let Some(engine) = game_session.engine_session() {
-                    if let Some(scene) = engine.get_current_scene() {
+                    if engine.get_current_scene().is_some() {
                         // Find actor
-                        if let Some(actor_obj) = scene
-                            .all_actors()
+                        if let Some(actor_obj) = engine
+                            .scene_actors()
                             .iter()
                             .find(|a| a.name == *actor || a.id.to_string() == *actor)
                         {
@@ -692,34 +1299,214 @@ impl IntentExecutor {
                 }
             }
 
-            Intent::Help { actor, target } => {
-                tracing::info!("Help: {} helps {}", actor, target);
+            Intent::Help {
+                actor,
+                target,
+                against,
+            } => {
+                tracing::info!("Help: {} helps {} (against: {:?})", actor, target, against);
+
+                // Help grants the target advantage on its next ability check, or on its next
+                // attack against `against`, until the start of the helper's next turn
+                let helper_id = resolve_actor_id(game_session, actor).ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError(format!("Unknown actor: {}", actor))
+                })?;
+                let helped_id = resolve_actor_id(game_session, target).ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError(format!("Unknown target: {}", target))
+                })?;
+                let scope = match against {
+                    Some(enemy) => AdvantageScope::AttackAgainst(
+                        resolve_actor_id(game_session, enemy).ok_or_else(|| {
+                            OrchestratorError::IntentExecutionError(format!(
+                                "Unknown enemy: {}",
+                                enemy
+                            ))
+                        })?,
+                    ),
+                    None => AdvantageScope::AbilityCheck,
+                };
 
-                // Help action grants advantage to target's next attack or ability check
-                // This is tracked in the turn state
-                if let Some(engine) = game_session.engine_session() {
-                    if let Some(scene) = engine.get_current_scene() {
-                        // Find target actor
-                        if let Some(target_actor) = scene
-                            .all_actors()
-                            .iter()
-                            .find(|a| a.name == *target || a.id.to_string() == *target)
-                        {
-                            tracing::info!(
-                                "Actor {} helped {}, granting advantage to next attack/check",
-                                actor,
-                                target_actor.name
-                            );
-                            // TODO: When Turn Engine is implemented, mark target as having advantage from Help action
-                        }
-                    }
-                }
+                let token = AdvantageToken {
+                    id: game_session.next_id(),
+                    source_id: helper_id,
+                    scope,
+                };
+                game_session.dispatch(GameEvent::HelpGranted { helped_id, token })?;
+                tracing::info!(
+                    "Actor {} helped {}, granting advantage on {:?}",
+                    actor,
+                    target,
+                    scope
+                );
+            }
+            Intent::LegendaryAction {
+                actor,
+                action,
+                target,
+            } => {
+                tracing::info!(
+                    "Legendary action: {} uses {} (target: {:?})",
+                    actor,
+                    action,
+                    target
+                );
+
+                let actor_id = resolve_actor_id(game_session, actor).ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError(format!("Unknown actor: {}", actor))
+                })?;
+                game_session.dispatch(GameEvent::LegendaryActionUsed {
+                    actor_id,
+                    action: action.clone(),
+                })?;
+            }
+            Intent::LairAction { actor, action } => {
+                tracing::info!("Lair action: {} uses {}", actor, action);
+
+                let actor_id = resolve_actor_id(game_session, actor).ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError(format!("Unknown actor: {}", actor))
+                })?;
+                game_session.dispatch(GameEvent::LairActionUsed {
+                    actor_id,
+                    action: action.clone(),
+                })?;
             }
         }
 
         Ok(())
     }
 
+    /// Resolve a roll a player was asked for, picking up where the request was issued
+    pub async fn resume_roll(
+        &self,
+        roll: &SettledRoll,
+        game_session: &mut GameSession,
+    ) -> Result<()> {
+        let request = &roll.request;
+        let actor = resolve_actor_id(game_session, &request.actor_id).map_or_else(
+            || request.actor_id.clone(),
+            |id| actor_name(game_session, id),
+        );
+        let rolled = if roll.auto_rolled {
+            format!(
+                "{} didn't roll in time for {}, so the table rolled {} (natural {})",
+                actor, request.reason, roll.total, roll.natural
+            )
+        } else {
+            format!(
+                "{} rolled {} (natural {}) for {}",
+                actor, roll.total, roll.natural, request.reason
+            )
+        };
+
+        let outcome = match &roll.resume {
+            RollResume::Narrate => match (request.dc, roll.success()) {
+                (Some(dc), Some(success)) => format!(
+                    ": {} against DC {}",
+                    if success { "success" } else { "failure" },
+                    dc
+                ),
+                _ => String::new(),
+            },
+            RollResume::Attack { target, weapon } => {
+                let target_id = resolve_actor_id(game_session, target).ok_or_else(|| {
+                    OrchestratorError::IntentExecutionError(format!("Target not found: {}", target))
+                })?;
+                let target_name = actor_name(game_session, target_id);
+                let ac = game_session
+                    .engine_session()
+                    .and_then(|e| e.get_actor(target_id))
+                    .map_or(10, |a| a.ac);
+                // A natural 20 always hits and a natural 1 always misses
+                let hit = roll.natural == 20 || (roll.natural != 1 && roll.total >= ac);
+                if hit {
+                    let (damage_expr, damage_type) = get_weapon_damage(weapon, false)
+                        .unwrap_or_else(|| ("1d8+3".to_string(), "slashing".to_string()));
+                    let seed = get_deterministic_seed(game_session);
+                    let damage = self
+                        .rules5e_client
+                        .calculate_damage(&damage_expr, &damage_type, seed)
+                        .await?;
+                    apply_damage(game_session, target, &damage)?;
+                    format!(
+                        ", hitting {} (AC {}) for {} {} damage",
+                        target_name, ac, damage.total_damage, damage.damage_type
+                    )
+                } else {
+                    format!(", missing {} (AC {})", target_name, ac)
+                }
+            }
+        };
+
+        tracing::info!("{}{}", rolled, outcome);
+        game_session.push_narration_prompt(format!(
+            "{}{}. Narrate this result without changing it.",
+            rolled, outcome
+        ));
+        Ok(())
+    }
+
+    /// Execute an INTENT issued on behalf of player `issuer` (a character name or id).
+    /// Players may only act as their own character or creatures it controls; the DM
+    /// runs everything else.
+    pub async fn execute_as(
+        &self,
+        intent: &Intent,
+        game_session: &mut GameSession,
+        issuer: &str,
+    ) -> Result<()> {
+        let (resolved, _) = resolve_intent(intent, game_session, &self.spells);
+        check_control(game_session, &resolved, issuer)?;
+        self.execute(&resolved, game_session).await
+    }
+
+    /// Check an INTENT against the session without running it
+    pub fn validate(&self, intent: &Intent, game_session: &GameSession) -> Vec<IntentError> {
+        validation::validate(
+            intent,
+            game_session,
+            &self.spells,
+            &self.tables,
+            self.untracked_slots,
+        )
+    }
+
+    /// Parse, resolve, validate and execute the INTENTs of an LLM response one at a
+    /// time, each checked against the state the previous ones left. Only valid INTENTs run;
+    /// the rest come back as errors (execution failures are only logged).
+    pub async fn execute_output(
+        &self,
+        text: &str,
+        game_session: &mut GameSession,
+        issuer: Option<&str>,
+    ) -> Vec<IntentError> {
+        let mut errors = Vec::new();
+        for (index, parsed) in IntentParser::parse_each(text).into_iter().enumerate() {
+            let intent = match parsed {
+                Ok(intent) => intent,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            let (intent, mut problems) = resolve_intent(&intent, game_session, &self.spells);
+            if problems.is_empty() {
+                problems = self.validate(&intent, game_session);
+            }
+            if !problems.is_empty() {
+                errors.extend(problems.into_iter().map(|e| IntentError { index, ..e }));
+                continue;
+            }
+            let result = match issuer {
+                Some(issuer) => self.execute_as(&intent, game_session, issuer).await,
+                None => self.execute(&intent, game_session).await,
+            };
+            if let Err(e) = result {
+                tracing::error!("Failed to execute INTENT: {}", e);
+            }
+        }
+        errors
+    }
+
     /// Execute multiple INTENTs in sequence
     pub async fn execute_many(
         &self,
@@ -764,8 +1551,7 @@ fn get_weapon_damage(
     weapon_name: &Option<String>,
     use_versatile: bool,
 ) -> Option<(String, String)> {
-    let weapon_name = weapon_name.as_ref()?;
-    let weapon = WeaponDatabase::get_weapon(weapon_name)?;
+    let weapon = resolve_weapon(weapon_name.as_ref()?).ok()?;
     let damage_expr = weapon.calculate_damage(use_versatile);
     let damage_str = dice_expression_to_string(&damage_expr);
     let damage_type_str = damage_type_to_string(&weapon.damage_type);
@@ -804,13 +1590,601 @@ fn check_advantage_conditions(
     None
 }
 
+/// Helper function to roll initiative for every active, living actor in the current scene
+///
+/// Returns the turn order (highest first) with each actor's initiative total
+fn roll_initiative(
+    engine: &EngineGameSession,
+    group_monsters: bool,
+    seed: Option<u64>,
+) -> Result<Vec<(Uuid, i32)>> {
+    if engine.get_current_scene().is_none() {
+        return Err(OrchestratorError::IntentExecutionError(
+            "No current scene".to_string(),
+        ));
+    }
+
+    // Sort by id so the same seed always gives the same order
+    let mut actors: Vec<_> = engine
+        .scene_actors()
+        .into_iter()
+        .filter(|a| a.active && a.is_alive())
+        .collect();
+    actors.sort_by_key(|a| a.id);
+
+    let request = InitiativeRequest {
+        combatants: actors
+            .iter()
+            .map(|a| InitiativeCombatant {
+                id: a.id.to_string(),
+                dexterity_score: a.abilities.dexterity,
+                proficiency_bonus: a.proficiency_bonus,
+                bonuses: a
+                    .features
+                    .iter()
+                    .filter_map(|f| InitiativeBonus::from_feature(f))
+                    .collect(),
+                advantage: false,
+                group: if group_monsters && a.actor_type == ActorType::Monster {
+                    a.stat_block.clone()
+                } else {
+                    None
+                },
+            })
+            .collect(),
+        seed,
+    };
+
+    let result = InitiativeRoller::new()
+        .roll(&request)
+        .map_err(|e| OrchestratorError::ServiceError(format!("Initiative roll failed: {}", e)))?;
+
+    result
+        .order
+        .into_iter()
+        .map(|roll| {
+            tracing::info!(
+                "Initiative: {} rolled {} ({:+}) = {}",
+                roll.id,
+                roll.natural_roll,
+                roll.modifier,
+                roll.total
+            );
+            let actor_id = Uuid::parse_str(&roll.id).map_err(|e| {
+                OrchestratorError::IntentExecutionError(format!("Invalid actor id: {}", e))
+            })?;
+            Ok((actor_id, roll.total))
+        })
+        .collect()
+}
+
+/// Helper function to find an actor in the current scene by UUID or name
+pub(crate) fn resolve_actor_id(game_session: &GameSession, name_or_id: &str) -> Option<Uuid> {
+    let engine = game_session.engine_session()?;
+    let scene_id = engine.current_scene?;
+    if let Ok(uuid) = Uuid::parse_str(name_or_id) {
+        if engine
+            .get_actor(uuid)
+            .is_some_and(|a| a.scene_id == Some(scene_id))
+        {
+            return Some(uuid);
+        }
+    }
+    engine
+        .scene_actors()
+        .iter()
+        .find(|a| a.name == name_or_id)
+        .map(|a| a.id)
+}
+
+/// Helper function to check that `issuer` may act as the intent's creature: player
+/// characters answer to their player, controlled creatures to their controller
+fn check_control(game_session: &GameSession, intent: &Intent, issuer: &str) -> Result<()> {
+    let (Some(actor), Some(engine)) = (intent.actor(), game_session.engine_session()) else {
+        return Ok(());
+    };
+    let Some(actor) = find_actor_id(engine, actor).and_then(|id| engine.get_actor(id)) else {
+        return Ok(());
+    };
+    match owning_player(engine, actor.id) {
+        Some(owner_id) if find_actor_id(engine, issuer) != Some(owner_id) => {
+            Err(OrchestratorError::IntentExecutionError(format!(
+                "{} cannot act for {}",
+                issuer, actor.name
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Helper function to find the player character an actor answers to: itself, or the
+/// player character controlling it. `None` for the DM's creatures.
+pub(crate) fn owning_player(engine: &EngineGameSession, actor_id: Uuid) -> Option<Uuid> {
+    let actor = engine.get_actor(actor_id)?;
+    let owner_id = actor
+        .controlled_by
+        .as_ref()
+        .map_or(actor.id, |c| c.controller_id);
+    engine
+        .get_actor(owner_id)
+        .filter(|owner| owner.actor_type == ActorType::Player)
+        .map(|owner| owner.id)
+}
+
+/// Helper function to find an actor in any scene by UUID or name
+pub(crate) fn find_actor_id(engine: &EngineGameSession, name_or_id: &str) -> Option<Uuid> {
+    Uuid::parse_str(name_or_id)
+        .ok()
+        .filter(|id| engine.get_actor(*id).is_some())
+        .or_else(|| {
+            engine
+                .actors
+                .values()
+                .find(|a| a.name.eq_ignore_ascii_case(name_or_id))
+                .map(|a| a.id)
+        })
+}
+
+/// Helper function to record damage to a target (by name or ID) as a game event
+fn apply_damage(
+    game_session: &mut GameSession,
+    target: &str,
+    damage: &DamageResponse,
+) -> Result<()> {
+    let Some(target_id) = resolve_actor_id(game_session, target) else {
+        tracing::warn!("Damage target {} not found in scene", target);
+        return Ok(());
+    };
+    game_session.dispatch(GameEvent::Damaged {
+        actor_id: target_id,
+        amount: damage.total_damage,
+        damage_type: Some(damage.damage_type.clone()),
+    })?;
+    let hp = game_session
+        .engine_session()
+        .and_then(|e| e.get_actor(target_id))
+        .map(|a| a.hp);
+    tracing::info!(
+        "Applied {} damage to {}, HP now: {:?}",
+        damage.total_damage,
+        target,
+        hp
+    );
+    Ok(())
+}
+
+/// Helper function to find which advantage token a roll would use
+///
+/// Attacks look for a token against `attack_target`, anything else for an ability check token
+fn advantage_token_key(
+    game_session: &GameSession,
+    actor: &str,
+    attack_target: Option<&str>,
+) -> Option<(Uuid, AdvantageScope)> {
+    let actor_id = resolve_actor_id(game_session, actor)?;
+    let scope = match attack_target {
+        Some(target) => AdvantageScope::AttackAgainst(resolve_actor_id(game_session, target)?),
+        None => AdvantageScope::AbilityCheck,
+    };
+    Some((actor_id, scope))
+}
+
+/// Helper function to fold a held advantage token into the roll's advantage state
+///
+/// Advantage and disadvantage cancel out, so a token against an existing disadvantage
+/// results in a normal roll
+fn apply_advantage_token(
+    advantage: Option<bool>,
+    game_session: &GameSession,
+    token_key: Option<(Uuid, AdvantageScope)>,
+) -> Option<bool> {
+    let has_token = token_key
+        .and_then(|(actor_id, scope)| {
+            let engine = game_session.engine_session()?;
+            Some(engine.get_actor(actor_id)?.has_advantage_for(scope))
+        })
+        .unwrap_or(false);
+
+    match (advantage, has_token) {
+        (Some(false), true) => None,
+        (_, true) => Some(true),
+        (advantage, false) => advantage,
+    }
+}
+
+/// Helper function to use up an advantage token once its roll has been resolved
+fn consume_advantage_token(
+    game_session: &mut GameSession,
+    token_key: Option<(Uuid, AdvantageScope)>,
+) -> Result<()> {
+    let Some((actor_id, scope)) = token_key else {
+        return Ok(());
+    };
+    let has_token = game_session
+        .engine_session()
+        .and_then(|e| e.get_actor(actor_id))
+        .is_some_and(|a| a.has_advantage_for(scope));
+    if has_token {
+        game_session.dispatch(GameEvent::AdvantageUsed { actor_id, scope })?;
+        tracing::info!("Consumed advantage token of {} for {:?}", actor_id, scope);
+    }
+    Ok(())
+}
+
+/// Helper function to work out cover and sight between attacker and target on the grid
+///
+/// Returns `None` for scenes without a grid or unknown actors. Fails if the target
+/// has total cover and can't be attacked directly.
+fn attack_visibility(
+    game_session: &GameSession,
+    actor: &str,
+    target: &str,
+) -> Result<Option<AttackVisibility>> {
+    let (Some(actor_id), Some(target_id), Some(engine)) = (
+        resolve_actor_id(game_session, actor),
+        resolve_actor_id(game_session, target),
+        game_session.engine_session(),
+    ) else {
+        return Ok(None);
+    };
+    let visibility = engine.attack_visibility(actor_id, target_id)?;
+    if visibility.is_some_and(|v| v.cover.ac_bonus().is_none()) {
+        return Err(OrchestratorError::IntentExecutionError(format!(
+            "{} has total cover from {}",
+            target, actor
+        )));
+    }
+    Ok(visibility)
+}
+
+/// Helper function to apply weapon range on the grid
+///
+/// Returns `Some(false)` (disadvantage) at long range or with a hostile creature within
+/// 5 ft. Fails if the target is beyond the weapon's long range. Scenes without a grid
+/// and unknown weapons are left alone.
+fn check_ranged_attack(
+    game_session: &GameSession,
+    actor: &str,
+    target: &str,
+    weapon: &Option<String>,
+) -> Result<Option<bool>> {
+    let (Some(actor_id), Some(target_id), Some(engine)) = (
+        resolve_actor_id(game_session, actor),
+        resolve_actor_id(game_session, target),
+        game_session.engine_session(),
+    ) else {
+        return Ok(None);
+    };
+    let Some(distance) = engine.distance_between(actor_id, target_id)? else {
+        return Ok(None);
+    };
+
+    let band = weapon
+        .as_deref()
+        .and_then(WeaponDatabase::get_weapon)
+        .and_then(|w| w.range_band(distance.max(0) as u32));
+    match band {
+        Some(RangeBand::OutOfRange) => Err(OrchestratorError::IntentExecutionError(format!(
+            "{} is out of range of {} ({} ft)",
+            target, actor, distance
+        ))),
+        Some(RangeBand::Long) => Ok(Some(false)),
+        _ if engine.hostile_within_5ft(actor_id) => Ok(Some(false)),
+        _ => Ok(None),
+    }
+}
+
+/// Helper function to find the ammunition a ranged attack would use up: none for weapons
+/// without ammunition or creatures that don't track it (an empty inventory), an error when
+/// a tracked quiver is empty
+fn ammunition_to_spend(
+    game_session: &GameSession,
+    actor: &str,
+    weapon: &Option<String>,
+) -> Result<Option<(Uuid, &'static str)>> {
+    let Some(ammunition) = weapon
+        .as_deref()
+        .and_then(WeaponDatabase::get_weapon)
+        .and_then(|w| w.ammunition())
+    else {
+        return Ok(None);
+    };
+    let Some(attacker) = resolve_actor_id(game_session, actor)
+        .and_then(|id| game_session.engine_session()?.get_actor(id))
+    else {
+        return Ok(None);
+    };
+    if attacker.inventory.is_empty() {
+        return Ok(None);
+    }
+    if attacker.inventory.count(ammunition) == 0 {
+        return Err(OrchestratorError::IntentExecutionError(format!(
+            "{} is out of {}",
+            attacker.name, ammunition
+        )));
+    }
+    Ok(Some((attacker.id, ammunition)))
+}
+
+/// Helper function to get the AC bonus from cover (0 without a grid)
+fn cover_bonus(visibility: Option<AttackVisibility>) -> i32 {
+    visibility.and_then(|v| v.cover.ac_bonus()).unwrap_or(0)
+}
+
+/// Where the magic for a spell comes from
+enum SpellSource {
+    /// One of the caster's spell slots, at least this level
+    Slot(u8),
+    /// A scroll or wand, used up (or its charge spent) by `event`; cast at `level`, or
+    /// the spell's own level
+    Item {
+        name: String,
+        level: Option<u8>,
+        event: GameEvent,
+    },
+}
+
+/// Helper function to get the action an item takes to use
+pub(crate) fn item_cost(action: ItemAction) -> ActionCost {
+    match action {
+        ItemAction::Action => ActionCost::Action,
+        ItemAction::BonusAction => ActionCost::BonusAction,
+        ItemAction::Free => ActionCost::Free,
+    }
+}
+
+/// Helper function to count what's left of an item after using it: its charges, or the
+/// stack for items that are used up
+fn item_uses_left(game_session: &GameSession, actor_id: Uuid, item: &str, charged: bool) -> u32 {
+    let Some(actor) = game_session
+        .engine_session()
+        .and_then(|e| e.get_actor(actor_id))
+    else {
+        return 0;
+    };
+    if !charged {
+        return actor.inventory.count(item);
+    }
+    actor
+        .charges
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(item))
+        .map_or(0, |(_, c)| c.current)
+}
+
+/// Helper function to get an item's full charges and when they come back
+fn item_charges(item: &Item) -> Option<Charges> {
+    let recharge = match item.recharge {
+        ItemRecharge::Dawn => Recharge::Dawn,
+        ItemRecharge::Dusk => Recharge::Dusk,
+        ItemRecharge::LongRest => Recharge::LongRest,
+    };
+    item.charges.map(|max| Charges::new(max, recharge))
+}
+
+/// How much of a spell's damage and effects a target takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpellShare {
+    Full,
+    Half,
+    None,
+}
+
+/// Helper function to find the creatures a spell affects
+///
+/// Self-range spells cast without targets affect the caster.
+fn spell_targets(
+    game_session: &GameSession,
+    spell: &Spell,
+    caster_id: Uuid,
+    targets: &[String],
+) -> Result<Vec<Uuid>> {
+    if targets.is_empty() && spell.range == SpellRange::Self_ {
+        return Ok(vec![caster_id]);
+    }
+    targets
+        .iter()
+        .map(|target| {
+            resolve_actor_id(game_session, target).ok_or_else(|| {
+                OrchestratorError::IntentExecutionError(format!(
+                    "Spell target {} not found in scene",
+                    target
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Helper function to get a creature's name for narration
+fn actor_name(game_session: &GameSession, actor_id: Uuid) -> String {
+    game_session
+        .engine_session()
+        .and_then(|e| e.get_actor(actor_id))
+        .map_or_else(|| actor_id.to_string(), |a| a.name.clone())
+}
+
+/// Helper function to pick the caster level sent to rules5e, which checks the slot
+/// against a full caster's table; the engine tracks the caster's real slots
+fn caster_level_for(slot: Option<u8>, level: i32) -> u8 {
+    let needed = slot.map_or(1, |slot| (slot * 2).saturating_sub(1).clamp(1, 17));
+    (level.clamp(1, 20) as u8).max(needed)
+}
+
+/// Helper function to convert a spell's duration to combat rounds; `None` for spells
+/// that don't run out on their own
+fn spell_duration_rounds(duration: &SpellDuration) -> Option<u32> {
+    match duration {
+        SpellDuration::Round(rounds) => Some(*rounds),
+        SpellDuration::Minute(minutes) => Some(minutes * 10),
+        SpellDuration::Hour(hours) => Some(hours * 600),
+        SpellDuration::Day(days) => Some(days * 14_400),
+        _ => None,
+    }
+}
+
+/// Helper function to merge two advantage states; advantage and disadvantage cancel out
+fn combine_roll_modes(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(x), Some(y)) if x != y => None,
+        (Some(x), _) | (None, Some(x)) => Some(x),
+        (None, None) => None,
+    }
+}
+
+/// Helper function to move a melee attacker within reach of its target on the scene grid
+///
+/// Scenes without a grid are left alone. Fails if the target is out of reach and the
+/// attacker may not (or cannot) move next to it this turn.
+fn move_into_reach(
+    game_session: &mut GameSession,
+    actor: &str,
+    target: &str,
+    weapon: &Option<String>,
+    move_required: bool,
+) -> Result<()> {
+    let (Some(actor_id), Some(target_id)) = (
+        resolve_actor_id(game_session, actor),
+        resolve_actor_id(game_session, target),
+    ) else {
+        return Ok(());
+    };
+    let Some(engine) = game_session.engine_session() else {
+        return Ok(());
+    };
+    let Some(scene) = engine.get_current_scene() else {
+        return Ok(());
+    };
+    if scene.grid.is_none() {
+        return Ok(());
+    }
+    let (Some(attacker), Some(defender)) =
+        (engine.get_actor(actor_id), engine.get_actor(target_id))
+    else {
+        return Ok(());
+    };
+
+    let reach = match weapon.as_deref().and_then(WeaponDatabase::get_weapon) {
+        Some(w) if w.properties.contains(&WeaponProperty::Reach) => 2,
+        _ => 1,
+    };
+    let distance = footprint_distance(
+        attacker.grid_position(),
+        attacker.size.squares(),
+        defender.grid_position(),
+        defender.size.squares(),
+    );
+    if distance <= reach {
+        return Ok(());
+    }
+    if !move_required {
+        return Err(OrchestratorError::IntentExecutionError(format!(
+            "{} is out of reach of {}",
+            target, actor
+        )));
+    }
+
+    // Only move if the attack can actually happen this turn
+    let path = engine.path_toward(actor_id, target_id, reach * SQUARE_FEET)?;
+    if !path.reaches_goal {
+        return Err(OrchestratorError::IntentExecutionError(format!(
+            "{} cannot reach {} this turn",
+            actor, target
+        )));
+    }
+    game_session.dispatch(GameEvent::Moved {
+        actor_id,
+        path: path.path,
+    })?;
+    tracing::info!(
+        "{} moved {} ft into reach of {} ({} opportunity attack(s))",
+        actor,
+        path.cost_ft,
+        target,
+        path.opportunity_attacks
+    );
+    Ok(())
+}
+
+/// Helper function to build a table context from where the party is, the time of day
+/// and the party's level (estimated from its average proficiency bonus)
+fn table_context(engine: &EngineGameSession) -> TableContext {
+    let region = engine
+        .travel
+        .journey
+        .as_ref()
+        .and_then(|j| j.current_leg())
+        .map(|leg| format!("{:?}", leg.terrain).to_lowercase())
+        .or_else(|| engine.travel.location.clone());
+    let time_of_day = serde_json::to_value(engine.clock.time_of_day())
+        .ok()
+        .and_then(|v| v.as_str().map(String::from));
+    let bonuses: Vec<i32> = engine
+        .party()
+        .into_iter()
+        .filter_map(|id| engine.get_actor(id))
+        .map(|a| a.proficiency_bonus)
+        .collect();
+    let party_level = (!bonuses.is_empty()).then(|| {
+        let average = bonuses.iter().sum::<i32>() / bonuses.len() as i32;
+        (4 * (average - 2) + 1).clamp(1, 20) as u32
+    });
+    TableContext {
+        region,
+        time_of_day,
+        party_level,
+    }
+}
+
+/// Helper function to roll one watch of travel: the party's best navigator and forager
+/// (highest Wisdom), the encounter d20 and everyone's Constitution save in case of a
+/// forced march
+fn roll_watch(engine: &EngineGameSession, seed: Option<u64>) -> Result<WatchRolls> {
+    let mut party: Vec<_> = engine
+        .scene_actors()
+        .into_iter()
+        .filter(|a| a.actor_type == ActorType::Player && a.is_alive())
+        .collect();
+    party.sort_by_key(|a| a.id);
+
+    let mut roller = seed.map(DiceRoller::with_seed).unwrap_or_default();
+    let mut d20 = |modifier: i32| -> Result<i32> {
+        let expression = DiceExpression {
+            count: 1,
+            sides: 20,
+            modifier,
+        };
+        roller
+            .roll(&expression, RollMode::Normal)
+            .map(|r| r.total)
+            .map_err(|e| OrchestratorError::ServiceError(format!("Travel roll failed: {}", e)))
+    };
+
+    let wisdom = party
+        .iter()
+        .map(|a| AbilityScores::modifier(a.abilities.wisdom))
+        .max();
+    Ok(WatchRolls {
+        navigation: wisdom.map(&mut d20).transpose()?,
+        forage: wisdom.map(&mut d20).transpose()?,
+        encounter: d20(0)? as u32,
+        forced_march: party
+            .iter()
+            .map(|a| {
+                let constitution = AbilityScores::modifier(a.abilities.constitution);
+                let saves = (0..WATCH_HOURS)
+                    .map(|_| d20(constitution))
+                    .collect::<Result<_>>()?;
+                Ok((a.id, saves))
+            })
+            .collect::<Result<_>>()?,
+    })
+}
+
 /// Helper function to generate a deterministic seed for rolls
 ///
-/// Uses session ID and current turn/round to create a reproducible seed
-fn get_deterministic_seed(_game_session: &GameSession) -> Option<u64> {
-    // For now, return None (non-deterministic)
-    // TODO: When turn tracking is implemented, use session_id + turn_number + action_index
-    None
+/// Uses session ID, round, turn and action index so a recorded session replays the same rolls
+fn get_deterministic_seed(game_session: &mut GameSession) -> Option<u64> {
+    Some(game_session.next_seed())
 }
 
 #[cfg(test)]
@@ -858,6 +2232,7 @@ mod tests {
 
         let intent = Intent::CombatStart {
             reason: Some("Ambush!".to_string()),
+            surprised: vec![],
         };
 
         // Should transition to CombatTurnBased
@@ -886,7 +2261,10 @@ mod tests {
         }
 
         // First start combat
-        let start_intent = Intent::CombatStart { reason: None };
+        let start_intent = Intent::CombatStart {
+            reason: None,
+            surprised: vec![],
+        };
         executor
             .execute(&start_intent, &mut game_session)
             .await
@@ -909,4 +2287,774 @@ mod tests {
             crate::fsm::SceneState::Exploration
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_help_grants_advantage_token() {
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Test Scene".to_string());
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        let goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        let (rogue_id, goblin_id) = (rogue.id, goblin.id);
+        engine_session
+            .add_actor_to_scene(scene_id, fighter)
+            .unwrap();
+        engine_session.add_actor_to_scene(scene_id, rogue).unwrap();
+        engine_session.add_actor_to_scene(scene_id, goblin).unwrap();
+
+        let intent = Intent::Help {
+            actor: "Fighter".to_string(),
+            target: "Rogue".to_string(),
+            against: Some("Goblin".to_string()),
+        };
+        executor.execute(&intent, &mut game_session).await.unwrap();
+
+        let scope = AdvantageScope::AttackAgainst(goblin_id);
+        let key = advantage_token_key(&game_session, "Rogue", Some("Goblin"));
+        assert_eq!(key, Some((rogue_id, scope)));
+        assert_eq!(apply_advantage_token(None, &game_session, key), Some(true));
+        assert_eq!(apply_advantage_token(Some(false), &game_session, key), None);
+
+        // The token only applies to attacks against the goblin
+        let check_key = advantage_token_key(&game_session, "Rogue", None);
+        assert_eq!(apply_advantage_token(None, &game_session, check_key), None);
+
+        consume_advantage_token(&mut game_session, key).unwrap();
+        assert_eq!(apply_advantage_token(None, &game_session, key), None);
+        // Using it up is recorded, so it can be undone
+        assert!(matches!(
+            game_session.undo().unwrap(),
+            GameEvent::AdvantageUsed { .. }
+        ));
+        assert_eq!(apply_advantage_token(None, &game_session, key), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_execute_help_unknown_target() {
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Test Scene".to_string());
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        engine_session
+            .add_actor_to_scene(scene_id, fighter)
+            .unwrap();
+
+        let intent = Intent::Help {
+            actor: "Fighter".to_string(),
+            target: "Nobody".to_string(),
+            against: None,
+        };
+        assert!(executor.execute(&intent, &mut game_session).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_legendary_action_spends_charges() {
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+        use game_engine::{LegendaryAction, LegendaryTraits};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Lair".to_string());
+        let dragon = Actor::new("Dragon".to_string(), ActorType::Monster).with_legendary(
+            LegendaryTraits::new(
+                3,
+                vec![LegendaryAction {
+                    name: "Wing Attack".to_string(),
+                    cost: 2,
+                    description: String::new(),
+                }],
+                3,
+                vec![],
+            ),
+        );
+        engine_session.add_actor_to_scene(scene_id, dragon).unwrap();
+
+        let intent = Intent::LegendaryAction {
+            actor: "Dragon".to_string(),
+            action: "Wing Attack".to_string(),
+            target: None,
+        };
+        executor.execute(&intent, &mut game_session).await.unwrap();
+        // Only one action left: a second Wing Attack can't be paid for
+        assert!(executor.execute(&intent, &mut game_session).await.is_err());
+    }
+
+    #[test]
+    fn test_move_into_reach_on_grid() {
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+        use game_engine::{Grid, GridPos};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Bridge".to_string());
+        engine_session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(Grid::new(10, 10));
+        let fighter = Actor::new("Fighter".to_string(), ActorType::Player);
+        let mut orc = Actor::new("Orc".to_string(), ActorType::Monster);
+        orc.set_grid_position(GridPos::new(5, 1), 0);
+        let fighter_id = fighter.id;
+        engine_session
+            .add_actor_to_scene(scene_id, fighter)
+            .unwrap();
+        engine_session.add_actor_to_scene(scene_id, orc).unwrap();
+
+        // A glaive reaches 10 ft, but the fighter still has to close in
+        assert!(move_into_reach(&mut game_session, "Fighter", "Orc", &None, false).is_err());
+        move_into_reach(
+            &mut game_session,
+            "Fighter",
+            "Orc",
+            &Some("Glaive".to_string()),
+            true,
+        )
+        .unwrap();
+
+        let position = game_session
+            .engine_session()
+            .unwrap()
+            .get_actor(fighter_id)
+            .unwrap()
+            .grid_position();
+        // Nearest square within 10 ft of the orc: 15 ft of movement
+        assert_eq!(position, GridPos::new(3, 0));
+    }
+
+    #[test]
+    fn test_attack_visibility_cover_and_darkness() {
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+        use game_engine::{Grid, GridPos, LightLevel, Terrain};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Ruins".to_string());
+        let mut grid = Grid::new(10, 5);
+        grid.set_terrain(GridPos::new(3, 2), Terrain::Wall).unwrap();
+        engine_session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(grid);
+        let mut archer = Actor::new("Archer".to_string(), ActorType::Player);
+        archer.set_grid_position(GridPos::new(0, 2), 0);
+        let mut orc = Actor::new("Orc".to_string(), ActorType::Monster);
+        orc.set_grid_position(GridPos::new(4, 2), 0);
+        engine_session.add_actor_to_scene(scene_id, archer).unwrap();
+        engine_session.add_actor_to_scene(scene_id, orc).unwrap();
+
+        // Half cover behind the pillar
+        let visibility = attack_visibility(&game_session, "Archer", "Orc").unwrap();
+        assert_eq!(cover_bonus(visibility), 2);
+        assert_eq!(visibility.unwrap().roll_mode(), None);
+
+        // Neither side sees in the dark: the two effects cancel
+        game_session
+            .engine_session_mut()
+            .unwrap()
+            .get_current_scene_mut()
+            .unwrap()
+            .ambient_light = LightLevel::Darkness;
+        let visibility = attack_visibility(&game_session, "Archer", "Orc").unwrap();
+        assert_eq!(visibility.unwrap().roll_mode(), None);
+        assert_eq!(combine_roll_modes(Some(true), Some(false)), None);
+        assert_eq!(combine_roll_modes(None, Some(false)), Some(false));
+
+        // A full wall between them gives total cover
+        let scene = game_session
+            .engine_session_mut()
+            .unwrap()
+            .get_current_scene_mut()
+            .unwrap();
+        let grid = scene.grid.as_mut().unwrap();
+        for y in 0..5 {
+            grid.set_terrain(GridPos::new(2, y), Terrain::Wall).unwrap();
+        }
+        assert!(attack_visibility(&game_session, "Archer", "Orc").is_err());
+    }
+
+    #[test]
+    fn test_ranged_attack_range_and_ammunition() {
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{Actor, ActorType};
+        use game_engine::{Grid, GridPos};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Valley".to_string());
+        engine_session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(Grid::new(40, 5));
+        let mut archer = Actor::new("Archer".to_string(), ActorType::Player);
+        archer.inventory.add("Arrow", 1);
+        let mut orc = Actor::new("Orc".to_string(), ActorType::Monster);
+        orc.set_grid_position(GridPos::new(20, 0), 0);
+        let orc_id = orc.id;
+        engine_session.add_actor_to_scene(scene_id, archer).unwrap();
+        engine_session.add_actor_to_scene(scene_id, orc).unwrap();
+
+        let shortbow = Some("Shortbow".to_string());
+        let dagger = Some("Dagger".to_string());
+        // 100 ft: long range for a shortbow, out of range for a thrown dagger
+        assert_eq!(
+            check_ranged_attack(&game_session, "Archer", "Orc", &shortbow).unwrap(),
+            Some(false)
+        );
+        assert!(check_ranged_attack(&game_session, "Archer", "Orc", &dagger).is_err());
+
+        // Normal range, but the orc is right next to the archer
+        game_session
+            .engine_session_mut()
+            .unwrap()
+            .get_actor_mut(orc_id)
+            .unwrap()
+            .set_grid_position(GridPos::new(1, 0), 0);
+        assert_eq!(
+            check_ranged_attack(&game_session, "Archer", "Orc", &dagger).unwrap(),
+            Some(false)
+        );
+
+        let (archer_id, arrow) = ammunition_to_spend(&game_session, "Archer", &shortbow)
+            .unwrap()
+            .unwrap();
+        assert_eq!(arrow, "Arrow");
+        assert_eq!(
+            ammunition_to_spend(&game_session, "Archer", &dagger).unwrap(),
+            None
+        );
+        // The orc doesn't track ammunition
+        assert_eq!(
+            ammunition_to_spend(&game_session, "Orc", &shortbow).unwrap(),
+            None
+        );
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let archer = engine_session.get_actor_mut(archer_id).unwrap();
+        archer.inventory.add("Rope", 1);
+        archer.inventory.remove("Arrow", 1).unwrap();
+        assert!(ammunition_to_spend(&game_session, "Archer", &shortbow).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_combat_start_rolls_initiative() {
+        let executor = IntentExecutor::new().with_group_initiative(true);
+        let mut game_session = GameSession::new();
+
+        use game_engine::actor::{AbilityScores, Actor, ActorType};
+
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Ambush".to_string());
+        let mut rogue =
+            Actor::new("Rogue".to_string(), ActorType::Player).with_abilities(AbilityScores {
+                dexterity: 18,
+                ..AbilityScores::default()
+            });
+        rogue.features.push("Alert".to_string());
+        let rogue_id = rogue.id;
+        engine_session.add_actor_to_scene(scene_id, rogue).unwrap();
+        for i in 1..=3 {
+            let goblin = Actor::new(format!("Goblin {}", i), ActorType::Monster)
+                .with_stat_block("goblin".to_string());
+            engine_session.add_actor_to_scene(scene_id, goblin).unwrap();
+        }
+
+        let intent = Intent::CombatStart {
+            reason: None,
+            surprised: vec!["Goblin 1".to_string()],
+        };
+        executor.execute(&intent, &mut game_session).await.unwrap();
+
+        let engine = game_session.engine_session().unwrap();
+        let order = engine.turn_order.all_actors();
+        assert_eq!(order.len(), 4);
+
+        // Rogue: d20 + 4 (DEX) + 5 (Alert)
+        let rogue_initiative = engine.get_actor(rogue_id).unwrap().initiative.unwrap();
+        assert!((10..=29).contains(&rogue_initiative));
+
+        // Goblins share one roll and act back to back
+        let goblin_positions: Vec<usize> = order
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| **id != rogue_id)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(goblin_positions[2] - goblin_positions[0], 2);
+        let goblin_initiatives: Vec<Option<i32>> = goblin_positions
+            .iter()
+            .map(|&i| engine.get_actor(order[i]).unwrap().initiative)
+            .collect();
+        assert!(goblin_initiatives
+            .iter()
+            .all(|i| *i == goblin_initiatives[0]));
+    }
+
+    #[tokio::test]
+    async fn test_execute_travel_until_the_day_ends_then_forced_march() {
+        use game_engine::{Actor, ActorType, TravelTerrain};
+
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        // A fixed session id fixes the seeds, and so every roll below
+        game_session.session_id = "trip".to_string();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Road".to_string());
+        let ranger = Actor::new("Ranger".to_string(), ActorType::Player);
+        let ranger_id = ranger.id;
+        engine.add_actor_to_scene(scene_id, ranger).unwrap();
+        engine.travel.map.add_location("Waterdeep", "");
+        engine.travel.map.add_location("Daggerford", "");
+        engine
+            .travel
+            .map
+            .connect("Waterdeep", "Daggerford", 30, TravelTerrain::Road);
+        engine.travel.location = Some("Waterdeep".to_string());
+        let start = engine.clock.now();
+
+        let intent = Intent::Travel {
+            destination: "daggerford".to_string(),
+            pace: Some("normal".to_string()),
+        };
+        executor.execute(&intent, &mut game_session).await.unwrap();
+
+        // 12 miles a watch on the road, stopping after the day's eight hours
+        let engine = game_session.engine_session().unwrap();
+        let report = engine.travel.last_watch.as_ref().unwrap();
+        assert!(!report.arrived && !report.encounter);
+        assert_eq!(report.forced_march_dc, None);
+        assert_eq!(engine.clock.now() - start, 8 * 3600);
+        let journey = engine.travel.journey.as_ref().unwrap();
+        assert_eq!(journey.miles_remaining(), 6);
+
+        // Going on is a forced march: a save per hour against DC 11 to 14, three failed
+        executor.execute(&intent, &mut game_session).await.unwrap();
+        let engine = game_session.engine_session().unwrap();
+        let report = engine.travel.last_watch.as_ref().unwrap();
+        assert!(report.arrived && !report.encounter);
+        assert_eq!(report.forced_march_dc, Some(14));
+        assert_eq!(report.exhausted, vec![ranger_id; 3]);
+        assert_eq!(engine.travel.location.as_deref(), Some("Daggerford"));
+        assert_eq!(engine.clock.now() - start, 12 * 3600);
+        assert_eq!(engine.get_actor(ranger_id).unwrap().exhaustion, 3);
+        assert!(game_session.take_narration_prompts().is_empty());
+
+        let slow = Intent::Travel {
+            destination: "Daggerford".to_string(),
+            pace: Some("crawling".to_string()),
+        };
+        assert!(executor.execute(&slow, &mut game_session).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_roll_table_queues_narration() {
+        let executor = IntentExecutor::new();
+        let intent = Intent::RollTable {
+            table: "weather".to_string(),
+            region: None,
+            party_level: None,
+        };
+
+        let mut first = GameSession::new();
+        let mut second = first.clone();
+        executor.execute(&intent, &mut first).await.unwrap();
+        executor.execute(&intent, &mut second).await.unwrap();
+        let prompts = first.take_narration_prompts();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].starts_with("The weather table rolled"));
+        // Same session, same seed, same result
+        assert_eq!(prompts, second.take_narration_prompts());
+
+        let unknown = Intent::RollTable {
+            table: "dragon_hoards".to_string(),
+            region: None,
+            party_level: None,
+        };
+        assert!(executor.execute(&unknown, &mut first).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_output_runs_only_valid_intents() {
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        let text = r#"{"intents": [
+            {"intent": "ROLL_TABLE", "table": "dragon_hoards"},
+            {"intent": "ROLL_TABLE", "table": "weather", "mood": "grim"},
+            {"intent": "ROLL_TABLE", "table": "weather"}
+        ]}"#;
+
+        let errors = executor.execute_output(text, &mut game_session, None).await;
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            (errors[0].index, errors[0].field.as_deref()),
+            (0, Some("table"))
+        );
+        assert_eq!(errors[1].index, 1);
+        assert!(errors[1].message.contains("mood"));
+        assert_eq!(game_session.take_narration_prompts().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spell_cast_checks_and_spends_slots() {
+        use game_engine::{Actor, ActorType};
+        use rules5e_service::{
+            AreaOfEffect, CastingTime, SpellComponents, SpellEffect, SpellLevel, SpellSchool,
+        };
+
+        let mut spells = SpellDatabase::new();
+        spells.add_spell(Spell {
+            name: "Magic Missile".to_string(),
+            level: SpellLevel::new(1).unwrap(),
+            school: SpellSchool::Evocation,
+            casting_time: CastingTime::Action,
+            range: SpellRange::Feet(120),
+            components: SpellComponents::new().with_verbal().with_somatic(),
+            duration: SpellDuration::Instantaneous,
+            description: "Three darts of magical force".to_string(),
+            higher_levels: None,
+            classes: vec!["Wizard".to_string()],
+            ritual: false,
+            concentration: false,
+            area_of_effect: AreaOfEffect::None,
+            attack_type: SpellAttackType::None,
+            saving_throw: None,
+            effect: SpellEffect {
+                damage: Some(DiceExpression {
+                    count: 3,
+                    sides: 4,
+                    modifier: 3,
+                }),
+                damage_type: Some("force".to_string()),
+                healing: None,
+                condition: None,
+                description: "Deals force damage".to_string(),
+            },
+        });
+        let executor = IntentExecutor::new().with_spells(Arc::new(spells));
+
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Tower".to_string());
+        let wizard =
+            Actor::new("Wizard".to_string(), ActorType::Player).with_spell_slots(&[(1, 1)]);
+        let wizard_id = wizard.id;
+        engine.add_actor_to_scene(scene_id, wizard).unwrap();
+        engine
+            .add_actor_to_scene(
+                scene_id,
+                Actor::with_stats("Goblin".to_string(), ActorType::Monster, 7, 15),
+            )
+            .unwrap();
+
+        let cast = Intent::SpellCast {
+            actor: "Wizard".to_string(),
+            spell: "magic missile".to_string(),
+            slot_level: 1,
+            area_center: None,
+            targets: vec!["Goblin".to_string()],
+        };
+        assert!(executor.validate(&cast, &game_session).is_empty());
+
+        // The slot is only spent once rules5e has rolled the spell (it isn't running here)
+        assert!(executor.execute(&cast, &mut game_session).await.is_err());
+        let slots = |game_session: &GameSession| {
+            game_session
+                .engine_session()
+                .unwrap()
+                .get_actor(wizard_id)
+                .unwrap()
+                .spell_slots[&1]
+                .current
+        };
+        assert_eq!(slots(&game_session), 1);
+
+        game_session
+            .dispatch(GameEvent::SpellSlotUsed {
+                actor_id: wizard_id,
+                level: 1,
+            })
+            .unwrap();
+        assert_eq!(slots(&game_session), 0);
+        let errors = executor.validate(&cast, &game_session);
+        assert_eq!(errors[0].message, "Wizard has no level 1 spell slots left");
+        // Checked again before rules5e is asked to roll the spell
+        let error = executor
+            .execute(&cast, &mut game_session)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no level 1 spell slots left"));
+
+        // A goblin has no slot data: only castable when the table allows it
+        let goblin_cast = Intent::SpellCast {
+            actor: "Goblin".to_string(),
+            spell: "magic missile".to_string(),
+            slot_level: 1,
+            area_center: None,
+            targets: vec!["Wizard".to_string()],
+        };
+        let errors = executor.validate(&goblin_cast, &game_session);
+        assert_eq!(
+            errors[0].message,
+            "Goblin has no spell slots to cast at level 1"
+        );
+        let executor = executor.with_untracked_slots(true);
+        assert!(executor.validate(&goblin_cast, &game_session).is_empty());
+
+        // rules5e checks slots against a full caster's table
+        assert_eq!(caster_level_for(Some(3), 1), 5);
+        assert_eq!(caster_level_for(None, 4), 4);
+        assert_eq!(spell_duration_rounds(&SpellDuration::Minute(1)), Some(10));
+        assert_eq!(spell_duration_rounds(&SpellDuration::Instantaneous), None);
+    }
+
+    #[tokio::test]
+    async fn test_resume_roll_narrates_checks_and_attack_misses() {
+        use crate::communication::RollRequest;
+        use game_engine::{Actor, ActorType};
+
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Keep".to_string());
+        let rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        let mut guard = Actor::new("Guard".to_string(), ActorType::Monster);
+        guard.ac = 16;
+        let (rogue_id, guard_id, guard_hp) = (rogue.id, guard.id, guard.hp);
+        engine.add_actor_to_scene(scene_id, rogue).unwrap();
+        engine.add_actor_to_scene(scene_id, guard).unwrap();
+
+        let mut roll = SettledRoll {
+            request: RollRequest {
+                session_id: game_session.session_id.clone(),
+                request_id: "r1".to_string(),
+                actor_id: rogue_id.to_string(),
+                roll_kind: "skill_check".to_string(),
+                skill: Some("stealth".to_string()),
+                ability: None,
+                dc: Some(15),
+                formula_hint: Some("1d20+4".to_string()),
+                reason: "sneaking past the guard".to_string(),
+                secret: false,
+            },
+            resume: RollResume::Narrate,
+            total: 13,
+            natural: 9,
+            auto_rolled: false,
+        };
+        executor
+            .resume_roll(&roll, &mut game_session)
+            .await
+            .unwrap();
+        assert_eq!(
+            game_session.take_narration_prompts(),
+            vec![
+                "Rogue rolled 13 (natural 9) for sneaking past the guard: failure against DC 15. \
+                  Narrate this result without changing it."
+                    .to_string()
+            ]
+        );
+
+        // A natural 1 misses whatever the total
+        roll.resume = RollResume::Attack {
+            target: "Guard".to_string(),
+            weapon: Some("Dagger".to_string()),
+        };
+        roll.request.reason = "a sneak attack".to_string();
+        roll.natural = 1;
+        roll.total = 30;
+        roll.auto_rolled = true;
+        executor
+            .resume_roll(&roll, &mut game_session)
+            .await
+            .unwrap();
+        let prompts = game_session.take_narration_prompts();
+        assert!(prompts[0].starts_with(
+            "Rogue didn't roll in time for a sneak attack, so the table rolled 30 (natural 1), \
+             missing Guard (AC 16)"
+        ));
+        let engine = game_session.engine_session().unwrap();
+        assert_eq!(engine.get_actor(guard_id).unwrap().hp, guard_hp);
+    }
+
+    #[tokio::test]
+    async fn test_execute_use_item_spends_it_and_the_action() {
+        use game_engine::{Actor, ActorType};
+
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Swamp".to_string());
+        let mut ranger = Actor::new("Ranger".to_string(), ActorType::Player);
+        ranger.inventory.add("Antitoxin", 1);
+        ranger.inventory.add("Torch", 2);
+        let ranger_id = ranger.id;
+        engine.add_actor_to_scene(scene_id, ranger).unwrap();
+        let use_item = |item: &str| Intent::UseItem {
+            actor: "Ranger".to_string(),
+            item_id: item.to_string(),
+            targets: vec![],
+        };
+
+        executor
+            .execute(&use_item("antitoxin"), &mut game_session)
+            .await
+            .unwrap();
+        let engine = game_session.engine_session().unwrap();
+        assert_eq!(
+            engine
+                .get_actor(ranger_id)
+                .unwrap()
+                .inventory
+                .count("Antitoxin"),
+            0
+        );
+        assert!(engine
+            .effects
+            .iter()
+            .any(|e| e.name == "Antitoxin" && e.target_id == ranger_id));
+        let prompts = game_session.take_narration_prompts();
+        assert!(prompts[0].starts_with("Ranger used Antitoxin, gaining Antitoxin (0 left)"));
+
+        // In combat the torch takes the ranger's action
+        game_session
+            .dispatch(GameEvent::CombatStarted {
+                order: vec![(ranger_id, 12)],
+                surprised: vec![],
+            })
+            .unwrap();
+        executor
+            .execute(&use_item("Torch"), &mut game_session)
+            .await
+            .unwrap();
+        let errors = executor.validate(&use_item("Torch"), &game_session);
+        assert_eq!(
+            errors[0].message,
+            "Ranger has already used its action this turn"
+        );
+        assert!(executor
+            .execute(&use_item("Torch"), &mut game_session)
+            .await
+            .is_err());
+        let engine = game_session.engine_session().unwrap();
+        assert_eq!(
+            engine
+                .get_actor(ranger_id)
+                .unwrap()
+                .inventory
+                .count("Torch"),
+            1
+        );
+
+        // A wand reports its charges, not how many wands there are
+        let wand = ItemDatabase::get_item("Wand of Web").unwrap();
+        game_session
+            .engine_session_mut()
+            .unwrap()
+            .get_actor_mut(ranger_id)
+            .unwrap()
+            .inventory
+            .add("Wand of Web", 1);
+        game_session
+            .dispatch(GameEvent::ItemUsed {
+                actor_id: ranger_id,
+                item: "wand of web".to_string(),
+                cost: ActionCost::Free,
+                charges: item_charges(&wand),
+            })
+            .unwrap();
+        assert_eq!(
+            item_uses_left(&game_session, ranger_id, "Wand of Web", true),
+            6
+        );
+        assert_eq!(
+            item_uses_left(&game_session, ranger_id, "Wand of Web", false),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_change_scene_moves_party_and_splits() {
+        use game_engine::{Actor, ActorType};
+
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let tavern = engine.create_scene("Tavern".to_string());
+        let bard = Actor::new("Bard".to_string(), ActorType::Player);
+        let monk = Actor::new("Monk".to_string(), ActorType::Player);
+        let (bard_id, monk_id) = (bard.id, monk.id);
+        engine.add_actor_to_scene(tavern, bard).unwrap();
+        engine.add_actor_to_scene(tavern, monk).unwrap();
+
+        // The monk heads down to the cellar alone; the scene is created on the way
+        let split = Intent::ChangeScene {
+            scene: "Cellar".to_string(),
+            actors: vec!["monk".to_string()],
+        };
+        executor.execute(&split, &mut game_session).await.unwrap();
+        let engine = game_session.engine_session().unwrap();
+        let cellar = engine.current_scene.unwrap();
+        assert_eq!(engine.scenes[&cellar].name, "Cellar");
+        assert_eq!(engine.party_locations()[&tavern], vec![bard_id]);
+
+        let regroup = Intent::ChangeScene {
+            scene: "cellar".to_string(),
+            actors: Vec::new(),
+        };
+        executor.execute(&regroup, &mut game_session).await.unwrap();
+        let engine = game_session.engine_session_mut().unwrap();
+        assert_eq!(engine.party_locations()[&cellar], vec![bard_id, monk_id]);
+        assert_eq!(engine.scenes.len(), 2);
+        assert_eq!(engine.take_scene_transitions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_execute_as_checks_who_controls_the_actor() {
+        use game_engine::{Actor, ActorType, Control, ControlKind};
+
+        let executor = IntentExecutor::new();
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Glade".to_string());
+        let ranger = Actor::new("Ranger".to_string(), ActorType::Player);
+        let wizard = Actor::new("Wizard".to_string(), ActorType::Player);
+        let hawk = Actor::new("Hawk".to_string(), ActorType::Npc);
+        let (ranger_id, hawk_id) = (ranger.id, hawk.id);
+        for actor in [ranger, wizard, hawk] {
+            engine.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+        game_session
+            .dispatch(GameEvent::ControlGranted {
+                actor_id: hawk_id,
+                control: Control::new(ranger_id, ControlKind::Companion),
+            })
+            .unwrap();
+
+        let dash = |actor: &str| Intent::Dash {
+            actor: actor.to_string(),
+        };
+        for (issuer, actor, allowed) in [
+            ("Ranger", "Hawk", true),
+            ("Ranger", "Ranger", true),
+            ("Wizard", "Hawk", false),
+            ("Wizard", "Ranger", false),
+        ] {
+            let result = executor
+                .execute_as(&dash(actor), &mut game_session, issuer)
+                .await;
+            assert_eq!(result.is_ok(), allowed, "{} as {}", issuer, actor);
+        }
+    }
 }
@@ -0,0 +1,169 @@
+//! JSON INTENT format
+//!
+//! The same intents as the DSL, as JSON checked against a schema derived from
+//! [`Intent`]:
+//! {"intents": [{"intent": "MELEE_ATTACK", "actor": "player_1",
+//!   "target": "npc_goblin_02", "weapon": "weapon_longsword", "move_required": true}]}
+//!
+//! Field names are the `Intent` field names; unknown keys are an error. A bare
+//! array of intents or a single intent object is accepted too, with or without
+//! a surrounding markdown code fence.
+
+use super::types::Intent;
+use super::validation::IntentError;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ops::Range;
+
+/// Intents in the JSON format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IntentBatch {
+    pub intents: Vec<Intent>,
+}
+
+/// JSON Schema of [`IntentBatch`], for prompts and constrained decoding
+pub fn schema() -> Value {
+    serde_json::to_value(schemars::schema_for!(IntentBatch)).expect("intent schema serializes")
+}
+
+/// Intents from one JSON payload (a batch, an array or a single intent), each
+/// parsed on its own
+pub fn parse_value(value: Value) -> Vec<std::result::Result<Intent, IntentError>> {
+    let items = match value {
+        Value::Object(mut map) if map.contains_key("intents") => {
+            if let Some(key) = map.keys().find(|k| k.as_str() != "intents") {
+                return vec![Err(IntentError::new(
+                    None,
+                    Some(key),
+                    format!("unknown field `{}`, expected `intents`", key),
+                ))];
+            }
+            match map.remove("intents") {
+                Some(Value::Array(items)) => items,
+                _ => {
+                    return vec![Err(IntentError::new(
+                        None,
+                        Some("intents"),
+                        "expected an array of intents",
+                    ))]
+                }
+            }
+        }
+        Value::Array(items) => items,
+        value => vec![value],
+    };
+    items.into_iter().map(parse_item).collect()
+}
+
+/// Intents from every JSON payload embedded in `text`
+pub fn parse(text: &str) -> Vec<std::result::Result<Intent, IntentError>> {
+    find_payloads(text)
+        .into_iter()
+        .flat_map(|(_, value)| parse_value(value))
+        .collect()
+}
+
+/// `text` without its JSON payloads (and the code fences around them)
+pub fn strip_payloads(text: &str) -> String {
+    let mut pieces = Vec::new();
+    let mut last = 0;
+    for (range, _) in find_payloads(text) {
+        let before = text[last..range.start].trim_end();
+        pieces.push(
+            before
+                .strip_suffix("```json")
+                .or_else(|| before.strip_suffix("```"))
+                .unwrap_or(before),
+        );
+        let after = text[range.end..].trim_start();
+        last = text.len() - after.strip_prefix("```").unwrap_or(after).len();
+    }
+    pieces.push(&text[last..]);
+    pieces
+        .iter()
+        .map(|piece| piece.trim())
+        .filter(|piece| !piece.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One intent object; errors name the intent type when it can be read
+fn parse_item(item: Value) -> std::result::Result<Intent, IntentError> {
+    let intent = item.get("intent").and_then(Value::as_str).map(String::from);
+    serde_json::from_value(item)
+        .map_err(|e| IntentError::new(intent.as_deref(), None, format!("invalid intent: {}", e)))
+}
+
+/// Whether `value` is an intent payload rather than unrelated JSON
+fn is_payload(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.contains_key("intents") || map.contains_key("intent"),
+        Value::Array(items) => {
+            !items.is_empty() && items.iter().all(|item| item.get("intent").is_some())
+        }
+        _ => false,
+    }
+}
+
+/// Byte ranges and values of the JSON intent payloads in `text`
+fn find_payloads(text: &str) -> Vec<(Range<usize>, Value)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = text[start..].find(['{', '[']) {
+        let begin = start + offset;
+        let mut stream = serde_json::Deserializer::from_str(&text[begin..]).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) if is_payload(&value) => {
+                let end = begin + stream.byte_offset();
+                found.push((begin..end, value));
+                start = end;
+            }
+            _ => start = begin + 1,
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_lists_every_intent() {
+        let schema = schema().to_string();
+        for name in [
+            "MELEE_ATTACK",
+            "SPELL_CAST",
+            "CHANGE_SCENE",
+            "GENERATE_BATTLEMAP",
+        ] {
+            assert!(schema.contains(name), "schema should list {}", name);
+        }
+        assert!(schema.contains("\"additionalProperties\":false"));
+    }
+
+    #[test]
+    fn test_payloads_inside_narration() {
+        let text = "The goblin lunges.\n```json\n{\"intents\": [{\"intent\": \"DASH\", \
+                    \"actor\": \"npc_goblin\"}]}\n```\nIt runs [fast].";
+        let intents = parse(text);
+        assert_eq!(
+            intents,
+            vec![Ok(Intent::Dash {
+                actor: "npc_goblin".to_string()
+            })]
+        );
+        assert_eq!(strip_payloads(text), "The goblin lunges.\nIt runs [fast].");
+
+        let unknown = r#"[{"intent": "DASH", "actor": "npc_goblin", "speed": 60},
+                          {"intent": "DISENGAGE", "actor": "npc_goblin"}]"#;
+        let results = parse(unknown);
+        assert_eq!(results.len(), 2);
+        let error = results[0].as_ref().unwrap_err();
+        assert_eq!(error.intent.as_deref(), Some("DASH"));
+        assert!(error.message.contains("speed"));
+        assert!(results[1].is_ok());
+    }
+}
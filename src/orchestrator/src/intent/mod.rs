@@ -1,15 +1,19 @@
 //! INTENT DSL parsing and execution
 //!
 //! This module handles:
-//! - Parsing INTENT DSL blocks from LLM output
-//! - Validating INTENTs
+//! - Parsing INTENT DSL blocks and JSON intents from LLM output
+//! - Resolving the names INTENTs use and validating INTENTs
 //! - Executing INTENTs by calling appropriate services
 
 pub mod actor_stats;
 pub mod executor;
+pub mod json;
 pub mod parser;
+pub mod resolver;
 pub mod types;
+pub mod validation;
 
 pub use executor::IntentExecutor;
 pub use parser::IntentParser;
 pub use types::Intent;
+pub use validation::IntentError;
@@ -9,8 +9,28 @@
 //! MOVE_REQUIRED: YES
 //! END_INTENT
 //! [/INTENTS]
+//!
+//! HELP takes an optional AGAINST field: with it the helped creature gains
+//! advantage on its next attack against that enemy, without it on its next
+//! ability check.
+//!
+//! LEGENDARY_ACTION (ACTOR, ACTION, optional TARGET) and LAIR_ACTION (ACTOR, ACTION)
+//! answer a legendary action window or a lair action on initiative count 20.
+//!
+//! TRAVEL takes a DESTINATION on the overland map and an optional PACE
+//! (FAST, NORMAL or SLOW).
+//!
+//! ROLL_TABLE takes a TABLE name and optional REGION and LEVEL filters.
+//!
+//! CHANGE_SCENE takes a SCENE (name or id) and optional comma-separated ACTORS;
+//! without ACTORS the whole party moves.
+//!
+//! Intents may also come in the JSON format (see [`super::json`]), either bare
+//! or inside an [INTENTS] block; both normalise to the same [`Intent`] values.
 
+use super::json;
 use super::types::Intent;
+use super::validation::IntentError;
 use crate::error::{OrchestratorError, Result};
 
 /// INTENT DSL Parser
@@ -19,13 +39,27 @@ pub struct IntentParser;
 impl IntentParser {
     /// Parse INTENT DSL block from text
     ///
-    /// Extracts all INTENT blocks from the text and parses them
+    /// Extracts all INTENT blocks from the text and parses them; text without
+    /// [INTENTS] blocks is searched for JSON intents instead. Fails on the first
+    /// malformed intent.
     pub fn parse(text: &str) -> Result<Vec<Intent>> {
-        let mut intents = Vec::new();
+        Self::parse_each(text)
+            .into_iter()
+            .map(|parsed| parsed.map_err(|e| OrchestratorError::IntentParseError(e.to_string())))
+            .collect()
+    }
+
+    /// Parse every intent in the text on its own, so a malformed intent doesn't
+    /// take the rest of its block down with it
+    pub fn parse_each(text: &str) -> Vec<std::result::Result<Intent, IntentError>> {
+        let mut results = Vec::new();
 
         // Find all [INTENTS] blocks
         let start_marker = "[INTENTS]";
         let end_marker = "[/INTENTS]";
+        if !text.contains(start_marker) {
+            results = json::parse(text);
+        }
 
         let mut remaining = text;
 
@@ -35,8 +69,7 @@ impl IntentParser {
 
             if let Some(end_idx) = block_text.find(end_marker) {
                 let block_content = block_text[..end_idx].trim();
-                let parsed = Self::parse_block(block_content)?;
-                intents.extend(parsed);
+                results.extend(Self::parse_block(block_content));
 
                 // Move past this block
                 remaining = &block_text[end_idx + end_marker.len()..];
@@ -46,11 +79,32 @@ impl IntentParser {
             }
         }
 
-        Ok(intents)
+        for (index, parsed) in results.iter_mut().enumerate() {
+            if let Err(e) = parsed {
+                e.index = index;
+            }
+        }
+        results
+    }
+
+    /// JSON Schema of the JSON intent format, for prompts and constrained decoding
+    pub fn json_schema() -> serde_json::Value {
+        json::schema()
     }
 
     /// Parse a single INTENT block
-    fn parse_block(block: &str) -> Result<Vec<Intent>> {
+    fn parse_block(block: &str) -> Vec<std::result::Result<Intent, IntentError>> {
+        if block.starts_with('{') || block.starts_with('[') {
+            return match serde_json::from_str(block) {
+                Ok(value) => json::parse_value(value),
+                Err(e) => vec![Err(IntentError::new(
+                    None,
+                    None,
+                    format!("invalid JSON: {}", e),
+                ))],
+            };
+        }
+
         let mut intents = Vec::new();
         let lines: Vec<&str> = block
             .lines()
@@ -60,14 +114,8 @@ impl IntentParser {
 
         let mut i = 0;
         while i < lines.len() {
-            if lines[i].starts_with("INTENT:") {
-                let intent_type = lines[i]
-                    .strip_prefix("INTENT:")
-                    .ok_or_else(|| {
-                        OrchestratorError::IntentParseError("Invalid INTENT line".to_string())
-                    })?
-                    .trim();
-
+            if let Some(intent_type) = lines[i].strip_prefix("INTENT:") {
+                let intent_type = intent_type.trim();
                 let mut fields = std::collections::HashMap::new();
                 i += 1;
 
@@ -82,13 +130,18 @@ impl IntentParser {
                 }
 
                 // Parse the intent
-                let intent = Self::parse_intent(intent_type, &fields)?;
-                intents.push(intent);
+                intents.push(Self::parse_intent(intent_type, &fields).map_err(|e| {
+                    let message = match e {
+                        OrchestratorError::IntentParseError(message) => message,
+                        other => other.to_string(),
+                    };
+                    IntentError::new(Some(intent_type), None, message)
+                }));
             }
             i += 1;
         }
 
-        Ok(intents)
+        intents
     }
 
     /// Parse a single INTENT from its type and fields
@@ -209,8 +262,97 @@ impl IntentParser {
                     .clone(),
                 context: fields.get("CONTEXT").cloned(),
             }),
+            "HELP" => Ok(Intent::Help {
+                actor: fields
+                    .get("ACTOR")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing ACTOR".to_string())
+                    })?
+                    .clone(),
+                target: fields
+                    .get("TARGET")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing TARGET".to_string())
+                    })?
+                    .clone(),
+                against: fields.get("AGAINST").cloned(),
+            }),
+            "LEGENDARY_ACTION" => Ok(Intent::LegendaryAction {
+                actor: fields
+                    .get("ACTOR")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing ACTOR".to_string())
+                    })?
+                    .clone(),
+                action: fields
+                    .get("ACTION")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing ACTION".to_string())
+                    })?
+                    .clone(),
+                target: fields.get("TARGET").cloned(),
+            }),
+            "LAIR_ACTION" => Ok(Intent::LairAction {
+                actor: fields
+                    .get("ACTOR")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing ACTOR".to_string())
+                    })?
+                    .clone(),
+                action: fields
+                    .get("ACTION")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing ACTION".to_string())
+                    })?
+                    .clone(),
+            }),
+            "ROLL_TABLE" => Ok(Intent::RollTable {
+                table: fields
+                    .get("TABLE")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing TABLE".to_string())
+                    })?
+                    .clone(),
+                region: fields.get("REGION").cloned(),
+                party_level: fields.get("LEVEL").and_then(|l| l.trim().parse().ok()),
+            }),
+            "TRAVEL" => Ok(Intent::Travel {
+                destination: fields
+                    .get("DESTINATION")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing DESTINATION".to_string())
+                    })?
+                    .clone(),
+                pace: fields.get("PACE").cloned(),
+            }),
+            "CHANGE_SCENE" => Ok(Intent::ChangeScene {
+                scene: fields
+                    .get("SCENE")
+                    .ok_or_else(|| {
+                        OrchestratorError::IntentParseError("Missing SCENE".to_string())
+                    })?
+                    .clone(),
+                actors: fields
+                    .get("ACTORS")
+                    .map(|s| {
+                        s.split(',')
+                            .map(|t| t.trim().to_string())
+                            .filter(|t| !t.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }),
             "COMBAT_START" => Ok(Intent::CombatStart {
                 reason: fields.get("REASON").cloned(),
+                surprised: fields
+                    .get("SURPRISED")
+                    .map(|s| {
+                        s.split(',')
+                            .map(|t| t.trim().to_string())
+                            .filter(|t| !t.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
             }),
             "COMBAT_END" => Ok(Intent::CombatEnd {
                 reason: fields.get("REASON").cloned(),
@@ -0,0 +1,754 @@
+//! Entity resolution
+//!
+//! Matches the names an LLM uses for creatures, weapons, spells and items
+//! ("npc_goblin_02", "the nearest goblin", "Espada Longa") to what is actually in
+//! the session. Names are compared without case or accents, Portuguese names are
+//! tried in English too, aliases and stat blocks count as names, and small typos
+//! are tolerated. When several things match equally well the resolver reports the
+//! ambiguity instead of guessing.
+
+use super::types::Intent;
+use super::validation::IntentError;
+use crate::session::GameSession;
+use game_engine::{Actor, GameSession as EngineGameSession};
+use rules5e_service::{Spell, SpellDatabase, Weapon, WeaponDatabase};
+use std::fmt;
+use uuid::Uuid;
+
+/// Articles, prepositions and id prefixes that don't help tell things apart
+const STOPWORDS: [&str; 21] = [
+    "the", "a", "an", "of", "o", "os", "as", "um", "uma", "de", "do", "da", "dos", "das", "mais",
+    "npc", "pc", "player", "monster", "weapon", "spell",
+];
+
+/// Words asking for the closest match
+const NEAREST: [&str; 3] = ["nearest", "closest", "proximo"];
+
+/// Words asking for the farthest match
+const FARTHEST: [&str; 3] = ["farthest", "furthest", "distante"];
+
+/// Portuguese names with their English equivalents
+const PORTUGUESE: &[(&str, &str)] = &[
+    // Weapons
+    ("espada longa", "longsword"),
+    ("espada curta", "shortsword"),
+    ("espada grande", "greatsword"),
+    ("montante", "greatsword"),
+    ("adaga", "dagger"),
+    ("punhal", "dagger"),
+    ("clava", "club"),
+    ("clava grande", "greatclub"),
+    ("machadinha", "handaxe"),
+    ("machado de batalha", "battleaxe"),
+    ("machado grande", "greataxe"),
+    ("azagaia", "javelin"),
+    ("dardo", "dart"),
+    ("martelo leve", "light hammer"),
+    ("martelo de guerra", "warhammer"),
+    ("maca", "mace"),
+    ("bordao", "quarterstaff"),
+    ("cajado", "quarterstaff"),
+    ("foice", "sickle"),
+    ("lanca", "spear"),
+    ("lanca de montaria", "lance"),
+    ("besta leve", "light crossbow"),
+    ("besta de mao", "hand crossbow"),
+    ("besta pesada", "heavy crossbow"),
+    ("arco curto", "shortbow"),
+    ("arco longo", "longbow"),
+    ("funda", "sling"),
+    ("mangual", "flail"),
+    ("alabarda", "halberd"),
+    ("malho", "maul"),
+    ("estrela da manha", "morningstar"),
+    ("pique", "pike"),
+    ("rapieira", "rapier"),
+    ("cimitarra", "scimitar"),
+    ("tridente", "trident"),
+    ("picareta de guerra", "war pick"),
+    ("chicote", "whip"),
+    ("zarabatana", "blowgun"),
+    ("rede", "net"),
+    // Items
+    ("pocao de cura", "potion of healing"),
+    ("pocao", "potion"),
+    ("pergaminho", "scroll"),
+    ("varinha", "wand"),
+    ("flecha", "arrow"),
+    ("flechas", "arrows"),
+    ("virote", "crossbow bolt"),
+    ("virotes", "crossbow bolts"),
+    ("tocha", "torch"),
+    ("corda", "rope"),
+    // Creatures
+    ("lobo", "wolf"),
+    ("esqueleto", "skeleton"),
+    ("zumbi", "zombie"),
+    ("aranha", "spider"),
+    ("dragao", "dragon"),
+    ("urso", "bear"),
+    ("arqueiro", "archer"),
+    ("guarda", "guard"),
+    ("bandido", "bandit"),
+];
+
+/// A name that didn't resolve to exactly one thing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    NotFound {
+        kind: &'static str,
+        query: String,
+        /// What could have been meant (creatures here, items carried)
+        known: Vec<String>,
+    },
+    Ambiguous {
+        query: String,
+        candidates: Vec<String>,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NotFound { kind, query, known } => {
+                write!(f, "no {} matching \"{}\"", kind, query)?;
+                if !known.is_empty() {
+                    write!(f, " ({})", known.join(", "))?;
+                }
+                Ok(())
+            }
+            ResolveError::Ambiguous { query, candidates } => {
+                write!(f, "\"{}\" could be {}", query, candidates.join(", "))
+            }
+        }
+    }
+}
+
+/// Which of several matches the name asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Nearest,
+    Farthest,
+}
+
+/// A name broken into comparable words, as written and translated from Portuguese
+struct Query {
+    variants: Vec<Vec<String>>,
+    order: Option<Order>,
+}
+
+impl Query {
+    fn new(text: &str) -> Self {
+        let mut words = words(text);
+        let order = if words.iter().any(|w| NEAREST.contains(&w.as_str())) {
+            Some(Order::Nearest)
+        } else if words.iter().any(|w| FARTHEST.contains(&w.as_str())) {
+            Some(Order::Farthest)
+        } else {
+            None
+        };
+        words.retain(|w| !NEAREST.contains(&w.as_str()) && !FARTHEST.contains(&w.as_str()));
+        let translated = translate(&words);
+        let mut variants = vec![words];
+        if translated != variants[0] {
+            variants.push(translated);
+        }
+        Self { variants, order }
+    }
+
+    /// How well the query matches something with these names and describing words:
+    /// 0 for the same name, 1 when every word appears, 2 when every word is a typo
+    /// away from one; `None` for no match
+    fn tier(&self, names: &[&str], description: &[&str]) -> Option<u8> {
+        let names: Vec<Vec<String>> = names.iter().map(|n| words(n)).collect();
+        let mut vocabulary: Vec<String> = names.iter().flatten().cloned().collect();
+        vocabulary.extend(description.iter().flat_map(|d| words(d)));
+        self.variants
+            .iter()
+            .filter(|query| !query.is_empty())
+            .filter_map(|query| {
+                if names.iter().any(|n| n.concat() == query.concat()) {
+                    Some(0)
+                } else if query.iter().all(|w| vocabulary.contains(w)) {
+                    Some(1)
+                } else if query
+                    .iter()
+                    .all(|w| vocabulary.iter().any(|v| is_typo(w, v)))
+                {
+                    Some(2)
+                } else {
+                    None
+                }
+            })
+            .min()
+    }
+}
+
+/// Finds session creatures by id, name, alias or description
+pub struct EntityResolver<'a> {
+    engine: &'a EngineGameSession,
+}
+
+impl<'a> EntityResolver<'a> {
+    pub fn new(engine: &'a EngineGameSession) -> Self {
+        Self { engine }
+    }
+
+    /// Creature matching `query`, preferring the current scene over the rest of the
+    /// session. "Nearest"/"farthest" are measured from `from`.
+    pub fn actor(&self, query: &str, from: Option<Uuid>) -> Result<&'a Actor, ResolveError> {
+        if let Some(actor) = Uuid::parse_str(query)
+            .ok()
+            .and_then(|id| self.engine.get_actor(id))
+        {
+            return Ok(actor);
+        }
+
+        let parsed = Query::new(query);
+        let mut here = self.engine.scene_actors();
+        here.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut everyone: Vec<&Actor> = self.engine.actors.values().collect();
+        everyone.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for pool in [&here, &everyone] {
+            let matches = best_matches(pool.iter().copied(), |actor| {
+                let mut names = vec![actor.name.as_str()];
+                names.extend(actor.aliases.iter().map(String::as_str));
+                let description: Vec<&str> = actor.stat_block.iter().map(String::as_str).collect();
+                parsed.tier(&names, &description)
+            });
+            if matches.is_empty() {
+                continue;
+            }
+            return self.pick(query, matches, parsed.order, from);
+        }
+        Err(ResolveError::NotFound {
+            kind: "creature",
+            query: query.to_string(),
+            known: here.iter().map(|a| a.name.clone()).collect(),
+        })
+    }
+
+    /// How intents should name `actor`: its name, or its id when the name is shared
+    pub fn reference(&self, actor: &Actor) -> String {
+        let shared = self
+            .engine
+            .actors
+            .values()
+            .any(|other| other.id != actor.id && other.name == actor.name);
+        if shared {
+            actor.id.to_string()
+        } else {
+            actor.name.clone()
+        }
+    }
+
+    fn pick(
+        &self,
+        query: &str,
+        mut matches: Vec<&'a Actor>,
+        order: Option<Order>,
+        from: Option<Uuid>,
+    ) -> Result<&'a Actor, ResolveError> {
+        let origin = from.and_then(|id| self.engine.get_actor(id));
+        if let (Some(order), Some(origin)) = (order, origin) {
+            matches.retain(|a| a.id != origin.id);
+            let distance = |a: &Actor| {
+                let (dx, dy) = (
+                    a.position.0 - origin.position.0,
+                    a.position.1 - origin.position.1,
+                );
+                dx.abs().max(dy.abs())
+            };
+            let best = matches
+                .iter()
+                .map(|a| distance(a))
+                .fold(None, |best: Option<f32>, d| {
+                    Some(match (best, order) {
+                        (None, _) => d,
+                        (Some(b), Order::Nearest) => b.min(d),
+                        (Some(b), Order::Farthest) => b.max(d),
+                    })
+                });
+            matches.retain(|a| Some(distance(a)) == best);
+        }
+        if matches.len() == 1 {
+            return Ok(matches[0]);
+        }
+        Err(ResolveError::Ambiguous {
+            query: query.to_string(),
+            candidates: matches
+                .iter()
+                .map(|a| {
+                    let shared = matches.iter().filter(|b| b.name == a.name).count() > 1;
+                    if shared {
+                        format!("{} ({})", a.name, a.id)
+                    } else {
+                        a.name.clone()
+                    }
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Weapon matching `query` ("longsword", "Long Sword", "Espada Longa")
+pub fn resolve_weapon(query: &str) -> Result<Weapon, ResolveError> {
+    let parsed = Query::new(query);
+    let matches = best_matches(WeaponDatabase::all_weapons(), |weapon| {
+        parsed.tier(&[weapon.name.as_str()], &[])
+    });
+    single("weapon", query, matches, |w| w.name.clone(), Vec::new())
+}
+
+/// Spell matching `query`
+pub fn resolve_spell<'s>(
+    spells: &'s SpellDatabase,
+    query: &str,
+) -> Result<&'s Spell, ResolveError> {
+    let parsed = Query::new(query);
+    let mut known = spells.list_spells();
+    known.sort_by(|a, b| a.name.cmp(&b.name));
+    let matches = best_matches(known, |spell| parsed.tier(&[spell.name.as_str()], &[]));
+    single("spell", query, matches, |s| s.name.clone(), Vec::new())
+}
+
+/// Name of the item in `actor`'s inventory matching `query`
+pub fn resolve_item(actor: &Actor, query: &str) -> Result<String, ResolveError> {
+    let parsed = Query::new(query);
+    let carried: Vec<&str> = actor.inventory.items().map(|(name, _)| name).collect();
+    let matches = best_matches(carried.iter().copied(), |item| parsed.tier(&[item], &[]));
+    single(
+        "item carried",
+        query,
+        matches,
+        |item: &&str| item.to_string(),
+        carried.iter().map(|item| item.to_string()).collect(),
+    )
+    .map(String::from)
+}
+
+/// Rewrite the names in `intent` to the creatures, weapons, spells and items they
+/// resolve to. Names that don't resolve are left as they are and reported.
+pub fn resolve_intent(
+    intent: &Intent,
+    game_session: &GameSession,
+    spells: &SpellDatabase,
+) -> (Intent, Vec<IntentError>) {
+    let mut resolved = intent.clone();
+    let mut fields = Fields {
+        resolver: game_session.engine_session().map(EntityResolver::new),
+        spells,
+        intent: intent.type_name(),
+        errors: Vec::new(),
+    };
+    match &mut resolved {
+        Intent::MeleeAttack {
+            actor,
+            target,
+            weapon,
+            ..
+        }
+        | Intent::RangedAttack {
+            actor,
+            target,
+            weapon,
+            ..
+        } => {
+            let from = fields.actor("actor", actor, None);
+            fields.actor("target", target, from);
+            fields.weapon(weapon);
+        }
+        Intent::SpellCast {
+            actor,
+            spell,
+            targets,
+            ..
+        } => {
+            let from = fields.actor("actor", actor, None);
+            for target in targets {
+                fields.actor("targets", target, from);
+            }
+            fields.spell(spell);
+        }
+        Intent::UseItem {
+            actor,
+            item_id,
+            targets,
+        } => {
+            let from = fields.actor("actor", actor, None);
+            fields.item(from, item_id);
+            for target in targets {
+                fields.actor("targets", target, from);
+            }
+        }
+        Intent::Help {
+            actor,
+            target,
+            against,
+        } => {
+            let from = fields.actor("actor", actor, None);
+            fields.actor("target", target, from);
+            if let Some(against) = against {
+                fields.actor("against", against, from);
+            }
+        }
+        Intent::LegendaryAction { actor, target, .. } => {
+            let from = fields.actor("actor", actor, None);
+            if let Some(target) = target {
+                fields.actor("target", target, from);
+            }
+        }
+        Intent::SkillCheck { actor, .. }
+        | Intent::InvestigateArea { actor, .. }
+        | Intent::SearchItem { actor, .. }
+        | Intent::InteractObject { actor, .. }
+        | Intent::ReadyAction { actor, .. }
+        | Intent::Dash { actor }
+        | Intent::Disengage { actor }
+        | Intent::LairAction { actor, .. } => {
+            fields.actor("actor", actor, None);
+        }
+        Intent::CombatStart { surprised, .. } => {
+            for name in surprised {
+                fields.actor("surprised", name, None);
+            }
+        }
+        Intent::ChangeScene { actors, .. } => {
+            for name in actors {
+                fields.actor("actors", name, None);
+            }
+        }
+        _ => {}
+    }
+    (resolved, fields.errors)
+}
+
+/// Resolves an intent's fields in place, collecting what didn't resolve
+struct Fields<'a> {
+    resolver: Option<EntityResolver<'a>>,
+    spells: &'a SpellDatabase,
+    intent: &'static str,
+    errors: Vec<IntentError>,
+}
+
+impl Fields<'_> {
+    fn fail(&mut self, field: &str, error: ResolveError) {
+        self.errors.push(IntentError::new(
+            Some(self.intent),
+            Some(field),
+            error.to_string(),
+        ));
+    }
+
+    /// Creatures are only resolved once the session has a game engine
+    fn actor(&mut self, field: &str, name: &mut String, from: Option<Uuid>) -> Option<Uuid> {
+        let resolver = self.resolver.as_ref()?;
+        match resolver.actor(name, from) {
+            Ok(actor) => {
+                *name = resolver.reference(actor);
+                Some(actor.id)
+            }
+            Err(e) => {
+                self.fail(field, e);
+                None
+            }
+        }
+    }
+
+    fn weapon(&mut self, weapon: &mut Option<String>) {
+        let Some(name) = weapon else { return };
+        match resolve_weapon(name) {
+            Ok(found) => *name = found.name,
+            Err(e) => self.fail("weapon", e),
+        }
+    }
+
+    /// Spells are only resolved against a database that has some
+    fn spell(&mut self, spell: &mut String) {
+        if self.spells.list_spells().is_empty() {
+            return;
+        }
+        match resolve_spell(self.spells, spell) {
+            Ok(found) => *spell = found.name.clone(),
+            Err(e) => self.fail("spell", e),
+        }
+    }
+
+    fn item(&mut self, owner: Option<Uuid>, item: &mut String) {
+        let Some(actor) = owner.and_then(|id| self.resolver.as_ref()?.engine.get_actor(id)) else {
+            return;
+        };
+        match resolve_item(actor, item) {
+            Ok(found) => *item = found,
+            Err(e) => self.fail("item_id", e),
+        }
+    }
+}
+
+/// Helper function to keep the candidates with the best match tier
+fn best_matches<T>(
+    candidates: impl IntoIterator<Item = T>,
+    tier: impl Fn(&T) -> Option<u8>,
+) -> Vec<T> {
+    let scored: Vec<(u8, T)> = candidates
+        .into_iter()
+        .filter_map(|c| tier(&c).map(|t| (t, c)))
+        .collect();
+    let Some(best) = scored.iter().map(|(t, _)| *t).min() else {
+        return Vec::new();
+    };
+    scored
+        .into_iter()
+        .filter(|(t, _)| *t == best)
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Helper function to turn a list of matches into one result or an error
+fn single<T>(
+    kind: &'static str,
+    query: &str,
+    mut matches: Vec<T>,
+    name: impl Fn(&T) -> String,
+    known: Vec<String>,
+) -> Result<T, ResolveError> {
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(ResolveError::NotFound {
+            kind,
+            query: query.to_string(),
+            known,
+        }),
+        _ => Err(ResolveError::Ambiguous {
+            query: query.to_string(),
+            candidates: matches.iter().map(name).collect(),
+        }),
+    }
+}
+
+/// Helper function to split a name into lowercase, accent-free words, without
+/// stopwords and with numbers normalised ("npc_Goblin_02" -> ["goblin", "2"])
+fn words(text: &str) -> Vec<String> {
+    let folded: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect();
+    folded
+        .split_whitespace()
+        .filter(|w| !STOPWORDS.contains(w))
+        .map(|w| {
+            if w.chars().all(|c| c.is_ascii_digit()) {
+                let number = w.trim_start_matches('0');
+                if number.is_empty() { "0" } else { number }.to_string()
+            } else {
+                w.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Helper function to replace Portuguese words and phrases with their English
+/// equivalents, longest phrases first
+fn translate(query: &[String]) -> Vec<String> {
+    let mut phrases: Vec<(Vec<String>, Vec<String>)> = PORTUGUESE
+        .iter()
+        .map(|(pt, en)| (words(pt), words(en)))
+        .collect();
+    phrases.sort_by_key(|(pt, _)| std::cmp::Reverse(pt.len()));
+
+    let mut translated = Vec::new();
+    let mut i = 0;
+    while i < query.len() {
+        match phrases
+            .iter()
+            .find(|(pt, _)| query[i..].starts_with(pt.as_slice()))
+        {
+            Some((pt, en)) => {
+                translated.extend(en.iter().cloned());
+                i += pt.len();
+            }
+            None => {
+                translated.push(query[i].clone());
+                i += 1;
+            }
+        }
+    }
+    translated
+}
+
+/// Helper function to tell whether `word` is a small typo of `other`; numbers have
+/// to match exactly
+fn is_typo(word: &str, other: &str) -> bool {
+    if word.chars().any(|c| c.is_ascii_digit()) {
+        return word == other;
+    }
+    let allowed = match word.chars().count().max(other.chars().count()) {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    };
+    edit_distance(word, other) <= allowed
+}
+
+/// Helper function to count the edits (insertions, deletions, substitutions and
+/// swaps of neighbouring letters) between two words
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_engine::ActorType;
+
+    fn goblin_camp() -> GameSession {
+        let mut game_session = GameSession::new();
+        let engine = game_session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Camp".to_string());
+        let mut rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        rogue.inventory.add("Potion of Healing", 2);
+        let mut actors = vec![rogue];
+        for (name, x) in [("Goblin 1", 5.0), ("Goblin 2", 8.0)] {
+            let mut goblin = Actor::new(name.to_string(), ActorType::Monster)
+                .with_stat_block("Goblin".to_string());
+            goblin.set_position(x, 0.0, 0.0);
+            actors.push(goblin);
+        }
+        let mut archer = Actor::new("Snaga".to_string(), ActorType::Monster)
+            .with_stat_block("Goblin Archer".to_string());
+        archer.set_position(2.0, 0.0, 0.0);
+        actors.push(archer);
+        actors.push(
+            Actor::new("Grukk".to_string(), ActorType::Monster)
+                .with_alias("the orc chief".to_string()),
+        );
+        for actor in actors {
+            engine.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+        game_session
+    }
+
+    #[test]
+    fn test_resolve_creatures_by_name_alias_and_context() {
+        let game_session = goblin_camp();
+        let engine = game_session.engine_session().unwrap();
+        let resolver = EntityResolver::new(engine);
+        let name =
+            |query: &str, from: Option<Uuid>| resolver.actor(query, from).map(|a| a.name.clone());
+        let rogue = resolver.actor("Rogue", None).unwrap().id;
+
+        assert_eq!(name("npc_goblin_02", None).unwrap(), "Goblin 2");
+        assert_eq!(name("the goblin archer", None).unwrap(), "Snaga");
+        assert_eq!(name("The Orc Chief", None).unwrap(), "Grukk");
+        assert_eq!(name("rouge", None).unwrap(), "Rogue");
+        assert_eq!(name("o goblin mais próximo", Some(rogue)).unwrap(), "Snaga");
+        assert_eq!(
+            name("the farthest goblin", Some(rogue)).unwrap(),
+            "Goblin 2"
+        );
+
+        match resolver.actor("goblin", None) {
+            Err(ResolveError::Ambiguous { candidates, .. }) => {
+                assert_eq!(candidates, vec!["Goblin 1", "Goblin 2", "Snaga"]);
+            }
+            other => panic!("Expected ambiguity, got {:?}", other.map(|a| &a.name)),
+        }
+        let missing = resolver.actor("dragon", None).unwrap_err().to_string();
+        assert_eq!(
+            missing,
+            "no creature matching \"dragon\" (Goblin 1, Goblin 2, Grukk, Rogue, Snaga)"
+        );
+    }
+
+    #[test]
+    fn test_resolve_weapons_items_and_intents() {
+        for query in [
+            "longsword",
+            "Long Sword",
+            "LONGSWORD",
+            "Espada Longa",
+            "longswrod",
+        ] {
+            assert_eq!(
+                resolve_weapon(query).unwrap().name,
+                "Longsword",
+                "{}",
+                query
+            );
+        }
+        assert_eq!(resolve_weapon("besta leve").unwrap().name, "Light Crossbow");
+        assert!(resolve_weapon("banana").is_err());
+
+        let game_session = goblin_camp();
+        let intent = Intent::MeleeAttack {
+            actor: "player_rogue".to_string(),
+            target: "npc_goblin_02".to_string(),
+            weapon: Some("Espada Longa".to_string()),
+            move_required: true,
+        };
+        let (resolved, errors) = resolve_intent(&intent, &game_session, &SpellDatabase::new());
+        assert!(errors.is_empty());
+        assert_eq!(
+            resolved,
+            Intent::MeleeAttack {
+                actor: "Rogue".to_string(),
+                target: "Goblin 2".to_string(),
+                weapon: Some("Longsword".to_string()),
+                move_required: true,
+            }
+        );
+
+        let use_potion = Intent::UseItem {
+            actor: "Rogue".to_string(),
+            item_id: "poção de cura".to_string(),
+            targets: vec![],
+        };
+        let (resolved, _) = resolve_intent(&use_potion, &game_session, &SpellDatabase::new());
+        assert!(
+            matches!(resolved, Intent::UseItem { item_id, .. } if item_id == "Potion of Healing")
+        );
+
+        let vague = Intent::Help {
+            actor: "Rogue".to_string(),
+            target: "Grukk".to_string(),
+            against: Some("goblin".to_string()),
+        };
+        let (_, errors) = resolve_intent(&vague, &game_session, &SpellDatabase::new());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field.as_deref(), Some("against"));
+        assert!(errors[0]
+            .message
+            .contains("could be Goblin 1, Goblin 2, Snaga"));
+    }
+}
@@ -1,9 +1,26 @@
 //! INTENT types and definitions
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+fn yes() -> bool {
+    true
+}
+
+fn first_level() -> u8 {
+    1
+}
+
 /// INTENT enum representing all possible intents
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Serialized as the JSON intent format: an object tagged with its type name,
+/// e.g. `{"intent": "DASH", "actor": "player_1"}`. Unknown keys are rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(
+    tag = "intent",
+    rename_all = "SCREAMING_SNAKE_CASE",
+    deny_unknown_fields
+)]
 pub enum Intent {
     // Social/Roleplay
     SkillCheck {
@@ -11,6 +28,7 @@ pub enum Intent {
         skill: String,
         target: Option<String>,
         context: Option<String>,
+        #[serde(default = "yes")]
         suggest_dc: bool,
     },
     LoreQuery {
@@ -30,6 +48,15 @@ pub enum Intent {
         description: String,
     },
 
+    /// Roll on a random table (encounters, rumors, trinkets, weather) for the DM to narrate
+    RollTable {
+        table: String,
+        /// Region for region-specific rows; defaults to where the party is traveling
+        region: Option<String>,
+        /// Party level for level-banded rows; defaults to the party's average level
+        party_level: Option<u32>,
+    },
+
     // Exploration
     InvestigateArea {
         actor: String,
@@ -43,30 +70,50 @@ pub enum Intent {
         actor: String,
         object_id: String,
     },
+    /// Overland travel to a location on the map, watch by watch
+    Travel {
+        destination: String,
+        /// "fast", "normal" or "slow"; keeps the current pace when omitted
+        pace: Option<String>,
+    },
+    /// Move the party, or some of it, to another scene (created if it doesn't exist yet)
+    ChangeScene {
+        scene: String,
+        /// Creatures moving; empty moves the whole party
+        #[serde(default)]
+        actors: Vec<String>,
+    },
 
     // Combat
     MeleeAttack {
         actor: String,
         target: String,
         weapon: Option<String>,
+        #[serde(default)]
         move_required: bool,
     },
     RangedAttack {
         actor: String,
         target: String,
         weapon: Option<String>,
+        #[serde(default)]
         move_required: bool,
     },
     SpellCast {
         actor: String,
         spell: String,
+        #[serde(default = "first_level")]
         slot_level: u8,
         area_center: Option<(i32, i32)>,
+        #[serde(default)]
         targets: Vec<String>,
     },
     UseItem {
         actor: String,
         item_id: String,
+        /// Creatures a scroll or wand is aimed at
+        #[serde(default)]
+        targets: Vec<String>,
     },
     ReadyAction {
         actor: String,
@@ -80,10 +127,27 @@ pub enum Intent {
     },
     Help {
         actor: String,
+        /// Creature receiving the help
         target: String,
+        /// Enemy the helped creature's next attack is against; `None` helps an ability check
+        against: Option<String>,
+    },
+    /// A legendary creature spends a legendary action at the end of another creature's turn
+    LegendaryAction {
+        actor: String,
+        action: String,
+        target: Option<String>,
+    },
+    /// The lair owner's lair acts on initiative count 20
+    LairAction {
+        actor: String,
+        action: String,
     },
     CombatStart {
         reason: Option<String>,
+        /// Creatures caught by surprise (they skip round 1)
+        #[serde(default)]
+        surprised: Vec<String>,
     },
     CombatEnd {
         reason: Option<String>,
@@ -97,6 +161,7 @@ pub enum Intent {
     GenerateScene {
         scene_id: String,
         style: Option<String>,
+        #[serde(default)]
         prompts: Vec<String>,
     },
     GenerateBattlemap {
@@ -114,9 +179,12 @@ impl Intent {
             Intent::RuleQuery { .. } => "RULE_QUERY",
             Intent::NpcDialogue { .. } => "NPC_DIALOGUE",
             Intent::SceneEvent { .. } => "SCENE_EVENT",
+            Intent::RollTable { .. } => "ROLL_TABLE",
             Intent::InvestigateArea { .. } => "INVESTIGATE_AREA",
             Intent::SearchItem { .. } => "SEARCH_ITEM",
             Intent::InteractObject { .. } => "INTERACT_OBJECT",
+            Intent::Travel { .. } => "TRAVEL",
+            Intent::ChangeScene { .. } => "CHANGE_SCENE",
             Intent::MeleeAttack { .. } => "MELEE_ATTACK",
             Intent::RangedAttack { .. } => "RANGED_ATTACK",
             Intent::SpellCast { .. } => "SPELL_CAST",
@@ -125,6 +193,8 @@ impl Intent {
             Intent::Dash { .. } => "DASH",
             Intent::Disengage { .. } => "DISENGAGE",
             Intent::Help { .. } => "HELP",
+            Intent::LegendaryAction { .. } => "LEGENDARY_ACTION",
+            Intent::LairAction { .. } => "LAIR_ACTION",
             Intent::CombatStart { .. } => "COMBAT_START",
             Intent::CombatEnd { .. } => "COMBAT_END",
             Intent::GeneratePortrait { .. } => "GENERATE_PORTRAIT",
@@ -132,4 +202,25 @@ impl Intent {
             Intent::GenerateBattlemap { .. } => "GENERATE_BATTLEMAP",
         }
     }
+
+    /// Creature acting, for intents that are a creature's action
+    pub fn actor(&self) -> Option<&str> {
+        match self {
+            Intent::SkillCheck { actor, .. }
+            | Intent::InvestigateArea { actor, .. }
+            | Intent::SearchItem { actor, .. }
+            | Intent::InteractObject { actor, .. }
+            | Intent::MeleeAttack { actor, .. }
+            | Intent::RangedAttack { actor, .. }
+            | Intent::SpellCast { actor, .. }
+            | Intent::UseItem { actor, .. }
+            | Intent::ReadyAction { actor, .. }
+            | Intent::Dash { actor }
+            | Intent::Disengage { actor }
+            | Intent::Help { actor, .. }
+            | Intent::LegendaryAction { actor, .. }
+            | Intent::LairAction { actor, .. } => Some(actor),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,505 @@
+//! INTENT validation
+//!
+//! Checks an intent against the session before it runs: every reference has to
+//! resolve to a real creature, weapon, spell, item or table, slot levels have to
+//! be legal and targets in range. Problems come back as [`IntentError`]s, compact
+//! enough to hand back to the LLM for repair.
+
+use super::executor::{find_actor_id, item_cost, resolve_actor_id};
+use super::types::Intent;
+use crate::session::GameSession;
+use game_engine::{ActionCost, Actor, TravelPace, SQUARE_FEET};
+use rules5e_service::{
+    ItemDatabase, RangeBand, Spell, SpellDatabase, SpellRange, TableLibrary, Weapon,
+    WeaponDatabase, WeaponProperty, WeaponType,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Highest spell slot level
+const MAX_SLOT_LEVEL: u8 = 9;
+
+/// An intent that was rejected, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntentError {
+    /// Position of the intent in the LLM output
+    pub index: usize,
+    /// Intent type, when it could be read
+    pub intent: Option<String>,
+    /// Field at fault (as named in the JSON format), when there is one
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl IntentError {
+    pub fn new(intent: Option<&str>, field: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            index: 0,
+            intent: intent.map(String::from),
+            field: field.map(String::from),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for IntentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.index + 1)?;
+        if let Some(intent) = &self.intent {
+            write!(f, " {}", intent)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " {}", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Compact prompt asking the LLM to correct rejected intents
+pub fn repair_prompt(errors: &[IntentError]) -> String {
+    let mut prompt = String::from("These INTENTs were rejected:\n");
+    for error in errors {
+        prompt.push_str(&format!("- {}\n", error));
+    }
+    prompt.push_str(
+        "Resend only corrected versions of them as JSON intents, without narration. \
+         Drop any that can't be fixed.",
+    );
+    prompt
+}
+
+/// Check `intent` against the session. References are only checked once the
+/// session has a game engine; an unknown spell is only an error when `spells`
+/// knows some spells.
+pub fn validate(
+    intent: &Intent,
+    game_session: &GameSession,
+    spells: &SpellDatabase,
+    tables: &TableLibrary,
+    untracked_slots: bool,
+) -> Vec<IntentError> {
+    let mut checks = Checks {
+        game_session,
+        intent: intent.type_name(),
+        errors: Vec::new(),
+    };
+    match intent {
+        Intent::MeleeAttack {
+            actor,
+            target,
+            weapon,
+            move_required,
+        } => {
+            let actor_id = checks.here("actor", actor);
+            let target_id = checks.here("target", target);
+            let weapon = checks.weapon(weapon);
+            if weapon
+                .as_ref()
+                .is_some_and(|w| w.weapon_type == WeaponType::Ranged)
+            {
+                checks.fail("weapon", "is a ranged weapon; use RANGED_ATTACK");
+            }
+            let reach = match &weapon {
+                Some(w) if w.properties.contains(&WeaponProperty::Reach) => 2 * SQUARE_FEET,
+                _ => SQUARE_FEET,
+            };
+            if let Some(distance) = checks.distance(actor_id, target_id) {
+                if distance > reach && !move_required {
+                    checks.fail(
+                        "target",
+                        format!(
+                            "{} is {} ft away, beyond {} ft reach; set move_required",
+                            target, distance, reach
+                        ),
+                    );
+                }
+            }
+        }
+        Intent::RangedAttack {
+            actor,
+            target,
+            weapon,
+            ..
+        } => {
+            let actor_id = checks.here("actor", actor);
+            let target_id = checks.here("target", target);
+            let weapon = checks.weapon(weapon);
+            if weapon.as_ref().is_some_and(|w| {
+                w.weapon_type == WeaponType::Melee
+                    && !w.properties.contains(&WeaponProperty::Thrown)
+            }) {
+                checks.fail("weapon", "is a melee weapon; use MELEE_ATTACK");
+            }
+            let distance = checks.distance(actor_id, target_id);
+            if let (Some(weapon), Some(distance)) = (weapon, distance) {
+                if weapon.range_band(distance.max(0) as u32) == Some(RangeBand::OutOfRange) {
+                    checks.fail(
+                        "target",
+                        format!(
+                            "{} is {} ft away, beyond the {}'s range",
+                            target, distance, weapon.name
+                        ),
+                    );
+                }
+            }
+        }
+        Intent::SpellCast {
+            actor,
+            spell,
+            slot_level,
+            targets,
+            ..
+        } => {
+            let actor_id = checks.here("actor", actor);
+            let target_ids: Vec<_> = targets
+                .iter()
+                .map(|t| (t, checks.here("targets", t)))
+                .collect();
+            if *slot_level > MAX_SLOT_LEVEL {
+                checks.fail("slot_level", format!("must be {} or lower", MAX_SLOT_LEVEL));
+            }
+            match find_spell(spells, spell) {
+                Some(known) => {
+                    let level = known.level.value();
+                    if !known.is_cantrip() && *slot_level < level {
+                        checks.fail(
+                            "slot_level",
+                            format!("{} needs a level {} slot or higher", known.name, level),
+                        );
+                    }
+                    let caster =
+                        actor_id.and_then(|id| game_session.engine_session()?.get_actor(id));
+                    if let (Some(caster), false) = (caster, known.is_cantrip()) {
+                        let slot = (*slot_level).max(level);
+                        if let Some(problem) = slot_problem(caster, slot, untracked_slots) {
+                            checks.fail("slot_level", problem);
+                        }
+                    }
+                    let range = match known.range {
+                        SpellRange::Touch => Some(SQUARE_FEET),
+                        SpellRange::Feet(feet) => Some(feet as i32),
+                        _ => None,
+                    };
+                    for (target, target_id) in target_ids {
+                        if known.range == SpellRange::Self_ && target_id != actor_id {
+                            checks
+                                .fail("targets", format!("{} only affects its caster", known.name));
+                            continue;
+                        }
+                        let Some(range) = range else { continue };
+                        if checks
+                            .distance(actor_id, target_id)
+                            .is_some_and(|distance| distance > range)
+                        {
+                            checks.fail(
+                                "targets",
+                                format!("{} is beyond {}'s {} ft range", target, known.name, range),
+                            );
+                        }
+                    }
+                }
+                None if !spells.list_spells().is_empty() => {
+                    checks.fail("spell", format!("unknown spell \"{}\"", spell));
+                }
+                None => {}
+            }
+        }
+        Intent::UseItem {
+            actor,
+            item_id,
+            targets,
+        } => {
+            let actor = checks
+                .creature("actor", actor)
+                .and_then(|id| game_session.engine_session()?.get_actor(id));
+            for target in targets {
+                checks.here("targets", target);
+            }
+            if let Some(actor) = actor {
+                let item = ItemDatabase::get_item(item_id);
+                let cost = item
+                    .as_ref()
+                    .map_or(ActionCost::Action, |i| item_cost(i.action));
+                let in_combat = game_session.engine_session().is_some_and(|e| e.in_combat());
+                let charges = actor
+                    .charges
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(item_id))
+                    .map(|(_, charges)| charges.current);
+                if actor.inventory.count(item_id) == 0 {
+                    checks.fail("item_id", format!("{} has no {}", actor.name, item_id));
+                } else if in_combat && !actor.has_action(cost) {
+                    checks.fail(
+                        "item_id",
+                        format!(
+                            "{} has already used its {} this turn",
+                            actor.name,
+                            cost.name()
+                        ),
+                    );
+                } else if item.is_some_and(|i| i.charges.is_some()) && charges == Some(0) {
+                    checks.fail("item_id", format!("{} has no charges left", item_id));
+                }
+            }
+        }
+        Intent::Help {
+            actor,
+            target,
+            against,
+        } => {
+            checks.here("actor", actor);
+            checks.here("target", target);
+            if let Some(against) = against {
+                checks.here("against", against);
+            }
+        }
+        Intent::LegendaryAction { actor, target, .. } => {
+            checks.here("actor", actor);
+            if let Some(target) = target {
+                checks.here("target", target);
+            }
+        }
+        Intent::ReadyAction { actor, .. }
+        | Intent::Dash { actor }
+        | Intent::Disengage { actor } => {
+            checks.here("actor", actor);
+        }
+        Intent::SkillCheck { actor, .. }
+        | Intent::InvestigateArea { actor, .. }
+        | Intent::SearchItem { actor, .. }
+        | Intent::InteractObject { actor, .. }
+        | Intent::LairAction { actor, .. } => {
+            checks.creature("actor", actor);
+        }
+        Intent::CombatStart { surprised, .. } => {
+            for name in surprised {
+                checks.here("surprised", name);
+            }
+        }
+        Intent::ChangeScene { actors, .. } => {
+            for name in actors {
+                checks.creature("actors", name);
+            }
+        }
+        Intent::Travel {
+            pace: Some(pace), ..
+        } if TravelPace::parse(pace).is_none() => {
+            checks.fail(
+                "pace",
+                format!("unknown pace \"{}\"; use fast, normal or slow", pace),
+            );
+        }
+        Intent::RollTable { table, .. } if tables.get(table).is_none() => {
+            checks.fail(
+                "table",
+                format!(
+                    "unknown table \"{}\" ({})",
+                    table,
+                    tables.names().join(", ")
+                ),
+            );
+        }
+        _ => {}
+    }
+    checks.errors
+}
+
+/// Helper function to find a spell by name, ignoring case
+fn find_spell<'a>(spells: &'a SpellDatabase, name: &str) -> Option<&'a Spell> {
+    spells.get_spell(name).or_else(|| {
+        spells
+            .list_spells()
+            .into_iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Why `caster` can't spend a level `slot` spell slot; slotless casters need `untracked_slots`
+pub(crate) fn slot_problem(caster: &Actor, slot: u8, untracked_slots: bool) -> Option<String> {
+    if caster.spell_slots.is_empty() {
+        return (!untracked_slots).then(|| {
+            format!(
+                "{} has no spell slots to cast at level {}",
+                caster.name, slot
+            )
+        });
+    }
+    (caster.spell_slots.get(&slot).map_or(0, |s| s.current) == 0)
+        .then(|| format!("{} has no level {} spell slots left", caster.name, slot))
+}
+
+/// Errors collected while checking one intent
+struct Checks<'a> {
+    game_session: &'a GameSession,
+    intent: &'static str,
+    errors: Vec<IntentError>,
+}
+
+impl Checks<'_> {
+    fn fail(&mut self, field: &str, message: impl Into<String>) {
+        self.errors
+            .push(IntentError::new(Some(self.intent), Some(field), message));
+    }
+
+    /// A creature anywhere in the session
+    fn creature(&mut self, field: &str, name: &str) -> Option<Uuid> {
+        let engine = self.game_session.engine_session()?;
+        let id = find_actor_id(engine, name);
+        if id.is_none() {
+            self.fail(field, format!("no creature named \"{}\"", name));
+        }
+        id
+    }
+
+    /// A creature in the current scene, listing who is there when it isn't found
+    fn here(&mut self, field: &str, name: &str) -> Option<Uuid> {
+        let engine = self.game_session.engine_session()?;
+        let id = resolve_actor_id(self.game_session, name);
+        if id.is_none() {
+            let mut present: Vec<&str> = engine
+                .scene_actors()
+                .into_iter()
+                .map(|a| a.name.as_str())
+                .collect();
+            present.sort_unstable();
+            self.fail(
+                field,
+                format!(
+                    "no creature named \"{}\" in this scene ({})",
+                    name,
+                    present.join(", ")
+                ),
+            );
+        }
+        id
+    }
+
+    fn weapon(&mut self, weapon: &Option<String>) -> Option<Weapon> {
+        let name = weapon.as_deref()?;
+        let found = WeaponDatabase::get_weapon(name);
+        if found.is_none() {
+            self.fail("weapon", format!("unknown weapon \"{}\"", name));
+        }
+        found
+    }
+
+    /// Distance in feet on a gridded scene
+    fn distance(&self, actor_id: Option<Uuid>, target_id: Option<Uuid>) -> Option<i32> {
+        let engine = self.game_session.engine_session()?;
+        engine
+            .distance_between(actor_id?, target_id?)
+            .ok()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::IntentParser;
+    use game_engine::{Actor, ActorType, Grid, GridPos};
+
+    fn session_with_goblin() -> GameSession {
+        let mut game_session = GameSession::new();
+        let engine_session = game_session.engine_session_mut().unwrap();
+        let scene_id = engine_session.create_scene("Cave".to_string());
+        engine_session
+            .get_current_scene_mut()
+            .unwrap()
+            .set_grid(Grid::new(10, 10));
+        let rogue = Actor::new("Rogue".to_string(), ActorType::Player);
+        let mut goblin = Actor::new("Goblin".to_string(), ActorType::Monster);
+        goblin.set_grid_position(GridPos::new(6, 0), 0);
+        engine_session.add_actor_to_scene(scene_id, rogue).unwrap();
+        engine_session.add_actor_to_scene(scene_id, goblin).unwrap();
+        game_session
+    }
+
+    #[test]
+    fn test_validate_references_and_range() {
+        let session = session_with_goblin();
+        let spells = SpellDatabase::new();
+        let tables = TableLibrary::builtin();
+        let check = |intent: &Intent| validate(intent, &session, &spells, &tables, false);
+
+        let reachable = Intent::MeleeAttack {
+            actor: "Rogue".to_string(),
+            target: "Goblin".to_string(),
+            weapon: Some("Dagger".to_string()),
+            move_required: true,
+        };
+        assert!(check(&reachable).is_empty());
+
+        let errors = check(&Intent::MeleeAttack {
+            actor: "Rogue".to_string(),
+            target: "Orc".to_string(),
+            weapon: Some("Laser".to_string()),
+            move_required: false,
+        });
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_deref().unwrap()).collect();
+        assert_eq!(fields, vec!["target", "weapon"]);
+        assert!(errors[0].message.contains("(Goblin, Rogue)"));
+
+        let out_of_reach = check(&Intent::MeleeAttack {
+            actor: "Rogue".to_string(),
+            target: "Goblin".to_string(),
+            weapon: Some("Dagger".to_string()),
+            move_required: false,
+        });
+        assert_eq!(out_of_reach.len(), 1);
+        assert!(out_of_reach[0].message.contains("30 ft away"));
+
+        let melee_weapon = check(&Intent::RangedAttack {
+            actor: "Rogue".to_string(),
+            target: "Goblin".to_string(),
+            weapon: Some("Longsword".to_string()),
+            move_required: false,
+        });
+        assert_eq!(melee_weapon[0].field.as_deref(), Some("weapon"));
+
+        let errors = check(&Intent::SpellCast {
+            actor: "Rogue".to_string(),
+            spell: "Fireball".to_string(),
+            slot_level: 12,
+            area_center: None,
+            targets: vec![],
+        });
+        assert_eq!(errors[0].field.as_deref(), Some("slot_level"));
+
+        let errors = check(&Intent::UseItem {
+            actor: "Rogue".to_string(),
+            item_id: "Potion of Healing".to_string(),
+            targets: vec![],
+        });
+        assert_eq!(
+            errors[0].to_string(),
+            "#1 USE_ITEM item_id: Rogue has no Potion of Healing"
+        );
+    }
+
+    #[test]
+    fn test_parse_each_keeps_good_intents() {
+        let text = r#"
+[INTENTS]
+INTENT: SKILL_CHECK
+ACTOR: Rogue
+SKILL: stealth
+END_INTENT
+INTENT: MELEE_ATTACK
+ACTOR: Rogue
+END_INTENT
+INTENT: COMBAT_END
+END_INTENT
+[/INTENTS]
+"#;
+        let results = IntentParser::parse_each(text);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok() && results[2].is_ok());
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.to_string(), "#2 MELEE_ATTACK: Missing TARGET");
+
+        let prompt = repair_prompt(&[error.clone()]);
+        assert!(prompt.contains("- #2 MELEE_ATTACK: Missing TARGET\n"));
+    }
+}
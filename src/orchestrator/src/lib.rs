@@ -6,15 +6,19 @@
 //! - Integration with services (rules5e, memory, game-engine)
 //! - Communication with UI (IPC/WebSocket)
 
+pub mod auth;
 pub mod cache;
 pub mod communication;
+pub mod dm_console;
 pub mod error;
 pub mod fsm;
 pub mod intent;
 pub mod intent_router;
 pub mod llm_client;
 pub mod orchestrator;
+pub mod outbox;
 pub mod pipeline;
+pub mod rolls;
 pub mod services;
 pub mod session;
 
@@ -100,6 +100,9 @@ pub struct LlmRequest {
     pub max_tokens: Option<u32>,
     /// Temperature for generation
     pub temperature: Option<f32>,
+    /// JSON Schema the INTENTs may follow (prompting or constrained decoding)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent_schema: Option<serde_json::Value>,
 }
 
 /// LLM Response
@@ -107,7 +110,7 @@ pub struct LlmRequest {
 pub struct LlmResponse {
     /// Generated narrative text
     pub text: String,
-    /// INTENT DSL blocks (if any)
+    /// INTENT DSL blocks or JSON intents (if any)
     pub intents: Option<String>,
     /// Tokens generated
     pub tokens_generated: Option<u32>,
@@ -6,15 +6,32 @@
 //! 3. Parses and executes INTENTs
 //! 4. Sends updates back to client
 
-use crate::communication::{CommunicationState, IpcMessage, PlayerAction, RollResult};
+use crate::communication::{
+    CombatUpdate, CommunicationState, DmCommandMessage, IpcMessage, Narration, PendingIntents,
+    PlayerAction, RollClosed, RollRequest, RollResult,
+};
+use crate::dm_console::{self, DmCommand};
 use crate::error::{OrchestratorError, Result};
-use crate::intent::{IntentExecutor, IntentParser};
+use crate::fsm::SceneState;
+use crate::intent::executor::resolve_actor_id;
+use crate::intent::validation::repair_prompt;
+use crate::intent::{IntentError, IntentExecutor, IntentParser};
 use crate::llm_client::{LlmClient, LlmRequest};
+use crate::rolls::{RollResume, RollStatus, SettledRoll};
 use crate::services::{SharedTtsClient, TtsClient};
-use crate::session::{GameSession, SessionManager};
+use crate::session::{GameSession, RecordedInput, SessionManager};
+use game_engine::clock::{
+    DAY_SECONDS, HOUR_SECONDS, MAX_ADVANCE_SECONDS, MINUTE_SECONDS, ROUND_SECONDS,
+};
+use game_engine::{GameEvent, GameSession as EngineSession, TurnEvent};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How many times the LLM is asked to fix rejected INTENTs by default
+const MAX_REPAIR_ATTEMPTS: usize = 2;
 
 /// Main Orchestrator
 pub struct Orchestrator {
@@ -28,6 +45,8 @@ pub struct Orchestrator {
     llm_client: Option<Arc<LlmClient>>,
     /// TTS Service client (for voice synthesis)
     tts_client: Option<SharedTtsClient>,
+    /// Repair prompts sent back to the LLM when INTENTs are rejected
+    max_repair_attempts: usize,
 }
 
 impl Orchestrator {
@@ -42,6 +61,7 @@ impl Orchestrator {
             communication,
             llm_client: None, // Will be set when LLM Core is available
             tts_client: Some(Arc::new(TtsClient::new())), // TTS client available by default
+            max_repair_attempts: MAX_REPAIR_ATTEMPTS,
         }
     }
 
@@ -57,6 +77,7 @@ impl Orchestrator {
             communication,
             llm_client: Some(llm_client),
             tts_client: Some(Arc::new(TtsClient::new())),
+            max_repair_attempts: MAX_REPAIR_ATTEMPTS,
         }
     }
 
@@ -70,6 +91,11 @@ impl Orchestrator {
         self.llm_client = Some(llm_client);
     }
 
+    /// Set how many repair prompts may follow an LLM response with rejected INTENTs
+    pub fn set_max_repair_attempts(&mut self, attempts: usize) {
+        self.max_repair_attempts = attempts;
+    }
+
     /// Process a PlayerAction
     ///
     /// Flow:
@@ -79,6 +105,17 @@ impl Orchestrator {
     /// 4. Execute INTENTs
     /// 5. Send updates to client
     pub async fn process_player_action(&self, action: PlayerAction) -> Result<()> {
+        let issuer = action.player_id.clone();
+        self.process_action(action, Some(&issuer)).await
+    }
+
+    /// Process an action sent by the DM, whose INTENTs may act for any creature
+    pub async fn process_dm_action(&self, action: PlayerAction) -> Result<()> {
+        self.process_action(action, None).await
+    }
+
+    /// INTENTs answering the action are checked against what `issuer` controls
+    async fn process_action(&self, action: PlayerAction, issuer: Option<&str>) -> Result<()> {
         info!(
             "Processing PlayerAction: {} from {}",
             action.kind, action.player_id
@@ -94,12 +131,15 @@ impl Orchestrator {
                 OrchestratorError::SessionError(format!("Session not found: {}", session_id))
             })?;
 
+        let was_in_combat = session.current_state() == SceneState::CombatTurnBased;
+
         // Process action based on kind
         match action.kind {
             crate::communication::ActionKind::Voice => {
                 // Voice action: send to LLM Core for INTENT generation
                 if let Some(text) = &action.text {
-                    self.process_voice_action(session, text, &action).await?;
+                    self.process_voice_action(session, text, &action, issuer)
+                        .await?;
                 } else {
                     warn!("Voice action without text");
                 }
@@ -113,6 +153,11 @@ impl Orchestrator {
         // Send scene update to client
         self.send_scene_update(&session_id, session).await?;
 
+        // Keep the initiative tracker in sync while combat starts, runs or ends
+        if was_in_combat || session.current_state() == SceneState::CombatTurnBased {
+            self.send_combat_update(session).await?;
+        }
+
         Ok(())
     }
 
@@ -122,8 +167,17 @@ impl Orchestrator {
         session: &mut GameSession,
         text: &str,
         action: &PlayerAction,
+        issuer: Option<&str>,
     ) -> Result<()> {
         info!("Processing voice action: {}", text);
+        session.record(RecordedInput::PlayerInput {
+            player_id: action.player_id.clone(),
+            text: text.to_string(),
+        });
+        if session.console.llm_paused {
+            info!("LLM paused by the DM, not answering {}", action.player_id);
+            return Ok(());
+        }
 
         // Send to LLM Core for INTENT generation (if available)
         let intent_text = if let Some(ref llm_client) = self.llm_client {
@@ -141,6 +195,7 @@ impl Orchestrator {
                         memory_context: self.get_memory_context(&action.session_id).await,
                         max_tokens: Some(2048),
                         temperature: Some(0.7),
+                        intent_schema: Some(IntentParser::json_schema()),
                     };
 
                     match llm_client.generate_with_intents(&llm_request).await {
@@ -170,54 +225,187 @@ impl Orchestrator {
             self.create_fallback_intent(&action.player_id, text)
         };
 
-        // Parse INTENTs from LLM response
-        let intents = IntentParser::parse(&intent_text)?;
+        self.apply_dm_output(session, &intent_text, issuer).await
+    }
 
-        // Extract narrative text (everything outside INTENT blocks)
-        let narrative = self.extract_narrative(&intent_text);
+    /// Narrate and execute an LLM response (narrative text plus INTENT blocks), or hold
+    /// it for the DM while review is on
+    async fn apply_dm_output(
+        &self,
+        session: &mut GameSession,
+        intent_text: &str,
+        issuer: Option<&str>,
+    ) -> Result<()> {
+        if !session.console.review {
+            return self.execute_dm_output(session, intent_text, issuer).await;
+        }
+        let batch_id = session.console.hold(intent_text, issuer);
+        info!("Holding LLM output {} for the DM", batch_id);
+        self.communication.send_to_dm(
+            &session.session_id,
+            IpcMessage::PendingIntents(PendingIntents {
+                session_id: session.session_id.clone(),
+                batch_id,
+                issuer: issuer.map(String::from),
+                text: intent_text.to_string(),
+            }),
+        )
+    }
 
-        // Send narration to client/TTS
+    /// Narrate and execute an LLM response. INTENTs answering a player's action are
+    /// checked against what that player controls.
+    async fn execute_dm_output(
+        &self,
+        session: &mut GameSession,
+        intent_text: &str,
+        issuer: Option<&str>,
+    ) -> Result<()> {
+        session.record(RecordedInput::LlmOutput {
+            text: intent_text.to_string(),
+            issuer: issuer.map(String::from),
+        });
+
+        // Extract narrative text (everything outside INTENT blocks)
+        let narrative = self.extract_narrative(intent_text);
         if !narrative.trim().is_empty() {
-            let narrative_text = narrative.clone();
-
-            // Send text to client
-            self.communication.broadcast(IpcMessage::Narration(
-                crate::communication::Narration {
-                    session_id: session.session_id.clone(),
-                    speaker_id: "dm".to_string(),
-                    text: narrative_text.clone(),
-                    emotion: None,
-                    tagged_for_tts: true,
-                },
-            ))?;
-
-            // Synthesize speech if TTS client is available
-            if let Some(tts_client) = &self.tts_client {
-                match tts_client.speak(&narrative_text, Some("pt")).await {
-                    Ok(tts_response) => {
-                        info!(
-                            "TTS synthesis successful: {}ms audio, actor={}, emotion={}",
-                            tts_response.duration_ms, tts_response.actor, tts_response.emotion
-                        );
-                        // Audio is ready - can be sent to client via IPC if needed
-                        // For now, the client can request audio separately if needed
-                    }
-                    Err(e) => {
-                        warn!("TTS synthesis failed: {}, continuing without audio", e);
-                        // Continue without audio - not critical for gameplay
-                    }
+            self.narrate(&session.session_id, &narrative).await?;
+        }
+
+        // Execute the INTENTs that pass validation, asking the LLM to fix the rest
+        let mut errors = self
+            .intent_executor
+            .execute_output(intent_text, session, issuer)
+            .await;
+        for _ in 0..self.max_repair_attempts {
+            if errors.is_empty() {
+                break;
+            }
+            let Some(repaired) = self.request_repair(session, &errors).await else {
+                break;
+            };
+            session.record(RecordedInput::LlmOutput {
+                text: repaired.clone(),
+                issuer: issuer.map(String::from),
+            });
+            errors = self
+                .intent_executor
+                .execute_output(&repaired, session, issuer)
+                .await;
+        }
+        for e in &errors {
+            warn!("Dropped INTENT {}", e);
+        }
+
+        let session_id = session.session_id.clone();
+        self.handle_scene_transitions(session, &session_id).await?;
+        self.handle_dismissals(session, &session_id).await?;
+
+        // Rolled results (e.g. random tables) go back to the DM to narrate
+        for prompt in session.take_narration_prompts() {
+            Box::pin(self.prompt_dm(session, &session_id, &prompt)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send DM narration to the room and synthesize speech if TTS is available
+    async fn narrate(&self, session_id: &str, text: &str) -> Result<()> {
+        self.communication
+            .broadcast(IpcMessage::Narration(Narration {
+                session_id: session_id.to_string(),
+                speaker_id: "dm".to_string(),
+                text: text.to_string(),
+                emotion: None,
+                tagged_for_tts: true,
+            }))?;
+
+        if let Some(tts_client) = &self.tts_client {
+            match tts_client.speak(text, Some("pt")).await {
+                Ok(tts_response) => {
+                    info!(
+                        "TTS synthesis successful: {}ms audio, actor={}, emotion={}",
+                        tts_response.duration_ms, tts_response.actor, tts_response.emotion
+                    );
+                    // Audio is ready - can be sent to client via IPC if needed
+                    // For now, the client can request audio separately if needed
+                }
+                Err(e) => {
+                    warn!("TTS synthesis failed: {}, continuing without audio", e);
+                    // Continue without audio - not critical for gameplay
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Carry out a command from the DM console and record it in the session's history
+    /// with `author` (the DM) as its author
+    pub async fn process_dm_command(&self, message: DmCommandMessage, author: &str) -> Result<()> {
+        let DmCommandMessage {
+            session_id,
+            command,
+        } = message;
+        info!("DM {} in session {}: {:?}", author, session_id, command);
+        let input = RecordedInput::DmOverride {
+            author: author.to_string(),
+            command: command.clone(),
+        };
+
+        if let DmCommand::OverrideRoll {
+            request_id,
+            total,
+            natural,
+        } = &command
+        {
+            let roll = self
+                .communication
+                .override_roll(&session_id, request_id, *total, *natural)
+                .await?;
+            self.session_manager
+                .write()
+                .await
+                .get_session_mut(&session_id)
+                .ok_or_else(|| {
+                    OrchestratorError::SessionError(format!("Session not found: {}", session_id))
+                })?
+                .record(input);
+            return self.resolve_roll(roll, RollStatus::Fulfilled).await;
+        }
 
-        // Execute all INTENTs
-        for intent in &intents {
-            if let Err(e) = self.intent_executor.execute(intent, session).await {
-                error!("Failed to execute INTENT: {}", e);
-                // Continue with other INTENTs even if one fails
+        let mut session_manager = self.session_manager.write().await;
+        let session = session_manager
+            .get_session_mut(&session_id)
+            .ok_or_else(|| {
+                OrchestratorError::SessionError(format!("Session not found: {}", session_id))
+            })?;
+        let was_in_combat = session.current_state() == SceneState::CombatTurnBased;
+
+        match &command {
+            DmCommand::Approve { batch_id } => {
+                let held = session.console.release(*batch_id)?;
+                session.record(input);
+                self.execute_dm_output(session, &held.text, held.issuer.as_deref())
+                    .await?;
+            }
+            DmCommand::Veto { batch_id } => {
+                session.console.release(*batch_id)?;
+                session.record(input);
+                info!("DM vetoed held output {}", batch_id);
+            }
+            DmCommand::Narrate { text } => {
+                session.record(input);
+                self.narrate(&session_id, text).await?;
+            }
+            _ => {
+                dm_console::apply(session, &command, author)?;
+                session.record(input);
             }
         }
 
+        self.send_scene_update(&session_id, session).await?;
+        if was_in_combat || session.current_state() == SceneState::CombatTurnBased {
+            self.send_combat_update(session).await?;
+        }
         Ok(())
     }
 
@@ -236,9 +424,58 @@ impl Orchestrator {
             match ui_intent.as_str() {
                 "end_turn" => {
                     // End current turn in combat
-                    if let Some(engine) = session.engine_session_mut() {
-                        engine.next_turn()?;
-                    }
+                    session.record(RecordedInput::Event {
+                        event: GameEvent::TurnAdvanced,
+                    });
+                    session.dispatch(GameEvent::TurnAdvanced)?;
+                    self.handle_turn_events(session, &action.session_id).await?;
+                }
+                "delay_turn" | "resume_turn" => {
+                    // Hold the current turn, or step back into the order after the current turn
+                    let actor = action.target_id.as_deref().unwrap_or(&action.player_id);
+                    let actor_id = resolve_actor_id(session, actor).ok_or_else(|| {
+                        OrchestratorError::IntentExecutionError(format!(
+                            "Actor not found: {}",
+                            actor
+                        ))
+                    })?;
+                    let event = if ui_intent == "delay_turn" {
+                        GameEvent::TurnDelayed { actor_id }
+                    } else {
+                        GameEvent::TurnResumed { actor_id }
+                    };
+                    session.record(RecordedInput::Event {
+                        event: event.clone(),
+                    });
+                    session.dispatch(event)?;
+                    self.handle_turn_events(session, &action.session_id).await?;
+                }
+                "undo" | "redo" => {
+                    // DM correction: step the engine's event log back or forward
+                    let event = if ui_intent == "undo" {
+                        session.record(RecordedInput::Undo);
+                        session.undo()?
+                    } else {
+                        session.record(RecordedInput::Redo);
+                        session.redo()?
+                    };
+                    info!("{}: {:?}", ui_intent, event);
+                    self.send_combat_update(session).await?;
+                }
+                "advance_time" => {
+                    // Travel, rests and downtime: metadata like {"hours": 8} or {"days": 3}
+                    let seconds = action
+                        .metadata
+                        .as_ref()
+                        .map(time_advance_seconds)
+                        .unwrap_or(0);
+                    let event = GameEvent::TimeAdvanced { seconds };
+                    session.record(RecordedInput::Event {
+                        event: event.clone(),
+                    });
+                    session.dispatch(event)?;
+                    self.handle_clock_events(session, &action.session_id)
+                        .await?;
                 }
                 "use_item" => {
                     // Use item from inventory
@@ -257,73 +494,327 @@ impl Orchestrator {
         Ok(())
     }
 
+    /// Log turn events and let the DM (LLM) choose legendary and lair actions when
+    /// a window opens
+    async fn handle_turn_events(&self, session: &mut GameSession, session_id: &str) -> Result<()> {
+        let Some(engine) = session.engine_session_mut() else {
+            return Ok(());
+        };
+        let events = engine.take_turn_events();
+
+        for event in events {
+            info!("Turn event: {:?}", event);
+            let prompt = session
+                .engine_session()
+                .and_then(|engine| legendary_prompt(engine, &event));
+            if let Some(prompt) = prompt {
+                self.prompt_dm(session, session_id, &prompt).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Log clock hooks (dawn, expired effects, NPC schedules) and refresh the scene's time
+    async fn handle_clock_events(&self, session: &mut GameSession, session_id: &str) -> Result<()> {
+        let Some(engine) = session.engine_session_mut() else {
+            return Ok(());
+        };
+        for event in engine.take_clock_events() {
+            info!("Clock event: {:?}", event);
+        }
+        self.handle_dismissals(session, session_id).await?;
+        self.send_scene_update(session_id, session).await
+    }
+
+    /// Tell the DM about summons that vanished and creatures that stopped obeying
+    async fn handle_dismissals(&self, session: &mut GameSession, session_id: &str) -> Result<()> {
+        let Some(engine) = session.engine_session_mut() else {
+            return Ok(());
+        };
+        let dismissals = engine.take_dismissals();
+        for dismissal in dismissals {
+            info!("Dismissed: {:?}", dismissal);
+            let prompt = if dismissal.removed {
+                format!("{} vanishes as the magic binding it ends.", dismissal.name)
+            } else {
+                format!("{} is no longer under anyone's control.", dismissal.name)
+            };
+            Box::pin(self.prompt_dm(session, session_id, &prompt)).await?;
+        }
+        Ok(())
+    }
+
+    /// Broadcast a scene update for every scene transition (party moved, split or regrouped)
+    async fn handle_scene_transitions(
+        &self,
+        session: &mut GameSession,
+        session_id: &str,
+    ) -> Result<()> {
+        let Some(engine) = session.engine_session_mut() else {
+            return Ok(());
+        };
+        let transitions = engine.take_scene_transitions();
+        for transition in transitions {
+            info!("Scene transition: {:?}", transition);
+            self.send_scene_update(session_id, session).await?;
+        }
+        Ok(())
+    }
+
+    /// Ask the DM persona to act on an engine prompt. Without an LLM the window passes;
+    /// while the DM has paused it, the prompt goes to the DM.
+    async fn prompt_dm(
+        &self,
+        session: &mut GameSession,
+        session_id: &str,
+        prompt: &str,
+    ) -> Result<()> {
+        if session.console.llm_paused {
+            info!("LLM paused, passing to the DM: {}", prompt);
+            return self.communication.send_to_dm(
+                session_id,
+                IpcMessage::Narration(Narration {
+                    session_id: session_id.to_string(),
+                    speaker_id: "system".to_string(),
+                    text: prompt.to_string(),
+                    emotion: None,
+                    tagged_for_tts: false,
+                }),
+            );
+        }
+        let Some(llm_client) = &self.llm_client else {
+            info!("No LLM client configured, skipping: {}", prompt);
+            return Ok(());
+        };
+        if !matches!(llm_client.health_check().await, Ok(true)) {
+            warn!("LLM Core not available, skipping: {}", prompt);
+            return Ok(());
+        }
+
+        let llm_request = LlmRequest {
+            text: prompt.to_string(),
+            persona: "dm".to_string(),
+            scene_state: Some(session.current_state().name().to_string()),
+            game_context: Some(serde_json::json!({
+                "context": self.serialize_game_context(session)
+            })),
+            memory_context: self.get_memory_context(session_id).await,
+            max_tokens: Some(512),
+            temperature: Some(0.7),
+            intent_schema: Some(IntentParser::json_schema()),
+        };
+
+        match llm_client.generate_with_intents(&llm_request).await {
+            Ok(llm_response) => {
+                let mut combined = llm_response.text;
+                if let Some(intents) = &llm_response.intents {
+                    combined.push_str("\n\n");
+                    combined.push_str(intents);
+                }
+                self.apply_dm_output(session, &combined, None).await
+            }
+            Err(e) => {
+                warn!("LLM Core request failed: {}, skipping prompt", e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Ask the LLM to correct rejected INTENTs; `None` when it can't be reached
+    async fn request_repair(
+        &self,
+        session: &GameSession,
+        errors: &[IntentError],
+    ) -> Option<String> {
+        let llm_client = self.llm_client.as_ref()?;
+        if session.console.llm_paused || !matches!(llm_client.health_check().await, Ok(true)) {
+            return None;
+        }
+
+        let llm_request = LlmRequest {
+            text: repair_prompt(errors),
+            persona: "dm".to_string(),
+            scene_state: Some(session.current_state().name().to_string()),
+            game_context: Some(serde_json::json!({
+                "context": self.serialize_game_context(session)
+            })),
+            memory_context: None,
+            max_tokens: Some(512),
+            temperature: Some(0.2),
+            intent_schema: Some(IntentParser::json_schema()),
+        };
+        match llm_client.generate_with_intents(&llm_request).await {
+            Ok(llm_response) => {
+                // Only the INTENTs matter here; repairs are not narrated
+                let mut combined = llm_response.text;
+                if let Some(intents) = &llm_response.intents {
+                    combined.push_str("\n\n");
+                    combined.push_str(intents);
+                }
+                Some(combined)
+            }
+            Err(e) => {
+                warn!("LLM Core repair request failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Ask a player to roll; once known, the roll is resolved as `resume` says
+    pub async fn request_roll(&self, request: RollRequest, resume: RollResume) -> Result<()> {
+        info!(
+            "Requesting {} roll from {}: request_id={}",
+            request.roll_kind, request.actor_id, request.request_id
+        );
+        self.communication
+            .store_roll_request(request.clone(), resume)
+            .await;
+        self.communication
+            .broadcast(IpcMessage::RollRequest(request))
+    }
+
     /// Process RollResult from client
+    ///
+    /// Results that don't answer a pending request, repeat one already answered or don't
+    /// fit its formula are rejected.
     pub async fn process_roll_result(&self, result: RollResult) -> Result<()> {
         info!(
             "Processing RollResult: request_id={}, total={}",
             result.request_id, result.total
         );
 
-        // Get pending roll request
-        if let Some(request) = self
-            .communication
-            .get_roll_request(&result.request_id)
-            .await
-        {
-            info!(
-                "Matched RollResult to RollRequest for actor {}",
-                request.actor_id
-            );
+        let roll = self.communication.fulfil_roll(&result).await.map_err(|e| {
+            warn!("Rejected RollResult: {}", e);
+            e
+        })?;
+        info!(
+            "Matched RollResult to RollRequest for actor {}",
+            roll.request.actor_id
+        );
+        self.resolve_roll(roll, RollStatus::Fulfilled).await
+    }
 
-            // Get session
-            let mut session_manager = self.session_manager.write().await;
-            let session_id = result.session_id.clone();
+    /// Withdraw a roll request the player hasn't answered yet
+    pub async fn cancel_roll(&self, request_id: &str) -> Result<()> {
+        let request = self.communication.cancel_roll(request_id).await?;
+        info!("Cancelled roll request {}", request_id);
+        self.close_roll(&request, RollStatus::Cancelled, None)
+    }
 
-            if let Some(_session) = session_manager.get_session_mut(&session_id) {
-                // Process roll result based on roll_kind
-                match request.roll_kind.as_str() {
-                    "skill_check" => {
-                        // Skill check completed, continue with narrative
-                        // TODO: Send result to LLM for narrative continuation
-                    }
-                    "attack" => {
-                        // Attack roll completed, calculate damage if hit
-                        // TODO: Process attack result
-                    }
-                    _ => {
-                        warn!("Unknown roll kind: {}", request.roll_kind);
-                    }
-                }
+    /// Expire roll requests past their table's timeout. Tables with auto-roll have the
+    /// server roll instead; otherwise the DM decides what happens without the roll.
+    pub async fn expire_rolls(&self) {
+        for expired in self.communication.expire_rolls().await {
+            let request_id = expired.request.request_id.clone();
+            let outcome = match expired.auto_roll {
+                Some(roll) => self.resolve_roll(roll, RollStatus::Expired).await,
+                None => self.report_missed_roll(expired.request).await,
+            };
+            if let Err(e) = outcome {
+                warn!("Failed to resolve expired roll {}: {}", request_id, e);
             }
-        } else {
-            warn!(
-                "Orphaned RollResult: request_id={} not found",
-                result.request_id
-            );
-            return Err(OrchestratorError::CommunicationError(format!(
-                "RollResult request_id {} not found",
-                result.request_id
-            )));
         }
+    }
+
+    /// Check for expired roll requests every `interval`
+    pub fn spawn_roll_timeouts(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.expire_rolls().await;
+            }
+        })
+    }
+
+    /// Resolve a settled roll in its session and tell the UI the request is closed
+    async fn resolve_roll(&self, roll: SettledRoll, status: RollStatus) -> Result<()> {
+        let session_id = roll.request.session_id.clone();
+        let mut session_manager = self.session_manager.write().await;
+        let session = session_manager
+            .get_session_mut(&session_id)
+            .ok_or_else(|| {
+                OrchestratorError::SessionError(format!("Session not found: {}", session_id))
+            })?;
 
+        session.record(RecordedInput::Roll { roll: roll.clone() });
+        self.intent_executor.resume_roll(&roll, session).await?;
+
+        self.close_roll(&roll.request, status, Some(roll.total))?;
+        if matches!(roll.resume, RollResume::Attack { .. }) {
+            self.send_combat_update(session).await?;
+        }
+        for prompt in session.take_narration_prompts() {
+            Box::pin(self.prompt_dm(session, &session_id, &prompt)).await?;
+        }
         Ok(())
     }
 
+    /// Tell the room a roll request is closed; the total of a secret roll only goes to the DM
+    fn close_roll(
+        &self,
+        request: &RollRequest,
+        status: RollStatus,
+        total: Option<i32>,
+    ) -> Result<()> {
+        let closed = |total| {
+            IpcMessage::RollClosed(RollClosed {
+                session_id: request.session_id.clone(),
+                request_id: request.request_id.clone(),
+                status,
+                total,
+            })
+        };
+        if request.secret && total.is_some() {
+            self.communication
+                .send_to_session(&request.session_id, closed(None))?;
+            return self
+                .communication
+                .send_to_dm(&request.session_id, closed(total));
+        }
+        self.communication.broadcast(closed(total))
+    }
+
+    /// Tell the UI and the DM that a player never rolled
+    async fn report_missed_roll(&self, request: RollRequest) -> Result<()> {
+        let session_id = request.session_id.clone();
+        let mut session_manager = self.session_manager.write().await;
+        let session = session_manager
+            .get_session_mut(&session_id)
+            .ok_or_else(|| {
+                OrchestratorError::SessionError(format!("Session not found: {}", session_id))
+            })?;
+
+        self.close_roll(&request, RollStatus::Expired, None)?;
+        let actor = resolve_actor_id(session, &request.actor_id)
+            .and_then(|id| session.engine_session()?.get_actor(id))
+            .map_or_else(|| request.actor_id.clone(), |a| a.name.clone());
+        let prompt = format!(
+            "{} didn't roll in time for {}. Decide what happens without the roll.",
+            actor, request.reason
+        );
+        self.prompt_dm(session, &session_id, &prompt).await
+    }
+
     /// Send scene update to client
     async fn send_scene_update(&self, session_id: &str, session: &GameSession) -> Result<()> {
-        let scene_update = IpcMessage::SceneUpdate(crate::communication::SceneUpdate {
-            session_id: session_id.to_string(),
-            scene_state: session.current_state().name().to_string(),
-            summary: format!("Scene in {:?} state", session.current_state()),
-            active_speaker_id: None,
-            participants: vec![],
-        });
+        let scene_update = IpcMessage::SceneUpdate(
+            crate::communication::SceneUpdate::from_session(session_id, session),
+        );
 
         self.communication.broadcast(scene_update)?;
         Ok(())
     }
 
-    /// Extract narrative text from LLM response (removes INTENT blocks)
+    /// Send combat update (round, initiative order, active creature) to client
+    async fn send_combat_update(&self, session: &GameSession) -> Result<()> {
+        let combat_update = IpcMessage::CombatUpdate(CombatUpdate::from_session(session));
+        self.communication.broadcast(combat_update)?;
+        self.communication.send_fog_of_war(session).await
+    }
+
+    /// Extract narrative text from LLM response (removes INTENT blocks and JSON intents)
     fn extract_narrative(&self, text: &str) -> String {
         // Remove INTENT blocks to get pure narrative
         let start_marker = "[INTENTS]";
@@ -352,7 +843,7 @@ impl Orchestrator {
         // Add remaining text
         result.push_str(remaining);
 
-        result.trim().to_string()
+        crate::intent::json::strip_payloads(&result)
     }
 
     /// Create a fallback INTENT when LLM Core is not available
@@ -383,7 +874,7 @@ END_INTENT
         if let Some(engine) = session.engine_session() {
             if let Some(scene) = engine.get_current_scene() {
                 context.push_str(&format!("Scene: {}\n", scene.name));
-                context.push_str(&format!("Actors: {}\n", scene.all_actors().len()));
+                context.push_str(&format!("Actors: {}\n", engine.scene_actors().len()));
             }
         }
 
@@ -397,3 +888,65 @@ END_INTENT
         None
     }
 }
+
+/// Seconds to advance from UI metadata such as `{"days": 1, "hours": 4}`, at most
+/// [`MAX_ADVANCE_SECONDS`]
+fn time_advance_seconds(metadata: &serde_json::Value) -> u64 {
+    [
+        ("rounds", ROUND_SECONDS),
+        ("minutes", MINUTE_SECONDS),
+        ("hours", HOUR_SECONDS),
+        ("days", DAY_SECONDS),
+    ]
+    .iter()
+    .map(|(unit, seconds)| {
+        metadata
+            .get(unit)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            .saturating_mul(*seconds)
+    })
+    .fold(0u64, u64::saturating_add)
+    .min(MAX_ADVANCE_SECONDS)
+}
+
+/// Describe an open legendary action window or lair action for the DM persona
+fn legendary_prompt(engine: &EngineSession, event: &TurnEvent) -> Option<String> {
+    match event {
+        TurnEvent::LegendaryWindow {
+            actor_id,
+            after_actor_id,
+            round,
+        } => {
+            let actor = engine.get_actor(*actor_id)?;
+            let legendary = actor.legendary.as_ref()?;
+            let after = engine
+                .get_actor(*after_actor_id)
+                .map(|a| a.name.as_str())
+                .unwrap_or("another creature");
+            let options = legendary
+                .affordable_actions()
+                .iter()
+                .map(|a| format!("{} (costs {})", a.name, a.cost))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(format!(
+                "Round {}: {}'s turn ended. {} may take a legendary action ({} left): {}. \
+                 Answer with a LEGENDARY_ACTION INTENT, or no INTENT to pass.",
+                round, after, actor.name, legendary.actions_remaining, options
+            ))
+        }
+        TurnEvent::LairAction { actor_id, round } => {
+            let actor = engine.get_actor(*actor_id)?;
+            let legendary = actor.legendary.as_ref()?;
+            Some(format!(
+                "Round {}, initiative count 20: {}'s lair acts. Options: {}. \
+                 Answer with a LAIR_ACTION INTENT.",
+                round,
+                actor.name,
+                legendary.lair_actions.join(", ")
+            ))
+        }
+        _ => None,
+    }
+}
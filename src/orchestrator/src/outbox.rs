@@ -0,0 +1,236 @@
+//! Per-session history of the messages sent to a room
+//!
+//! Every message for a session gets the next sequence number of that session and is
+//! kept in a bounded outbox. A client that reconnects says the last sequence number it
+//! saw and gets what it missed replayed. Critical messages (roll requests) stay unacked
+//! until a client in the room acks them, and are sent again until then.
+
+use crate::communication::Delivery;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Messages kept per session for replay
+pub const OUTBOX_CAPACITY: usize = 256;
+
+#[derive(Debug, Default)]
+struct SessionOutbox {
+    /// Sequence number of the last message sent
+    last_seq: u64,
+    sent: VecDeque<Delivery>,
+    /// Critical messages waiting for an ack, by seq, with when they were last sent
+    unacked: BTreeMap<u64, Instant>,
+}
+
+impl SessionOutbox {
+    fn get(&self, seq: u64) -> Option<&Delivery> {
+        let first = self.sent.front()?.seq?;
+        self.sent.get(seq.checked_sub(first)? as usize)
+    }
+}
+
+#[derive(Debug)]
+pub struct Outbox {
+    capacity: usize,
+    sessions: HashMap<String, SessionOutbox>,
+}
+
+impl Default for Outbox {
+    fn default() -> Self {
+        Self::new(OUTBOX_CAPACITY)
+    }
+}
+
+impl Outbox {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Give a session's delivery its sequence number and keep it for replay
+    pub fn record(&mut self, session_id: &str, delivery: &mut Delivery, now: Instant) -> u64 {
+        let outbox = self.sessions.entry(session_id.to_string()).or_default();
+        outbox.last_seq += 1;
+        let seq = outbox.last_seq;
+        delivery.seq = Some(seq);
+
+        if delivery.message.needs_ack() {
+            outbox.unacked.insert(seq, now);
+        }
+        outbox.sent.push_back(delivery.clone());
+        while outbox.sent.len() > self.capacity {
+            if let Some(seq) = outbox.sent.pop_front().and_then(|d| d.seq) {
+                outbox.unacked.remove(&seq);
+            }
+        }
+        seq
+    }
+
+    /// Sequence number of the session's last message (0 before any)
+    pub fn last_seq(&self, session_id: &str) -> u64 {
+        self.sessions.get(session_id).map_or(0, |o| o.last_seq)
+    }
+
+    /// Messages sent after `last_seen`, or `None` when the client can't catch up from
+    /// them: some were already dropped, or it saw more than was ever sent (a restart)
+    pub fn since(&self, session_id: &str, last_seen: u64) -> Option<Vec<Delivery>> {
+        let last_seq = self.last_seq(session_id);
+        if last_seen > last_seq {
+            return None;
+        }
+        let Some(outbox) = self.sessions.get(session_id) else {
+            return Some(Vec::new());
+        };
+        let first_kept = outbox
+            .sent
+            .front()
+            .and_then(|d| d.seq)
+            .unwrap_or(last_seq + 1);
+        if last_seen + 1 < first_kept {
+            return None;
+        }
+        Some(
+            outbox
+                .sent
+                .iter()
+                .filter(|d| d.seq.is_some_and(|seq| seq > last_seen))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Everything still kept for the session
+    pub fn retained(&self, session_id: &str) -> Vec<Delivery> {
+        self.sessions
+            .get(session_id)
+            .map(|o| o.sent.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Mark a critical message as received; false if it wasn't waiting for an ack
+    pub fn ack(&mut self, session_id: &str, seq: u64) -> bool {
+        self.sessions
+            .get_mut(session_id)
+            .is_some_and(|o| o.unacked.remove(&seq).is_some())
+    }
+
+    pub fn is_acked(&self, session_id: &str, seq: u64) -> bool {
+        self.sessions
+            .get(session_id)
+            .map_or(true, |o| !o.unacked.contains_key(&seq))
+    }
+
+    /// Unacked messages last sent at least `after` ago, marked as sent again now
+    pub fn due_for_resend(&mut self, now: Instant, after: Duration) -> Vec<Delivery> {
+        let mut due = Vec::new();
+        for outbox in self.sessions.values_mut() {
+            let SessionOutbox { sent, unacked, .. } = outbox;
+            for (seq, last_sent) in unacked.iter_mut() {
+                if now.duration_since(*last_sent) < after {
+                    continue;
+                }
+                let first = sent.front().and_then(|d| d.seq).unwrap_or(1);
+                if let Some(delivery) = sent.get((*seq - first) as usize) {
+                    *last_sent = now;
+                    due.push(delivery.clone());
+                }
+            }
+        }
+        due.sort_by_key(|d| d.seq);
+        due
+    }
+
+    /// A session's message by sequence number, if still kept
+    pub fn get(&self, session_id: &str, seq: u64) -> Option<&Delivery> {
+        self.sessions.get(session_id)?.get(seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::communication::{Audience, IpcMessage, Narration, RollRequest};
+
+    fn narration(text: &str) -> Delivery {
+        Delivery {
+            audience: Audience::Session("table".to_string()),
+            message: IpcMessage::Narration(Narration {
+                session_id: "table".to_string(),
+                speaker_id: "dm".to_string(),
+                text: text.to_string(),
+                emotion: None,
+                tagged_for_tts: true,
+            }),
+            seq: None,
+        }
+    }
+
+    fn roll_request() -> Delivery {
+        Delivery {
+            audience: Audience::Session("table".to_string()),
+            message: IpcMessage::RollRequest(RollRequest {
+                session_id: "table".to_string(),
+                request_id: "r1".to_string(),
+                actor_id: "Aria".to_string(),
+                roll_kind: "skill_check".to_string(),
+                skill: None,
+                ability: None,
+                dc: None,
+                formula_hint: None,
+                reason: "listening at the door".to_string(),
+                secret: false,
+            }),
+            seq: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_since_last_seen_and_gaps() {
+        let now = Instant::now();
+        let mut outbox = Outbox::new(3);
+        for text in ["one", "two", "three"] {
+            outbox.record("table", &mut narration(text), now);
+        }
+        assert_eq!(outbox.last_seq("table"), 3);
+        assert_eq!(outbox.last_seq("other"), 0);
+
+        let missed = outbox.since("table", 1).unwrap();
+        assert_eq!(
+            missed.iter().map(|d| d.seq).collect::<Vec<_>>(),
+            vec![Some(2), Some(3)]
+        );
+        assert!(outbox.since("table", 3).unwrap().is_empty());
+        assert!(outbox.since("other", 0).unwrap().is_empty());
+        assert!(outbox.since("table", 9).is_none());
+
+        // Once the first message is dropped a client that never saw it has a gap
+        outbox.record("table", &mut narration("four"), now);
+        assert!(outbox.since("table", 0).is_none());
+        assert_eq!(outbox.since("table", 1).unwrap().len(), 3);
+        assert!(outbox.get("table", 1).is_none());
+        assert_eq!(outbox.get("table", 4).unwrap().seq, Some(4));
+    }
+
+    #[test]
+    fn test_critical_messages_are_resent_until_acked() {
+        let now = Instant::now();
+        let mut outbox = Outbox::new(8);
+        outbox.record("table", &mut narration("hello"), now);
+        let seq = outbox.record("table", &mut roll_request(), now);
+        assert!(outbox.is_acked("table", 1));
+        assert!(!outbox.is_acked("table", seq));
+
+        let after = Duration::from_secs(5);
+        assert!(outbox.due_for_resend(now, after).is_empty());
+        let due = outbox.due_for_resend(now + after, after);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].seq, Some(seq));
+        // Just resent, so not due again yet
+        assert!(outbox.due_for_resend(now + after, after).is_empty());
+
+        assert!(outbox.ack("table", seq));
+        assert!(!outbox.ack("table", seq));
+        assert!(outbox.due_for_resend(now + after * 3, after).is_empty());
+    }
+}
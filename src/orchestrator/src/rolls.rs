@@ -0,0 +1,545 @@
+//! Lifecycle of the dice rolls players are asked to make
+//!
+//! A roll request stays pending until the player's result arrives (fulfilled), the
+//! table's timeout passes (expired, optionally rolled by the server instead) or the DM
+//! withdraws it (cancelled). Settled requests are kept for a while so a late or repeated
+//! result is rejected instead of being treated as unknown.
+
+use crate::communication::{RollRequest, RollResult};
+use crate::session::replay::{derive_seed, SeedCursor};
+use rules5e_service::{DiceExpression, DiceRoller, RollMode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long a player has to roll when the table sets nothing else
+pub const DEFAULT_ROLL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long settled requests are remembered to catch duplicate results
+const SETTLED_RETENTION: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RollStatus {
+    Pending,
+    Fulfilled,
+    Expired,
+    Cancelled,
+}
+
+/// Per-table rules for player rolls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollPolicy {
+    /// Time a player has to roll; `None` waits forever
+    pub timeout: Option<Duration>,
+    /// Roll on the server for players who run out of time
+    pub auto_roll: bool,
+}
+
+impl Default for RollPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Some(DEFAULT_ROLL_TIMEOUT),
+            auto_roll: false,
+        }
+    }
+}
+
+/// What happens with the roll once it is known
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RollResume {
+    /// Hand the result (and its DC, if any) to the DM to narrate
+    #[default]
+    Narrate,
+    /// Attack roll against `target`'s AC; a hit rolls the weapon's damage
+    Attack {
+        target: String,
+        weapon: Option<String>,
+    },
+}
+
+/// A request whose roll is known, ready to be resolved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettledRoll {
+    pub request: RollRequest,
+    pub resume: RollResume,
+    pub total: i32,
+    pub natural: i32,
+    /// Rolled by the server after the player ran out of time
+    #[serde(default)]
+    pub auto_rolled: bool,
+}
+
+impl SettledRoll {
+    /// Whether the roll meets the request's DC; `None` without a DC
+    pub fn success(&self) -> Option<bool> {
+        self.request.dc.map(|dc| self.total >= dc)
+    }
+}
+
+/// A request that ran out of time, with the server's roll if the table allows one
+#[derive(Debug, Clone)]
+pub struct ExpiredRoll {
+    pub request: RollRequest,
+    pub auto_roll: Option<SettledRoll>,
+}
+
+#[derive(Debug, Clone)]
+struct TrackedRoll {
+    request: RollRequest,
+    resume: RollResume,
+    status: RollStatus,
+    deadline: Option<Instant>,
+    settled_at: Option<Instant>,
+}
+
+impl TrackedRoll {
+    fn settle(&mut self, status: RollStatus, now: Instant) {
+        self.status = status;
+        self.settled_at = Some(now);
+    }
+}
+
+/// Roll requests by id, with each table's policy
+#[derive(Debug, Default)]
+pub struct RollTracker {
+    rolls: HashMap<String, TrackedRoll>,
+    policies: HashMap<String, RollPolicy>,
+}
+
+impl RollTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the roll policy for a session's table
+    pub fn set_policy(&mut self, session_id: &str, policy: RollPolicy) {
+        self.policies.insert(session_id.to_string(), policy);
+    }
+
+    pub fn policy(&self, session_id: &str) -> RollPolicy {
+        self.policies.get(session_id).copied().unwrap_or_default()
+    }
+
+    /// Start waiting for a roll; the deadline comes from the table's timeout
+    pub fn issue(&mut self, request: RollRequest, resume: RollResume, now: Instant) {
+        let deadline = self
+            .policy(&request.session_id)
+            .timeout
+            .map(|timeout| now + timeout);
+        self.rolls.insert(
+            request.request_id.clone(),
+            TrackedRoll {
+                request,
+                resume,
+                status: RollStatus::Pending,
+                deadline,
+                settled_at: None,
+            },
+        );
+    }
+
+    pub fn status(&self, request_id: &str) -> Option<RollStatus> {
+        self.rolls.get(request_id).map(|r| r.status)
+    }
+
+    /// Pending request by id
+    pub fn pending(&self, request_id: &str) -> Option<&RollRequest> {
+        self.rolls
+            .get(request_id)
+            .filter(|r| r.status == RollStatus::Pending)
+            .map(|r| &r.request)
+    }
+
+    /// Accept a player's result for a pending request
+    ///
+    /// Results for unknown or already settled requests, from the wrong actor or that
+    /// don't fit the request's formula are rejected and leave the request as it was.
+    pub fn fulfil(
+        &mut self,
+        result: &RollResult,
+        now: Instant,
+    ) -> std::result::Result<SettledRoll, String> {
+        let tracked = self
+            .rolls
+            .get_mut(&result.request_id)
+            .ok_or_else(|| format!("RollResult request_id {} not found", result.request_id))?;
+        if tracked.status != RollStatus::Pending {
+            return Err(format!(
+                "Roll request {} is already {}",
+                result.request_id,
+                status_name(tracked.status)
+            ));
+        }
+        if tracked.deadline.is_some_and(|deadline| now >= deadline) {
+            return Err(format!("Roll request {} has expired", result.request_id));
+        }
+        check_roll(&tracked.request, result)?;
+
+        tracked.settle(RollStatus::Fulfilled, now);
+        Ok(SettledRoll {
+            request: tracked.request.clone(),
+            resume: tracked.resume.clone(),
+            total: result.total,
+            natural: result.natural,
+            auto_rolled: false,
+        })
+    }
+
+    /// Withdraw a pending request
+    pub fn cancel(
+        &mut self,
+        request_id: &str,
+        now: Instant,
+    ) -> std::result::Result<RollRequest, String> {
+        let tracked = self.pending_mut(request_id)?;
+        tracked.settle(RollStatus::Cancelled, now);
+        Ok(tracked.request.clone())
+    }
+
+    /// Settle a pending request with the DM's numbers instead of the player's, even past
+    /// its deadline. Without a natural roll it is worked out from the formula hint.
+    pub fn override_roll(
+        &mut self,
+        session_id: &str,
+        request_id: &str,
+        total: i32,
+        natural: Option<i32>,
+        now: Instant,
+    ) -> std::result::Result<SettledRoll, String> {
+        let tracked = self.pending_mut(request_id)?;
+        if tracked.request.session_id != session_id {
+            return Err(format!(
+                "Roll request {} is not in session {}",
+                request_id, session_id
+            ));
+        }
+        let natural = natural.unwrap_or_else(|| {
+            formula(&tracked.request).map_or(total, |expr| total.saturating_sub(expr.modifier))
+        });
+        tracked.settle(RollStatus::Fulfilled, now);
+        Ok(SettledRoll {
+            request: tracked.request.clone(),
+            resume: tracked.resume.clone(),
+            total,
+            natural,
+            auto_rolled: false,
+        })
+    }
+
+    fn pending_mut(&mut self, request_id: &str) -> std::result::Result<&mut TrackedRoll, String> {
+        let tracked = self
+            .rolls
+            .get_mut(request_id)
+            .ok_or_else(|| format!("Roll request {} not found", request_id))?;
+        if tracked.status != RollStatus::Pending {
+            return Err(format!(
+                "Roll request {} is already {}",
+                request_id,
+                status_name(tracked.status)
+            ));
+        }
+        Ok(tracked)
+    }
+
+    /// Expire the requests past their deadline and forget long-settled ones
+    pub fn expire(&mut self, now: Instant) -> Vec<ExpiredRoll> {
+        self.rolls.retain(|_, r| {
+            r.settled_at
+                .map_or(true, |at| now.duration_since(at) < SETTLED_RETENTION)
+        });
+
+        let mut expired = Vec::new();
+        for tracked in self.rolls.values_mut() {
+            if tracked.status != RollStatus::Pending
+                || tracked.deadline.map_or(true, |deadline| now < deadline)
+            {
+                continue;
+            }
+            tracked.settle(RollStatus::Expired, now);
+            let auto_roll = self
+                .policies
+                .get(&tracked.request.session_id)
+                .copied()
+                .unwrap_or_default()
+                .auto_roll
+                .then(|| {
+                    let (total, natural) = auto_roll(&tracked.request);
+                    SettledRoll {
+                        request: tracked.request.clone(),
+                        resume: tracked.resume.clone(),
+                        total,
+                        natural,
+                        auto_rolled: true,
+                    }
+                });
+            expired.push(ExpiredRoll {
+                request: tracked.request.clone(),
+                auto_roll,
+            });
+        }
+        expired.sort_by(|a, b| a.request.request_id.cmp(&b.request.request_id));
+        expired
+    }
+}
+
+fn status_name(status: RollStatus) -> &'static str {
+    match status {
+        RollStatus::Pending => "pending",
+        RollStatus::Fulfilled => "fulfilled",
+        RollStatus::Expired => "expired",
+        RollStatus::Cancelled => "cancelled",
+    }
+}
+
+/// The request's formula hint as dice, if it has a readable one
+fn formula(request: &RollRequest) -> Option<DiceExpression> {
+    match &request.formula_hint {
+        Some(hint) => DiceRoller::parse(hint).ok(),
+        None => None,
+    }
+}
+
+/// Check a player's result against the request it answers
+///
+/// With a formula hint the natural roll must fit the dice and the total must be the
+/// natural roll plus the modifier. A client seed must reproduce the total.
+pub fn check_roll(request: &RollRequest, result: &RollResult) -> std::result::Result<(), String> {
+    if result.session_id != request.session_id || result.actor_id != request.actor_id {
+        return Err(format!(
+            "Roll request {} is for {} in session {}",
+            request.request_id, request.actor_id, request.session_id
+        ));
+    }
+    let Some(expr) = formula(request) else {
+        return Ok(());
+    };
+
+    let hint = request.formula_hint.as_deref().unwrap_or_default();
+    let bounds = i32::try_from(expr.count).ok().zip(
+        expr.count
+            .checked_mul(expr.sides)
+            .and_then(|highest| i32::try_from(highest).ok()),
+    );
+    let Some((lowest, highest)) = bounds else {
+        return Err(format!("Dice {} are too large to check", hint));
+    };
+    if result.natural < lowest || result.natural > highest {
+        return Err(format!(
+            "Natural roll {} is impossible on {}",
+            result.natural, hint
+        ));
+    }
+    if result.natural.checked_add(expr.modifier) != Some(result.total) {
+        return Err(format!(
+            "Total {} doesn't match natural roll {} with modifier {}",
+            result.total, result.natural, expr.modifier
+        ));
+    }
+    if let Some(seed) = &result.client_seed {
+        let seed: u64 = seed
+            .parse()
+            .map_err(|_| format!("Invalid client seed: {}", seed))?;
+        let rolled = DiceRoller::with_seed(seed)
+            .roll(&expr, RollMode::Normal)
+            .map_err(|e| e.to_string())?;
+        if rolled.total != result.total {
+            return Err(format!(
+                "Client seed {} rolls {}, not {}",
+                seed, rolled.total, result.total
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Server roll for a request, as (total, natural)
+///
+/// The seed comes from the request id, so the same request always rolls the same.
+fn auto_roll(request: &RollRequest) -> (i32, i32) {
+    let expr = formula(request).unwrap_or(DiceExpression {
+        count: 1,
+        sides: 20,
+        modifier: 0,
+    });
+    let seed = derive_seed(&request.request_id, SeedCursor::default());
+    let total = DiceRoller::with_seed(seed)
+        .roll(&expr, RollMode::Normal)
+        .map(|r| r.total)
+        .unwrap_or(expr.modifier);
+    (total, total.saturating_sub(expr.modifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str) -> RollRequest {
+        RollRequest {
+            session_id: "table".to_string(),
+            request_id: id.to_string(),
+            actor_id: "Aria".to_string(),
+            roll_kind: "skill_check".to_string(),
+            skill: Some("stealth".to_string()),
+            ability: None,
+            dc: Some(15),
+            formula_hint: Some("1d20+4".to_string()),
+            reason: "sneaking past the guards".to_string(),
+            secret: false,
+        }
+    }
+
+    fn result(id: &str, natural: i32, total: i32) -> RollResult {
+        RollResult {
+            session_id: "table".to_string(),
+            request_id: id.to_string(),
+            actor_id: "Aria".to_string(),
+            total,
+            natural,
+            breakdown: serde_json::json!({}),
+            client_seed: None,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_results_are_checked_and_fulfil_once() {
+        let now = Instant::now();
+        let mut tracker = RollTracker::new();
+        tracker.issue(request("r1"), RollResume::Narrate, now);
+
+        for (natural, total) in [(21, 25), (12, 20), (0, 4)] {
+            assert!(tracker.fulfil(&result("r1", natural, total), now).is_err());
+        }
+        let mut wrong_actor = result("r1", 12, 16);
+        wrong_actor.actor_id = "Borin".to_string();
+        assert!(tracker.fulfil(&wrong_actor, now).is_err());
+        let mut bad_seed = result("r1", 12, 16);
+        bad_seed.client_seed = Some("lucky".to_string());
+        assert!(tracker.fulfil(&bad_seed, now).is_err());
+        assert_eq!(tracker.status("r1"), Some(RollStatus::Pending));
+
+        let settled = tracker.fulfil(&result("r1", 12, 16), now).unwrap();
+        assert_eq!(settled.success(), Some(true));
+        assert!(!settled.auto_rolled);
+        assert_eq!(tracker.status("r1"), Some(RollStatus::Fulfilled));
+
+        let duplicate = tracker.fulfil(&result("r1", 12, 16), now).unwrap_err();
+        assert!(duplicate.contains("already fulfilled"));
+        assert!(tracker
+            .fulfil(&result("missing", 12, 16), now)
+            .unwrap_err()
+            .contains("not found"));
+    }
+
+    #[test]
+    fn test_dm_overrides_a_pending_roll() {
+        let now = Instant::now();
+        let mut tracker = RollTracker::new();
+        tracker.issue(request("r1"), RollResume::Narrate, now);
+
+        // Past the deadline, with a total the dice can't make
+        let later = now + DEFAULT_ROLL_TIMEOUT * 2;
+        assert!(tracker
+            .override_roll("other", "r1", 30, None, later)
+            .is_err());
+        let settled = tracker
+            .override_roll("table", "r1", 30, None, later)
+            .unwrap();
+        assert_eq!((settled.total, settled.natural), (30, 26));
+        assert_eq!(tracker.status("r1"), Some(RollStatus::Fulfilled));
+        assert!(tracker
+            .override_roll("table", "r1", 2, Some(1), later)
+            .unwrap_err()
+            .contains("already fulfilled"));
+    }
+
+    #[test]
+    fn test_client_seed_must_reproduce_the_total() {
+        let seed = 42;
+        let expr = DiceRoller::parse("1d20+4").unwrap();
+        let total = DiceRoller::with_seed(seed)
+            .roll(&expr, RollMode::Normal)
+            .unwrap()
+            .total;
+
+        let mut honest = result("r1", total - 4, total);
+        honest.client_seed = Some(seed.to_string());
+        assert!(check_roll(&request("r1"), &honest).is_ok());
+
+        let other = if total == 24 { 5 } else { total + 1 };
+        let mut forged = result("r1", other - 4, other);
+        forged.client_seed = Some(seed.to_string());
+        assert!(check_roll(&request("r1"), &forged).is_err());
+    }
+
+    #[test]
+    fn test_huge_dice_are_rejected_instead_of_overflowing() {
+        let mut huge = request("r1");
+        huge.formula_hint = Some("100000d100000".to_string());
+        let error = check_roll(&huge, &result("r1", 500_000, 500_000)).unwrap_err();
+        assert!(error.contains("too large"));
+
+        huge.formula_hint = Some("1d20+2147483647".to_string());
+        assert!(check_roll(&huge, &result("r1", 20, i32::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_timeouts_expire_and_auto_roll_per_table() {
+        let now = Instant::now();
+        let mut tracker = RollTracker::new();
+        tracker.issue(request("r1"), RollResume::Narrate, now);
+        let mut other_table = request("r2");
+        other_table.session_id = "other".to_string();
+        tracker.set_policy(
+            "other",
+            RollPolicy {
+                timeout: Some(Duration::from_secs(5)),
+                auto_roll: true,
+            },
+        );
+        tracker.issue(other_table, RollResume::Narrate, now);
+
+        let later = now + Duration::from_secs(10);
+        let expired = tracker.expire(later);
+        assert_eq!(expired.len(), 1);
+        let auto = expired[0].auto_roll.clone().unwrap();
+        assert!(auto.auto_rolled);
+        assert!((1..=20).contains(&auto.natural));
+        assert_eq!(auto.total, auto.natural + 4);
+        assert_eq!(tracker.status("r2"), Some(RollStatus::Expired));
+
+        // A late answer is refused, the default table is still waiting
+        let mut late = result("r2", 10, 14);
+        late.session_id = "other".to_string();
+        assert!(tracker
+            .fulfil(&late, later)
+            .unwrap_err()
+            .contains("expired"));
+        assert_eq!(tracker.status("r1"), Some(RollStatus::Pending));
+
+        let expired = tracker.expire(now + DEFAULT_ROLL_TIMEOUT);
+        assert_eq!(expired.len(), 1);
+        assert!(expired[0].auto_roll.is_none());
+
+        // Settled requests are forgotten after a while
+        tracker.expire(now + DEFAULT_ROLL_TIMEOUT + SETTLED_RETENTION);
+        assert_eq!(tracker.status("r1"), None);
+        assert_eq!(tracker.status("r2"), None);
+    }
+
+    #[test]
+    fn test_cancelled_requests_refuse_results() {
+        let now = Instant::now();
+        let mut tracker = RollTracker::new();
+        tracker.issue(request("r1"), RollResume::Narrate, now);
+        assert!(tracker.cancel("r1", now).is_ok());
+        assert!(tracker.cancel("r1", now).is_err());
+        assert!(tracker
+            .fulfil(&result("r1", 12, 16), now)
+            .unwrap_err()
+            .contains("already cancelled"));
+        assert!(tracker.expire(now + DEFAULT_ROLL_TIMEOUT).is_empty());
+    }
+}
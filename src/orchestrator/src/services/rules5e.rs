@@ -5,6 +5,7 @@
 
 use crate::error::{OrchestratorError, Result};
 use reqwest::Client;
+use rules5e_service::{SpellCastRequest, SpellCastResult};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -204,6 +205,65 @@ impl Rules5eClient {
 
         Ok(result)
     }
+
+    /// Make a saving throw
+    pub async fn saving_throw(&self, request: &SavingThrowRequest) -> Result<SavingThrowResponse> {
+        let response = self
+            .client
+            .post(format!("{}/saving-throw", self.base_url))
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| {
+                OrchestratorError::ServiceError(format!(
+                    "Rules5e saving throw request failed: {}",
+                    e
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(OrchestratorError::ServiceError(format!(
+                "Rules5e saving throw failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        let result: SavingThrowResponse = response.json().await.map_err(|e| {
+            OrchestratorError::ServiceError(format!("Failed to parse saving throw response: {}", e))
+        })?;
+
+        Ok(result)
+    }
+
+    /// Cast a spell: rolls its damage or healing at the slot level it is cast with
+    pub async fn cast_spell(&self, request: &SpellCastRequest) -> Result<SpellCastResult> {
+        let response = self
+            .client
+            .post(format!("{}/spells/cast", self.base_url))
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| {
+                OrchestratorError::ServiceError(format!("Rules5e spell cast request failed: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(OrchestratorError::ServiceError(format!(
+                "Rules5e spell cast failed with status {}: {}",
+                status, text
+            )));
+        }
+
+        let result: SpellCastResult = response.json().await.map_err(|e| {
+            OrchestratorError::ServiceError(format!("Failed to parse spell cast response: {}", e))
+        })?;
+
+        Ok(result)
+    }
 }
 
 // Request/Response types matching rules5e-service API
@@ -281,3 +341,26 @@ pub struct SkillCheckResponse {
     pub dc: i32,
     pub margin: i32,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavingThrowRequest {
+    pub ability: String,
+    pub ability_modifier: i32,
+    pub proficiency_bonus: i32,
+    pub has_proficiency: bool,
+    pub dc: i32,
+    pub advantage: Option<bool>,
+    pub disadvantage: Option<bool>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavingThrowResponse {
+    pub roll: i32,
+    pub natural_roll: u32,
+    pub ability_modifier: i32,
+    pub proficiency_bonus: i32,
+    pub total: i32,
+    pub dc: i32,
+    pub success: bool,
+}
@@ -1,15 +1,18 @@
 //! Game Session Management
 
 pub mod persistence;
+pub mod replay;
 
-use crate::error::Result;
+use crate::dm_console::DmConsole;
+use crate::error::{OrchestratorError, Result};
 use crate::fsm::SceneStateMachine;
 use chrono::{DateTime, Utc};
-use game_engine::GameSession as EngineGameSession;
+use game_engine::{EventLog, GameEvent, GameSession as EngineGameSession};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub use persistence::{SessionPersistence, SerializableSession};
+pub use replay::{RecordedInput, SeedCursor, SessionRecording};
 
 /// Game Session
 ///
@@ -20,9 +23,24 @@ pub struct GameSession {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub state_machine: SceneStateMachine,
-    /// Engine session for combat, scenes, and actors
+    /// Engine session for combat, scenes, and actors, with the events that built it
     #[serde(skip)]
-    pub engine_session: Option<EngineGameSession>,
+    pub engine_log: Option<EventLog>,
+    /// Inputs since the engine log started, for replay
+    #[serde(default)]
+    pub inputs: Vec<RecordedInput>,
+    /// Position of the next roll seed
+    #[serde(default)]
+    pub seed_cursor: SeedCursor,
+    /// Rolled results (random tables, ...) waiting for the DM to narrate them
+    #[serde(skip)]
+    narration_prompts: Vec<String>,
+    /// The human DM's switches and the LLM output held for review
+    #[serde(default)]
+    pub console: DmConsole,
+    /// Author recorded on the events dispatched while a DM command runs
+    #[serde(skip)]
+    event_author: Option<String>,
 }
 
 impl GameSession {
@@ -34,7 +52,14 @@ impl GameSession {
             created_at: now,
             updated_at: now,
             state_machine: SceneStateMachine::new(),
-            engine_session: Some(EngineGameSession::new("VRPG Session".to_string())),
+            engine_log: Some(EventLog::new(EngineGameSession::new(
+                "VRPG Session".to_string(),
+            ))),
+            inputs: Vec::new(),
+            seed_cursor: SeedCursor::default(),
+            narration_prompts: Vec::new(),
+            console: DmConsole::default(),
+            event_author: None,
         }
     }
 
@@ -46,7 +71,12 @@ impl GameSession {
             created_at: now,
             updated_at: now,
             state_machine: SceneStateMachine::new(),
-            engine_session: Some(EngineGameSession::new(name)),
+            engine_log: Some(EventLog::new(EngineGameSession::new(name))),
+            inputs: Vec::new(),
+            seed_cursor: SeedCursor::default(),
+            narration_prompts: Vec::new(),
+            console: DmConsole::default(),
+            event_author: None,
         }
     }
 
@@ -58,41 +88,128 @@ impl GameSession {
     /// Transition to new state
     pub fn transition_to(&mut self, new_state: crate::fsm::SceneState) -> Result<()> {
         self.state_machine.transition_to(new_state)?;
+        self.sync_engine_state(new_state)
+    }
+
+    /// Change state without checking the transition (DM override)
+    pub fn force_transition(&mut self, new_state: crate::fsm::SceneState) -> Result<()> {
+        self.state_machine.force_transition(new_state);
+        self.sync_engine_state(new_state)
+    }
+
+    /// Sync engine session state with FSM state
+    fn sync_engine_state(&mut self, new_state: crate::fsm::SceneState) -> Result<()> {
         self.updated_at = Utc::now();
 
-        // Sync engine session state with FSM state
-        if let Some(ref mut engine) = self.engine_session {
-            match new_state {
-                crate::fsm::SceneState::CombatTurnBased => {
-                    // Ensure we have a current scene before starting combat
-                    if engine.current_scene.is_none() {
-                        engine.create_scene("Combat Scene".to_string());
-                    }
-                    // Combat will be started by INTENT executor
-                }
-                crate::fsm::SceneState::Exploration | crate::fsm::SceneState::SocialFreeFlow => {
-                    // End combat if it was active
-                    if let Some(scene) = engine.get_current_scene_mut() {
-                        if scene.combat_active {
-                            scene.end_combat();
-                        }
-                    }
-                }
-                _ => {}
+        let Some((has_scene, in_combat)) = self
+            .engine_session()
+            .map(|e| (e.current_scene.is_some(), e.in_combat()))
+        else {
+            return Ok(());
+        };
+        match new_state {
+            // Ensure we have a current scene before starting combat
+            // (combat itself will be started by the INTENT executor)
+            crate::fsm::SceneState::CombatTurnBased if !has_scene => {
+                let scene_id = self.next_id();
+                self.dispatch(GameEvent::SceneCreated {
+                    scene_id,
+                    name: "Combat Scene".to_string(),
+                })?;
+            }
+            // End combat if it was active
+            crate::fsm::SceneState::Exploration | crate::fsm::SceneState::SocialFreeFlow
+                if in_combat =>
+            {
+                self.dispatch(GameEvent::CombatEnded)?;
             }
+            _ => {}
         }
 
         Ok(())
     }
 
-    /// Get mutable reference to engine session
-    pub fn engine_session_mut(&mut self) -> Option<&mut EngineGameSession> {
-        self.engine_session.as_mut()
+    /// Queue something for the DM persona to narrate
+    pub fn push_narration_prompt(&mut self, prompt: String) {
+        self.narration_prompts.push(prompt);
+    }
+
+    /// Drain queued narration prompts
+    pub fn take_narration_prompts(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.narration_prompts)
+    }
+
+    /// Get mutable reference to engine session, for draining its buffers (turn events,
+    /// clock hooks, transitions, dismissals) and setting up tests
+    ///
+    /// Changes made here aren't recorded as events; game state changes go through
+    /// `dispatch`.
+    pub(crate) fn engine_session_mut(&mut self) -> Option<&mut EngineGameSession> {
+        self.engine_log.as_mut().map(EventLog::state_mut)
     }
 
     /// Get reference to engine session
     pub fn engine_session(&self) -> Option<&EngineGameSession> {
-        self.engine_session.as_ref()
+        self.engine_log.as_ref().map(EventLog::state)
+    }
+
+    /// Get the engine's event history
+    pub fn engine_log(&self) -> Option<&EventLog> {
+        self.engine_log.as_ref()
+    }
+
+    /// Apply and record a game event on the engine session
+    pub fn dispatch(&mut self, event: GameEvent) -> Result<()> {
+        let author = self.event_author.clone();
+        let log = self.engine_log_mut()?;
+        let recorded = log
+            .dispatch_as(event, author)
+            .map_err(|e| OrchestratorError::EventRejected(e.to_string()))?;
+        tracing::debug!("Event #{}: {:?}", recorded.sequence, recorded.event);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Run `change` with every event it dispatches recorded as made by `author`
+    pub fn authored_by<T>(
+        &mut self,
+        author: &str,
+        change: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let previous = self.event_author.replace(author.to_string());
+        let result = change(self);
+        self.event_author = previous;
+        result
+    }
+
+    /// Revert the last game event
+    pub fn undo(&mut self) -> Result<GameEvent> {
+        let log = self.engine_log_mut()?;
+        if !log.can_undo() {
+            return Err(OrchestratorError::HistoryEmpty("undo".to_string()));
+        }
+        let event = log.undo()?;
+        self.updated_at = Utc::now();
+        Ok(event)
+    }
+
+    /// Re-apply the last undone game event
+    pub fn redo(&mut self) -> Result<GameEvent> {
+        let log = self.engine_log_mut()?;
+        if !log.can_redo() {
+            return Err(OrchestratorError::HistoryEmpty("redo".to_string()));
+        }
+        let event = log
+            .redo()
+            .map_err(|e| OrchestratorError::EventRejected(e.to_string()))?;
+        self.updated_at = Utc::now();
+        Ok(event)
+    }
+
+    fn engine_log_mut(&mut self) -> Result<&mut EventLog> {
+        self.engine_log
+            .as_mut()
+            .ok_or_else(|| OrchestratorError::SessionError("No engine session".to_string()))
     }
 }
 
@@ -155,6 +272,31 @@ mod tests {
         assert!(!session.session_id.is_empty());
     }
 
+    #[test]
+    fn test_dispatch_undo_redo() {
+        let mut session = GameSession::new();
+        assert!(matches!(
+            session.undo(),
+            Err(OrchestratorError::HistoryEmpty(_))
+        ));
+        assert!(matches!(
+            session.dispatch(GameEvent::CombatEnded),
+            Err(OrchestratorError::EventRejected(_))
+        ));
+
+        session
+            .transition_to(crate::fsm::SceneState::CombatTurnBased)
+            .unwrap();
+        assert!(session.engine_session().unwrap().current_scene.is_some());
+        assert!(matches!(
+            session.undo().unwrap(),
+            GameEvent::SceneCreated { .. }
+        ));
+        assert!(session.engine_session().unwrap().current_scene.is_none());
+        session.redo().unwrap();
+        assert!(session.engine_session().unwrap().current_scene.is_some());
+    }
+
     #[test]
     fn test_session_manager() {
         let mut manager = SessionManager::new();
@@ -0,0 +1,537 @@
+//! Session recording and deterministic replay
+//!
+//! A session records its inputs (player text, LLM output and direct UI commands) on top
+//! of the engine state it started from. Every roll seed and generated id comes from the
+//! session id, round, turn and action index, so feeding the same inputs to a fresh
+//! session rebuilds the same game state without calling the LLM. The action index counts
+//! every seed the session has handed out, so no two rolls or ids share one.
+
+use super::GameSession;
+use crate::dm_console::{self, DmCommand};
+use crate::error::{OrchestratorError, Result};
+use crate::intent::IntentExecutor;
+use crate::rolls::SettledRoll;
+use game_engine::{Effect, EffectType, EventLog, GameEvent, GameSession as EngineGameSession};
+use serde::{Deserialize, Serialize};
+
+/// Something that changed the session, in the order it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedInput {
+    /// What a player said (kept for context; the LLM isn't called again on replay)
+    PlayerInput {
+        player_id: String,
+        text: String,
+    },
+    /// LLM/DM output whose INTENTs were executed
+    LlmOutput {
+        text: String,
+        /// Player whose action the output answers; their INTENTs are permission-checked
+        #[serde(default)]
+        issuer: Option<String>,
+    },
+    /// Engine event sent directly by the UI
+    Event {
+        event: GameEvent,
+    },
+    /// A player's (or the server's) answer to a roll request, resolved where it was asked
+    Roll {
+        roll: SettledRoll,
+    },
+    Undo,
+    Redo,
+    /// A command from the DM console
+    DmOverride {
+        /// The DM who sent it
+        author: String,
+        command: DmCommand,
+    },
+}
+
+/// Everything needed to replay a session offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecording {
+    pub session_id: String,
+    pub initial_state: EngineGameSession,
+    pub inputs: Vec<RecordedInput>,
+    /// Engine state at the end of the recording, for verification
+    #[serde(default)]
+    pub final_state: Option<serde_json::Value>,
+}
+
+/// Where the next seed comes from: the n-th roll of the session, and the turn it falls in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedCursor {
+    pub round: u32,
+    pub turn: usize,
+    pub action_index: u64,
+}
+
+/// FNV-1a, stable across platforms and compiler versions
+fn hash_str(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// SplitMix64 finalizer
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Seed for a roll from the session id and its place in the game
+pub fn derive_seed(session_id: &str, cursor: SeedCursor) -> u64 {
+    [cursor.round as u64, cursor.turn as u64, cursor.action_index]
+        .into_iter()
+        .fold(hash_str(session_id), |seed, part| mix(seed ^ part))
+}
+
+impl GameSession {
+    /// Seed for the next roll. The action index never restarts: a later combat, a return
+    /// to exploration or a turn revisited after an undo all get fresh seeds.
+    pub fn next_seed(&mut self) -> u64 {
+        let (round, turn) = self
+            .engine_session()
+            .filter(|e| e.in_combat())
+            .map(|e| (e.get_round(), e.turn_order.current_index()))
+            .unwrap_or((0, 0));
+        self.seed_cursor.round = round;
+        self.seed_cursor.turn = turn;
+        let seed = derive_seed(&self.session_id, self.seed_cursor);
+        self.seed_cursor.action_index += 1;
+        seed
+    }
+
+    /// Id for something the session creates (scenes, advantage tokens, ...)
+    pub fn next_id(&mut self) -> uuid::Uuid {
+        let high = self.next_seed();
+        uuid::Uuid::from_u64_pair(high, mix(high))
+    }
+
+    /// Effect with an id and start time from the session, so a replay builds the same one
+    pub fn new_effect(
+        &mut self,
+        name: String,
+        effect_type: EffectType,
+        target_id: uuid::Uuid,
+        duration_rounds: Option<u32>,
+    ) -> Effect {
+        let id = self.next_id();
+        let now = self.engine_session().map_or(0, |e| e.clock.now());
+        Effect::with_id(id, name, effect_type, target_id, duration_rounds, now)
+    }
+
+    /// Remember an input for replay
+    pub fn record(&mut self, input: RecordedInput) {
+        self.inputs.push(input);
+    }
+
+    /// The session so far as a replayable recording
+    pub fn recording(&self) -> Result<SessionRecording> {
+        let log = self
+            .engine_log()
+            .ok_or_else(|| OrchestratorError::SessionError("No engine session".to_string()))?;
+        Ok(SessionRecording {
+            session_id: self.session_id.clone(),
+            initial_state: log.initial_state().clone(),
+            inputs: self.inputs.clone(),
+            final_state: Some(serde_json::to_value(log.state())?),
+        })
+    }
+
+    /// Fresh session at the start of a recording
+    pub fn from_recording(recording: &SessionRecording) -> Self {
+        let mut session = Self::new();
+        session.session_id = recording.session_id.clone();
+        session.engine_log = Some(EventLog::new(recording.initial_state.clone()));
+        session
+    }
+}
+
+/// Re-run a recording against a fresh session. Inputs that failed when recorded fail
+/// again and are skipped the same way.
+pub async fn replay(
+    recording: &SessionRecording,
+    executor: &IntentExecutor,
+) -> Result<GameSession> {
+    let mut session = GameSession::from_recording(recording);
+    for input in &recording.inputs {
+        session.record(input.clone());
+        let outcome = match input {
+            RecordedInput::PlayerInput { .. } => Ok(()),
+            RecordedInput::LlmOutput { text, issuer } => {
+                for e in executor
+                    .execute_output(text, &mut session, issuer.as_deref())
+                    .await
+                {
+                    tracing::warn!("Replayed INTENT rejected: {}", e);
+                }
+                Ok(())
+            }
+            RecordedInput::Event { event } => session.dispatch(event.clone()),
+            RecordedInput::Roll { roll } => executor.resume_roll(roll, &mut session).await,
+            RecordedInput::Undo => session.undo().map(|_| ()),
+            RecordedInput::Redo => session.redo().map(|_| ()),
+            // Approved output and overridden rolls are recorded after the command
+            RecordedInput::DmOverride { author, command } => {
+                dm_console::apply(&mut session, command, author)
+            }
+        };
+        if let Err(e) = outcome {
+            tracing::warn!("Replayed input failed: {}", e);
+        }
+    }
+    Ok(session)
+}
+
+/// Check a replayed session against the state saved with the recording
+pub fn verify(recording: &SessionRecording, replayed: &GameSession) -> Result<()> {
+    let expected = recording.final_state.as_ref().ok_or_else(|| {
+        OrchestratorError::SessionError("Recording has no final state".to_string())
+    })?;
+    let actual = serde_json::to_value(replayed.engine_session().ok_or_else(|| {
+        OrchestratorError::SessionError("Replayed session has no engine".to_string())
+    })?)?;
+    if &actual != expected {
+        return Err(OrchestratorError::SessionError(
+            "Replayed state differs from the recording".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_engine::{Actor, ActorType};
+
+    #[test]
+    fn test_seeds_follow_turn_and_action() {
+        let cursor = SeedCursor {
+            round: 2,
+            turn: 1,
+            action_index: 0,
+        };
+        let seed = derive_seed("session-a", cursor);
+        assert_eq!(seed, derive_seed("session-a", cursor));
+        assert_ne!(seed, derive_seed("session-b", cursor));
+        assert_ne!(
+            seed,
+            derive_seed(
+                "session-a",
+                SeedCursor {
+                    action_index: 1,
+                    ..cursor
+                }
+            )
+        );
+
+        let mut session = GameSession::new();
+        let first = session.next_seed();
+        assert_ne!(first, session.next_seed());
+        session.seed_cursor = SeedCursor::default();
+        assert_eq!(first, session.next_seed());
+    }
+
+    #[test]
+    fn test_seeds_and_ids_never_repeat_across_combats() {
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Road".to_string());
+        let mut order = Vec::new();
+        for name in ["Fighter", "Goblin"] {
+            let actor = Actor::new(name.to_string(), ActorType::Player);
+            order.push((actor.id, 10));
+            engine.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+
+        let mut seeds = std::collections::HashSet::new();
+        let mut ids = std::collections::HashSet::new();
+        let mut roll = |session: &mut GameSession| {
+            for _ in 0..3 {
+                assert!(seeds.insert(session.next_seed()));
+                assert!(ids.insert(session.next_id()));
+            }
+        };
+        let combat = |session: &mut GameSession, roll: &mut dyn FnMut(&mut GameSession)| {
+            session
+                .dispatch(GameEvent::CombatStarted {
+                    order: order.clone(),
+                    surprised: vec![],
+                })
+                .unwrap();
+            roll(session);
+            session.dispatch(GameEvent::TurnAdvanced).unwrap();
+            roll(session);
+            // Back to the first turn: its seeds are not handed out again
+            session.undo().unwrap();
+            roll(session);
+            session.dispatch(GameEvent::CombatEnded).unwrap();
+        };
+
+        combat(&mut session, &mut roll);
+        combat(&mut session, &mut roll);
+        roll(&mut session);
+        combat(&mut session, &mut roll);
+        roll(&mut session);
+        assert_eq!(seeds.len(), 33);
+    }
+
+    #[tokio::test]
+    async fn test_replay_rebuilds_identical_state() {
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Road".to_string());
+        for (name, dexterity) in [("Fighter", 12), ("Goblin", 14)] {
+            let mut actor = Actor::with_stats(name.to_string(), ActorType::Player, 12, 14);
+            actor.abilities.dexterity = dexterity;
+            engine.add_actor_to_scene(scene_id, actor).unwrap();
+        }
+        // Start the recording from the prepared scene
+        let initial = session.engine_session().unwrap().clone();
+        session.engine_log = Some(EventLog::new(initial));
+
+        let executor = IntentExecutor::new();
+        let inputs = vec![
+            RecordedInput::PlayerInput {
+                player_id: "p1".to_string(),
+                text: "I help the goblin... wait, I attack!".to_string(),
+            },
+            RecordedInput::LlmOutput {
+                text: "[INTENTS]\nINTENT: COMBAT_START\nREASON: ambush\nEND_INTENT\n\
+                       INTENT: HELP\nACTOR: Fighter\nTARGET: Goblin\nEND_INTENT\n[/INTENTS]"
+                    .to_string(),
+                issuer: None,
+            },
+            RecordedInput::Event {
+                event: GameEvent::TurnAdvanced,
+            },
+            RecordedInput::Undo,
+            RecordedInput::Redo,
+        ];
+        let recording = SessionRecording {
+            session_id: session.session_id.clone(),
+            initial_state: session.engine_log().unwrap().initial_state().clone(),
+            inputs,
+            final_state: None,
+        };
+
+        let first = replay(&recording, &executor).await.unwrap();
+        let recorded = first.recording().unwrap();
+        assert_eq!(recorded.inputs.len(), 5);
+        assert!(first.engine_session().unwrap().in_combat());
+
+        let second = replay(&recorded, &executor).await.unwrap();
+        verify(&recorded, &second).unwrap();
+    }
+
+    /// Stand-in for rules5e that answers every spell cast without rolling anything
+    async fn spell_server() -> String {
+        use crate::services::rules5e::{SavingThrowRequest, SavingThrowResponse};
+        use axum::{routing::post, Json, Router};
+        use rules5e_service::{SpellCastRequest, SpellCastResult};
+
+        // Every saving throw fails
+        let app = Router::new()
+            .route(
+                "/spells/cast",
+                post(|Json(request): Json<SpellCastRequest>| async move {
+                    Json(SpellCastResult {
+                        spell_name: request.spell_name,
+                        slot_used: request.slot_level,
+                        success: true,
+                        attack_roll: None,
+                        saving_throw_result: None,
+                        damage: None,
+                        healing: None,
+                        effects_applied: Vec::new(),
+                    })
+                }),
+            )
+            .route(
+                "/saving-throw",
+                post(|Json(request): Json<SavingThrowRequest>| async move {
+                    Json(SavingThrowResponse {
+                        roll: 3,
+                        natural_roll: 3,
+                        ability_modifier: 0,
+                        proficiency_bonus: 0,
+                        total: 3,
+                        dc: request.dc,
+                        success: false,
+                    })
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_replay_rebuilds_spell_effects() {
+        use crate::services::{MemoryClient, Rules5eClient};
+        use rules5e_service::{
+            AreaOfEffect, CastingTime, Spell, SpellAttackType, SpellComponents, SpellDatabase,
+            SpellDuration, SpellEffect, SpellLevel, SpellRange, SpellSchool,
+        };
+        use std::sync::Arc;
+
+        let mut spells = SpellDatabase::new();
+        spells.add_spell(Spell {
+            name: "Bless".to_string(),
+            level: SpellLevel::new(1).unwrap(),
+            school: SpellSchool::Enchantment,
+            casting_time: CastingTime::Action,
+            range: SpellRange::Feet(30),
+            components: SpellComponents::new().with_verbal().with_somatic(),
+            duration: SpellDuration::Minute(1),
+            description: "Up to three creatures add a d4 to attacks and saves".to_string(),
+            higher_levels: None,
+            classes: vec!["Cleric".to_string()],
+            ritual: false,
+            concentration: true,
+            area_of_effect: AreaOfEffect::None,
+            attack_type: SpellAttackType::None,
+            saving_throw: None,
+            effect: SpellEffect {
+                damage: None,
+                damage_type: None,
+                healing: None,
+                condition: None,
+                description: "Blessed".to_string(),
+            },
+        });
+        let executor = IntentExecutor::with_clients(
+            Arc::new(Rules5eClient::new(spell_server().await)),
+            Arc::new(MemoryClient::default()),
+        )
+        .with_spells(Arc::new(spells));
+
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Chapel".to_string());
+        let cleric =
+            Actor::new("Cleric".to_string(), ActorType::Player).with_spell_slots(&[(1, 2)]);
+        engine.add_actor_to_scene(scene_id, cleric).unwrap();
+        engine
+            .add_actor_to_scene(
+                scene_id,
+                Actor::with_stats("Fighter".to_string(), ActorType::Player, 12, 16),
+            )
+            .unwrap();
+        let recording = SessionRecording {
+            session_id: session.session_id.clone(),
+            initial_state: session.engine_session().unwrap().clone(),
+            inputs: vec![RecordedInput::LlmOutput {
+                text: "[INTENTS]\nINTENT: SPELL_CAST\nACTOR: Cleric\nSPELL: Bless\n\
+                       SLOT_LEVEL: 1\nTARGETS: Fighter\nEND_INTENT\n[/INTENTS]"
+                    .to_string(),
+                issuer: None,
+            }],
+            final_state: None,
+        };
+
+        let first = replay(&recording, &executor).await.unwrap();
+        assert_eq!(first.engine_session().unwrap().effects.len(), 1);
+        let recorded = first.recording().unwrap();
+        let second = replay(&recorded, &executor).await.unwrap();
+        verify(&recorded, &second).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_spends_legendary_resistance_on_failed_saves() {
+        use crate::services::{MemoryClient, Rules5eClient};
+        use game_engine::LegendaryTraits;
+        use rules5e_service::{
+            AreaOfEffect, CastingTime, Spell, SpellAttackType, SpellComponents, SpellDatabase,
+            SpellDuration, SpellEffect, SpellLevel, SpellRange, SpellSavingThrow, SpellSchool,
+        };
+        use std::sync::Arc;
+
+        let mut spells = SpellDatabase::new();
+        spells.add_spell(Spell {
+            name: "Dread Word".to_string(),
+            level: SpellLevel::new(0).unwrap(),
+            school: SpellSchool::Enchantment,
+            casting_time: CastingTime::Action,
+            range: SpellRange::Feet(60),
+            components: SpellComponents::new().with_verbal(),
+            duration: SpellDuration::Minute(1),
+            description: "The target is frightened on a failed Wisdom save".to_string(),
+            higher_levels: None,
+            classes: vec!["Cleric".to_string()],
+            ritual: false,
+            concentration: false,
+            area_of_effect: AreaOfEffect::None,
+            attack_type: SpellAttackType::None,
+            saving_throw: Some(SpellSavingThrow {
+                ability: "wisdom".to_string(),
+                success: "No effect".to_string(),
+                failure: "Frightened".to_string(),
+            }),
+            effect: SpellEffect {
+                damage: None,
+                damage_type: None,
+                healing: None,
+                condition: Some("frightened".to_string()),
+                description: "Frightened".to_string(),
+            },
+        });
+        let executor = IntentExecutor::with_clients(
+            Arc::new(Rules5eClient::new(spell_server().await)),
+            Arc::new(MemoryClient::default()),
+        )
+        .with_spells(Arc::new(spells));
+
+        let mut session = GameSession::new();
+        let engine = session.engine_session_mut().unwrap();
+        let scene_id = engine.create_scene("Lair".to_string());
+        engine
+            .add_actor_to_scene(
+                scene_id,
+                Actor::new("Cleric".to_string(), ActorType::Player),
+            )
+            .unwrap();
+        let dragon = Actor::with_stats("Dragon".to_string(), ActorType::Monster, 200, 19)
+            .with_legendary(LegendaryTraits::new(3, Vec::new(), 1, Vec::new()));
+        let dragon_id = dragon.id;
+        engine.add_actor_to_scene(scene_id, dragon).unwrap();
+        let cast = RecordedInput::LlmOutput {
+            text: "[INTENTS]\nINTENT: SPELL_CAST\nACTOR: Cleric\nSPELL: Dread Word\n\
+                   TARGETS: Dragon\nEND_INTENT\n[/INTENTS]"
+                .to_string(),
+            issuer: None,
+        };
+        let recording = SessionRecording {
+            session_id: session.session_id.clone(),
+            initial_state: session.engine_session().unwrap().clone(),
+            inputs: vec![cast.clone()],
+            final_state: None,
+        };
+
+        // The first failed save is turned into a success, the next one sticks
+        let first = replay(&recording, &executor).await.unwrap();
+        let engine = first.engine_session().unwrap();
+        let legendary = engine
+            .get_actor(dragon_id)
+            .unwrap()
+            .legendary
+            .clone()
+            .unwrap();
+        assert_eq!(legendary.resistances_remaining, 0);
+        assert!(engine.effects.is_empty());
+
+        let recording = SessionRecording {
+            inputs: vec![cast.clone(), cast],
+            ..recording
+        };
+        let second = replay(&recording, &executor).await.unwrap();
+        assert_eq!(second.engine_session().unwrap().effects.len(), 1);
+        let recorded = second.recording().unwrap();
+        verify(&recorded, &replay(&recorded, &executor).await.unwrap()).unwrap();
+    }
+}
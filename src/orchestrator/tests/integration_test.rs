@@ -133,3 +133,296 @@ async fn test_roll_result_processing() {
     // This should fail because request doesn't exist, but shouldn't panic
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_roll_request_lifecycle() {
+    use orchestrator::communication::{Audience, IpcMessage, RollRequest, RollResult};
+    use orchestrator::rolls::{RollPolicy, RollResume, RollStatus};
+    use std::time::Duration;
+
+    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let communication = Arc::new(CommunicationState::new(session_manager.clone()));
+    let mut client = communication.subscribe();
+    let session_id = session_manager.write().await.create_session();
+    let orchestrator = Orchestrator::new(session_manager.clone(), communication.clone());
+
+    let request = |id: &str| RollRequest {
+        session_id: session_id.clone(),
+        request_id: id.to_string(),
+        actor_id: "player_1".to_string(),
+        roll_kind: "skill_check".to_string(),
+        skill: Some("perception".to_string()),
+        ability: None,
+        dc: Some(12),
+        formula_hint: Some("1d20+2".to_string()),
+        reason: "spotting the ambush".to_string(),
+        secret: false,
+    };
+    let result = |id: &str, natural: i32, total: i32| RollResult {
+        session_id: session_id.clone(),
+        request_id: id.to_string(),
+        actor_id: "player_1".to_string(),
+        total,
+        natural,
+        breakdown: serde_json::json!({}),
+        client_seed: None,
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+
+    // The player answers, and the same answer can't be used twice
+    orchestrator
+        .request_roll(request("r1"), RollResume::Narrate)
+        .await
+        .unwrap();
+    assert!(matches!(
+        client.recv().await.map(|d| d.message),
+        Ok(IpcMessage::RollRequest(_))
+    ));
+    assert!(orchestrator
+        .process_roll_result(result("r1", 15, 30))
+        .await
+        .is_err());
+    orchestrator
+        .process_roll_result(result("r1", 15, 17))
+        .await
+        .unwrap();
+    match client.recv().await.map(|d| d.message) {
+        Ok(IpcMessage::RollClosed(closed)) => {
+            assert_eq!(closed.status, RollStatus::Fulfilled);
+            assert_eq!(closed.total, Some(17));
+        }
+        other => panic!("Expected roll-closed, got {:?}", other),
+    }
+    let duplicate = orchestrator
+        .process_roll_result(result("r1", 15, 17))
+        .await
+        .unwrap_err();
+    assert!(duplicate.to_string().contains("already fulfilled"));
+
+    // A table with auto-roll has the server roll for players who run out of time
+    communication
+        .set_roll_policy(
+            &session_id,
+            RollPolicy {
+                timeout: Some(Duration::ZERO),
+                auto_roll: true,
+            },
+        )
+        .await;
+    orchestrator
+        .request_roll(request("r2"), RollResume::Narrate)
+        .await
+        .unwrap();
+    assert!(matches!(
+        client.recv().await.map(|d| d.message),
+        Ok(IpcMessage::RollRequest(_))
+    ));
+    orchestrator.expire_rolls().await;
+    match client.recv().await.map(|d| d.message) {
+        Ok(IpcMessage::RollClosed(closed)) => {
+            assert_eq!(closed.status, RollStatus::Expired);
+            assert!(closed.total.is_some());
+        }
+        other => panic!("Expected roll-closed, got {:?}", other),
+    }
+
+    // Cancelled requests refuse late results
+    communication
+        .set_roll_policy(&session_id, RollPolicy::default())
+        .await;
+    orchestrator
+        .request_roll(request("r3"), RollResume::Narrate)
+        .await
+        .unwrap();
+    orchestrator.cancel_roll("r3").await.unwrap();
+    assert_eq!(
+        communication.roll_status("r3").await,
+        Some(RollStatus::Cancelled)
+    );
+    assert!(orchestrator
+        .process_roll_result(result("r3", 10, 12))
+        .await
+        .is_err());
+
+    // Only the DM learns the total of a secret roll
+    let mut secret = request("r4");
+    secret.secret = true;
+    orchestrator
+        .request_roll(secret, RollResume::Narrate)
+        .await
+        .unwrap();
+    let mut client = communication.subscribe();
+    orchestrator
+        .process_roll_result(result("r4", 4, 6))
+        .await
+        .unwrap();
+    let to_room = client.recv().await.unwrap();
+    let to_dm = client.recv().await.unwrap();
+    assert_eq!(to_room.audience, Audience::Session(session_id.clone()));
+    assert!(matches!(to_room.message, IpcMessage::RollClosed(ref c) if c.total.is_none()));
+    assert_eq!(to_dm.audience, Audience::Dm(session_id.clone()));
+    assert!(matches!(to_dm.message, IpcMessage::RollClosed(ref c) if c.total == Some(6)));
+}
+
+#[tokio::test]
+async fn test_dm_console_reviews_and_overrides() {
+    use orchestrator::communication::{DmCommandMessage, IpcMessage, RollRequest};
+    use orchestrator::dm_console::DmCommand;
+    use orchestrator::rolls::{RollResume, RollStatus};
+    use orchestrator::session::RecordedInput;
+
+    let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+    let communication = Arc::new(CommunicationState::new(session_manager.clone()));
+    let mut client = communication.subscribe();
+    let session_id = session_manager.write().await.create_session();
+    let orchestrator = Orchestrator::new(session_manager.clone(), communication.clone());
+
+    let command = |command| DmCommandMessage {
+        session_id: session_id.clone(),
+        command,
+    };
+    let speak = |text: &str| PlayerAction {
+        session_id: session_id.clone(),
+        player_id: "player_1".to_string(),
+        kind: ActionKind::Voice,
+        text: Some(text.to_string()),
+        ui_intent: None,
+        target_id: None,
+        metadata: None,
+    };
+    let mut held_batches = || {
+        let mut batches = Vec::new();
+        while let Ok(delivery) = client.try_recv() {
+            if let IpcMessage::PendingIntents(pending) = delivery.message {
+                batches.push(pending.batch_id);
+            }
+        }
+        batches
+    };
+    let inputs = || async {
+        session_manager
+            .read()
+            .await
+            .get_session(&session_id)
+            .unwrap()
+            .inputs
+            .clone()
+    };
+    let llm_outputs = |inputs: &[RecordedInput]| {
+        inputs
+            .iter()
+            .filter(|i| matches!(i, RecordedInput::LlmOutput { .. }))
+            .count()
+    };
+
+    // With review on the output is held for the DM instead of executed
+    orchestrator
+        .process_dm_command(command(DmCommand::SetReview { enabled: true }), "dm")
+        .await
+        .unwrap();
+    orchestrator
+        .process_player_action(speak("I search the altar"))
+        .await
+        .unwrap();
+    let vetoed = held_batches();
+    assert_eq!(vetoed.len(), 1);
+    orchestrator
+        .process_dm_command(
+            command(DmCommand::Veto {
+                batch_id: vetoed[0],
+            }),
+            "dm",
+        )
+        .await
+        .unwrap();
+    assert_eq!(llm_outputs(&inputs().await), 0);
+
+    orchestrator
+        .process_player_action(speak("I listen at the door"))
+        .await
+        .unwrap();
+    let approved = held_batches();
+    assert_ne!(approved, vetoed);
+    orchestrator
+        .process_dm_command(
+            command(DmCommand::Approve {
+                batch_id: approved[0],
+            }),
+            "dm",
+        )
+        .await
+        .unwrap();
+    assert_eq!(llm_outputs(&inputs().await), 1);
+    assert!(orchestrator
+        .process_dm_command(
+            command(DmCommand::Approve {
+                batch_id: approved[0]
+            }),
+            "dm"
+        )
+        .await
+        .is_err());
+
+    // The DM settles a roll with a total the dice can't make
+    orchestrator
+        .request_roll(
+            RollRequest {
+                session_id: session_id.clone(),
+                request_id: "r1".to_string(),
+                actor_id: "player_1".to_string(),
+                roll_kind: "skill_check".to_string(),
+                skill: Some("perception".to_string()),
+                ability: None,
+                dc: Some(12),
+                formula_hint: Some("1d20+2".to_string()),
+                reason: "hearing the cultists".to_string(),
+                secret: false,
+            },
+            RollResume::Narrate,
+        )
+        .await
+        .unwrap();
+    orchestrator
+        .process_dm_command(
+            command(DmCommand::OverrideRoll {
+                request_id: "r1".to_string(),
+                total: 40,
+                natural: None,
+            }),
+            "dm",
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        communication.roll_status("r1").await,
+        Some(RollStatus::Fulfilled)
+    );
+
+    // Paused, the LLM isn't asked and nothing is held
+    orchestrator
+        .process_dm_command(command(DmCommand::PauseLlm), "dm")
+        .await
+        .unwrap();
+    orchestrator
+        .process_player_action(speak("I open the chest"))
+        .await
+        .unwrap();
+    assert!(held_batches().is_empty());
+
+    let inputs = inputs().await;
+    assert_eq!(llm_outputs(&inputs), 1);
+    assert!(inputs.iter().any(|i| matches!(
+        i,
+        RecordedInput::Roll { roll } if roll.total == 40 && roll.natural == 38
+    )));
+    let overrides: Vec<_> = inputs
+        .iter()
+        .filter_map(|i| match i {
+            RecordedInput::DmOverride { author, command } => Some((author.as_str(), command)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(overrides.len(), 5);
+    assert!(overrides.iter().all(|(author, _)| *author == "dm"));
+    assert!(matches!(overrides[4].1, DmCommand::PauseLlm));
+}
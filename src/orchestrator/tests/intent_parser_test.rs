@@ -149,7 +149,7 @@ END_INTENT
     let intents = IntentParser::parse(text).unwrap();
     assert_eq!(intents.len(), 1);
 
-    if let Intent::CombatStart { reason } = &intents[0] {
+    if let Intent::CombatStart { reason, .. } = &intents[0] {
         // Parser preserves quotes in values
         assert_eq!(reason.as_ref().unwrap(), "\"Goblins attack the party\"");
     } else {
@@ -178,6 +178,46 @@ END_INTENT
     }
 }
 
+#[test]
+fn test_parse_legendary_and_lair_actions() {
+    let text = r#"
+[INTENTS]
+INTENT: LAIR_ACTION
+ACTOR: ancient_red_dragon
+ACTION: Magma Eruption
+END_INTENT
+INTENT: LEGENDARY_ACTION
+ACTOR: ancient_red_dragon
+ACTION: Tail Attack
+TARGET: player_1
+END_INTENT
+[/INTENTS]
+"#;
+
+    let intents = IntentParser::parse(text).unwrap();
+    assert_eq!(intents.len(), 2);
+
+    assert_eq!(
+        intents[0],
+        Intent::LairAction {
+            actor: "ancient_red_dragon".to_string(),
+            action: "Magma Eruption".to_string(),
+        }
+    );
+    if let Intent::LegendaryAction {
+        actor,
+        action,
+        target,
+    } = &intents[1]
+    {
+        assert_eq!(actor, "ancient_red_dragon");
+        assert_eq!(action, "Tail Attack");
+        assert_eq!(target.as_deref(), Some("player_1"));
+    } else {
+        panic!("Expected LegendaryAction intent");
+    }
+}
+
 #[test]
 fn test_parse_lore_query() {
     let text = r#"
@@ -345,3 +385,121 @@ END_INTENT
         assert!(!*move_required);
     }
 }
+
+#[test]
+fn test_parse_travel() {
+    let text = r#"
+[INTENTS]
+INTENT: TRAVEL
+DESTINATION: Daggerford
+PACE: slow
+END_INTENT
+[/INTENTS]
+"#;
+
+    let intents = IntentParser::parse(text).unwrap();
+    assert_eq!(
+        intents,
+        vec![Intent::Travel {
+            destination: "Daggerford".to_string(),
+            pace: Some("slow".to_string()),
+        }]
+    );
+
+    let missing = "[INTENTS]\nINTENT: TRAVEL\nPACE: fast\nEND_INTENT\n[/INTENTS]";
+    assert!(IntentParser::parse(missing).is_err());
+}
+
+#[test]
+fn test_parse_roll_table() {
+    let text = r#"
+[INTENTS]
+INTENT: ROLL_TABLE
+TABLE: wilderness_encounters
+REGION: forest
+LEVEL: 3
+END_INTENT
+[/INTENTS]
+"#;
+
+    let intents = IntentParser::parse(text).unwrap();
+    assert_eq!(
+        intents,
+        vec![Intent::RollTable {
+            table: "wilderness_encounters".to_string(),
+            region: Some("forest".to_string()),
+            party_level: Some(3),
+        }]
+    );
+}
+
+#[test]
+fn test_parse_change_scene() {
+    let text = r#"
+[INTENTS]
+INTENT: CHANGE_SCENE
+SCENE: Sunken Crypt
+ACTORS: Rogue, Cleric
+END_INTENT
+[/INTENTS]
+"#;
+
+    let intents = IntentParser::parse(text).unwrap();
+    assert_eq!(
+        intents,
+        vec![Intent::ChangeScene {
+            scene: "Sunken Crypt".to_string(),
+            actors: vec!["Rogue".to_string(), "Cleric".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn test_parse_json_matches_dsl() {
+    let dsl = r#"
+[INTENTS]
+INTENT: SPELL_CAST
+ACTOR: player_1
+SPELL: fireball
+SLOT_LEVEL: 3
+AREA_CENTER: 4, 7
+TARGETS: goblin_1, goblin_2
+END_INTENT
+INTENT: MELEE_ATTACK
+ACTOR: player_2
+TARGET: goblin_3
+END_INTENT
+[/INTENTS]
+"#;
+    let json = r#"The air ignites.
+{"intents": [
+  {"intent": "SPELL_CAST", "actor": "player_1", "spell": "fireball", "slot_level": 3,
+   "area_center": [4, 7], "targets": ["goblin_1", "goblin_2"]},
+  {"intent": "MELEE_ATTACK", "actor": "player_2", "target": "goblin_3"}
+]}"#;
+    let in_block = "[INTENTS]\n[{\"intent\": \"MELEE_ATTACK\", \"actor\": \"player_2\", \
+                    \"target\": \"goblin_3\"}]\n[/INTENTS]";
+
+    let expected = IntentParser::parse(dsl).unwrap();
+    assert_eq!(expected.len(), 2);
+    assert_eq!(IntentParser::parse(json).unwrap(), expected);
+    assert_eq!(IntentParser::parse(in_block).unwrap(), expected[1..]);
+
+    // Values may hold colons and newlines; unknown keys are rejected
+    let context = r#"{"intent": "SKILL_CHECK", "actor": "player_1", "skill": "insight",
+                      "context": "She said: \"no\".\nThen left."}"#;
+    match &IntentParser::parse(context).unwrap()[0] {
+        Intent::SkillCheck {
+            context,
+            suggest_dc,
+            ..
+        } => {
+            assert_eq!(context.as_deref(), Some("She said: \"no\".\nThen left."));
+            assert!(*suggest_dc);
+        }
+        other => panic!("Expected SkillCheck, got {:?}", other),
+    }
+    let unknown = r#"{"intent": "DASH", "actor": "player_1", "distance": 60}"#;
+    assert!(IntentParser::parse(unknown).is_err());
+    assert!(IntentParser::json_schema()["definitions"]["Intent"].is_object());
+}
@@ -0,0 +1,369 @@
+//! Initiative - D&D 5e
+//! Rolls initiative (d20 + DEX + bonuses) for every combatant and orders them,
+//! breaking ties by DEX score and then by a d20 roll-off
+
+use crate::dice::{DiceExpression, DiceRoller, RollMode};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Features that add to initiative rolls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InitiativeBonus {
+    /// Alert feat: +5
+    Alert,
+    /// Bard: half proficiency (rounded down) to checks without proficiency
+    JackOfAllTrades,
+    /// Champion: half proficiency (rounded up) to STR/DEX/CON checks without proficiency
+    RemarkableAthlete,
+    /// Any other fixed bonus (magic items, Dread Ambusher, ...)
+    Flat(i32),
+}
+
+impl InitiativeBonus {
+    /// Map a feat or class feature name to its initiative bonus, if it has one
+    pub fn from_feature(name: &str) -> Option<Self> {
+        let normalized = name.trim().to_lowercase().replace(['_', '-'], " ");
+        match normalized.as_str() {
+            "alert" => Some(InitiativeBonus::Alert),
+            "jack of all trades" => Some(InitiativeBonus::JackOfAllTrades),
+            "remarkable athlete" => Some(InitiativeBonus::RemarkableAthlete),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self, proficiency_bonus: i32) -> i32 {
+        match self {
+            InitiativeBonus::Alert => 5,
+            InitiativeBonus::JackOfAllTrades => proficiency_bonus / 2,
+            InitiativeBonus::RemarkableAthlete => (proficiency_bonus + 1) / 2,
+            InitiativeBonus::Flat(bonus) => *bonus,
+        }
+    }
+}
+
+/// A creature taking part in the initiative roll
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitiativeCombatant {
+    pub id: String,
+    pub dexterity_score: i32,
+    pub proficiency_bonus: i32,
+    #[serde(default)]
+    pub bonuses: Vec<InitiativeBonus>,
+    #[serde(default)]
+    pub advantage: bool,
+    /// Combatants sharing a group (identical monsters) roll once and act together
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+impl InitiativeCombatant {
+    pub fn modifier(&self) -> i32 {
+        // Jack of All Trades and Remarkable Athlete don't stack with each other
+        let mut half_proficiency = 0;
+        let mut total = (self.dexterity_score - 10).div_euclid(2);
+        for bonus in &self.bonuses {
+            match bonus {
+                InitiativeBonus::JackOfAllTrades | InitiativeBonus::RemarkableAthlete => {
+                    half_proficiency = half_proficiency.max(bonus.value(self.proficiency_bonus));
+                }
+                _ => total += bonus.value(self.proficiency_bonus),
+            }
+        }
+        total + half_proficiency
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitiativeRequest {
+    pub combatants: Vec<InitiativeCombatant>,
+    pub seed: Option<u64>,
+}
+
+/// Initiative of a single combatant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitiativeRoll {
+    pub id: String,
+    pub natural_roll: u32,
+    pub modifier: i32,
+    pub total: i32,
+    pub dexterity_score: i32,
+    pub group: Option<String>,
+    /// d20 roll-offs used to break a tie on total and DEX (empty if there was none)
+    pub roll_off: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitiativeResult {
+    /// Combatants in turn order (highest initiative first)
+    pub order: Vec<InitiativeRoll>,
+    pub seed: Option<u64>,
+}
+
+pub struct InitiativeRoller;
+
+impl Default for InitiativeRoller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InitiativeRoller {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn roll(&self, request: &InitiativeRequest) -> Result<InitiativeResult> {
+        let mut roller = if let Some(seed) = request.seed {
+            DiceRoller::with_seed(seed)
+        } else {
+            DiceRoller::new()
+        };
+
+        // Each group (or ungrouped combatant) is one unit with a single d20
+        let mut units: Vec<Vec<usize>> = Vec::new();
+        let mut group_units: HashMap<&str, usize> = HashMap::new();
+        for (index, combatant) in request.combatants.iter().enumerate() {
+            match combatant.group.as_deref() {
+                Some(group) => match group_units.get(group) {
+                    Some(&unit) => units[unit].push(index),
+                    None => {
+                        group_units.insert(group, units.len());
+                        units.push(vec![index]);
+                    }
+                },
+                None => units.push(vec![index]),
+            }
+        }
+
+        let mut rolls: Vec<InitiativeRoll> = request
+            .combatants
+            .iter()
+            .map(|c| InitiativeRoll {
+                id: c.id.clone(),
+                natural_roll: 0,
+                modifier: c.modifier(),
+                total: 0,
+                dexterity_score: c.dexterity_score,
+                group: c.group.clone(),
+                roll_off: Vec::new(),
+            })
+            .collect();
+
+        for unit in &units {
+            let leader = &request.combatants[unit[0]];
+            let natural_roll = Self::roll_d20(&mut roller, leader.advantage)?;
+            for &index in unit {
+                rolls[index].natural_roll = natural_roll;
+                rolls[index].total = natural_roll as i32 + rolls[index].modifier;
+            }
+        }
+
+        // Order units by total, then DEX score; remaining ties go to a roll-off
+        let keys: Vec<(i32, i32)> = units
+            .iter()
+            .map(|unit| (rolls[unit[0]].total, rolls[unit[0]].dexterity_score))
+            .collect();
+        let mut sorted_units: Vec<usize> = (0..units.len()).collect();
+        sorted_units.sort_by(|&a, &b| keys[b].cmp(&keys[a]));
+
+        let mut ordered_units = Vec::with_capacity(units.len());
+        let mut roll_offs: HashMap<usize, Vec<u32>> = HashMap::new();
+        let mut start = 0;
+        while start < sorted_units.len() {
+            let mut end = start + 1;
+            while end < sorted_units.len() && keys[sorted_units[end]] == keys[sorted_units[start]] {
+                end += 1;
+            }
+            let tied = sorted_units[start..end].to_vec();
+            if tied.len() == 1 {
+                ordered_units.extend(tied);
+            } else {
+                ordered_units.extend(Self::roll_off(&mut roller, tied, &mut roll_offs)?);
+            }
+            start = end;
+        }
+        for (unit, dice) in roll_offs {
+            for &index in &units[unit] {
+                rolls[index].roll_off = dice.clone();
+            }
+        }
+
+        let order = ordered_units
+            .into_iter()
+            .flat_map(|unit| units[unit].iter().map(|&index| rolls[index].clone()))
+            .collect::<Vec<_>>();
+
+        Ok(InitiativeResult {
+            order,
+            seed: request.seed,
+        })
+    }
+
+    fn roll_d20(roller: &mut DiceRoller, advantage: bool) -> Result<u32> {
+        let (count, mode) = if advantage {
+            (2, RollMode::Advantage)
+        } else {
+            (1, RollMode::Normal)
+        };
+        let result = roller.roll(
+            &DiceExpression {
+                count,
+                sides: 20,
+                modifier: 0,
+            },
+            mode,
+        )?;
+        Ok(result.total as u32)
+    }
+
+    /// Tied units each roll a d20, highest first; ties re-roll among themselves
+    fn roll_off(
+        roller: &mut DiceRoller,
+        tied: Vec<usize>,
+        roll_offs: &mut HashMap<usize, Vec<u32>>,
+    ) -> Result<Vec<usize>> {
+        let mut rolled = Vec::with_capacity(tied.len());
+        for unit in tied {
+            let roll = Self::roll_d20(roller, false)?;
+            roll_offs.entry(unit).or_default().push(roll);
+            rolled.push((unit, roll));
+        }
+        rolled.sort_by_key(|&(_, roll)| std::cmp::Reverse(roll));
+
+        let mut ordered = Vec::with_capacity(rolled.len());
+        let mut start = 0;
+        while start < rolled.len() {
+            let mut end = start + 1;
+            while end < rolled.len() && rolled[end].1 == rolled[start].1 {
+                end += 1;
+            }
+            if end - start == 1 {
+                ordered.push(rolled[start].0);
+            } else {
+                let still_tied = rolled[start..end].iter().map(|(unit, _)| *unit).collect();
+                ordered.extend(Self::roll_off(roller, still_tied, roll_offs)?);
+            }
+            start = end;
+        }
+        Ok(ordered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combatant(id: &str, dexterity_score: i32) -> InitiativeCombatant {
+        InitiativeCombatant {
+            id: id.to_string(),
+            dexterity_score,
+            proficiency_bonus: 2,
+            bonuses: vec![],
+            advantage: false,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_initiative_bonuses() {
+        let mut bard = combatant("bard", 14);
+        bard.proficiency_bonus = 3;
+        bard.bonuses = vec![InitiativeBonus::JackOfAllTrades, InitiativeBonus::Alert];
+        assert_eq!(bard.modifier(), 2 + 1 + 5);
+
+        let mut champion = combatant("champion", 8);
+        champion.proficiency_bonus = 3;
+        champion.bonuses = vec![InitiativeBonus::RemarkableAthlete];
+        assert_eq!(champion.modifier(), -1 + 2);
+
+        assert_eq!(
+            InitiativeBonus::from_feature("Jack_of_All_Trades"),
+            Some(InitiativeBonus::JackOfAllTrades)
+        );
+        assert_eq!(InitiativeBonus::from_feature("Sneak Attack"), None);
+    }
+
+    #[test]
+    fn test_initiative_order_is_sorted_and_deterministic() {
+        let request = InitiativeRequest {
+            combatants: vec![
+                combatant("a", 10),
+                combatant("b", 18),
+                combatant("c", 12),
+                combatant("d", 14),
+            ],
+            seed: Some(7),
+        };
+        let roller = InitiativeRoller::new();
+        let first = roller.roll(&request).unwrap();
+        let second = roller.roll(&request).unwrap();
+
+        let ids = |r: &InitiativeResult| r.order.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&first), ids(&second));
+        assert_eq!(first.order.len(), 4);
+        for pair in first.order.windows(2) {
+            assert!(
+                (pair[0].total, pair[0].dexterity_score)
+                    >= (pair[1].total, pair[1].dexterity_score)
+            );
+        }
+    }
+
+    #[test]
+    fn test_initiative_ties_break_on_dexterity_then_roll_off() {
+        // All three have +2; "slow" gets it from DEX 12 plus a flat bonus
+        let mut slow = combatant("slow", 12);
+        slow.bonuses = vec![InitiativeBonus::Flat(1)];
+        let fast = combatant("fast", 14);
+        let twin = combatant("twin", 14);
+
+        for seed in 0..50 {
+            let result = InitiativeRoller::new()
+                .roll(&InitiativeRequest {
+                    combatants: vec![slow.clone(), fast.clone(), twin.clone()],
+                    seed: Some(seed),
+                })
+                .unwrap();
+            for pair in result.order.windows(2) {
+                if pair[0].total != pair[1].total {
+                    continue;
+                }
+                assert!(pair[0].dexterity_score >= pair[1].dexterity_score);
+                if pair[0].dexterity_score == pair[1].dexterity_score {
+                    assert!(pair[0].roll_off > pair[1].roll_off);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_initiative_shares_roll() {
+        let mut goblin_1 = combatant("goblin_1", 14);
+        goblin_1.group = Some("goblin".to_string());
+        let mut goblin_2 = combatant("goblin_2", 14);
+        goblin_2.group = Some("goblin".to_string());
+        let fighter = combatant("fighter", 12);
+
+        let result = InitiativeRoller::new()
+            .roll(&InitiativeRequest {
+                combatants: vec![goblin_1, fighter, goblin_2],
+                seed: Some(3),
+            })
+            .unwrap();
+
+        let positions: Vec<usize> = result
+            .order
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.group.is_some())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[1], positions[0] + 1);
+        assert_eq!(
+            result.order[positions[0]].total,
+            result.order[positions[1]].total
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// Items System - D&D 5e
+// Potions, spell scrolls, wands and other consumables a creature can use
+
+use serde::{Deserialize, Serialize};
+
+/// What using an item costs on the creature's turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemAction {
+    Action,
+    BonusAction,
+    Free,
+}
+
+/// When an item's spent charges come back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemRecharge {
+    #[default]
+    Dawn,
+    Dusk,
+    LongRest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ItemEffect {
+    /// Restores hit points, as a dice expression (e.g. "2d4+2")
+    Healing { dice: String },
+    /// Casts a spell; without a level it is cast at the spell's own level
+    Spell { spell: String, level: Option<u8> },
+    /// Puts a lasting effect on the user (e.g. antitoxin)
+    Effect {
+        name: String,
+        duration_rounds: Option<u32>,
+    },
+    /// Used up with nothing for the rules to resolve (rations, torches)
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub action: ItemAction,
+    pub effect: ItemEffect,
+    /// Charges when full; items without charges are used up
+    pub charges: Option<u32>,
+    /// When spent charges come back
+    #[serde(default)]
+    pub recharge: ItemRecharge,
+}
+
+impl Item {
+    /// An item that is used up
+    pub fn new(name: &str, action: ItemAction, effect: ItemEffect) -> Self {
+        Self {
+            name: name.to_string(),
+            action,
+            effect,
+            charges: None,
+            recharge: ItemRecharge::Dawn,
+        }
+    }
+
+    fn with_charges(mut self, charges: u32) -> Self {
+        self.charges = Some(charges);
+        self
+    }
+
+    /// A scroll holding `spell`, read as an action and used up
+    pub fn spell_scroll(spell: &str) -> Self {
+        Self::new(
+            &format!("Spell Scroll ({})", spell),
+            ItemAction::Action,
+            ItemEffect::Spell {
+                spell: spell.to_string(),
+                level: None,
+            },
+        )
+    }
+
+    /// Whether using the item spends one of the creature's stack
+    pub fn is_consumed(&self) -> bool {
+        self.charges.is_none()
+    }
+}
+
+pub struct ItemDatabase;
+
+impl ItemDatabase {
+    /// Item by name; any "Spell Scroll (<spell>)" or "Scroll of <spell>" is a spell scroll
+    pub fn get_item(name: &str) -> Option<Item> {
+        if let Some(item) = Self::all_items()
+            .into_iter()
+            .find(|i| i.name.to_lowercase() == name.to_lowercase())
+        {
+            return Some(item);
+        }
+        let lower = name.to_lowercase();
+        let spell = if lower.starts_with("spell scroll (") && lower.ends_with(')') {
+            &name["spell scroll (".len()..name.len() - 1]
+        } else if lower.starts_with("scroll of ") {
+            &name["scroll of ".len()..]
+        } else {
+            return None;
+        };
+        let spell = spell.trim();
+        (!spell.is_empty()).then(|| Item::spell_scroll(spell))
+    }
+
+    pub fn all_items() -> Vec<Item> {
+        let healing = |name: &str, dice: &str| {
+            Item::new(
+                name,
+                ItemAction::Action,
+                ItemEffect::Healing {
+                    dice: dice.to_string(),
+                },
+            )
+        };
+        let wand = |name: &str, spell: &str, level: u8| {
+            Item::new(
+                name,
+                ItemAction::Action,
+                ItemEffect::Spell {
+                    spell: spell.to_string(),
+                    level: Some(level),
+                },
+            )
+            .with_charges(7)
+        };
+        vec![
+            // Potions
+            healing("Potion of Healing", "2d4+2"),
+            healing("Potion of Greater Healing", "4d4+4"),
+            healing("Potion of Superior Healing", "8d4+8"),
+            healing("Potion of Supreme Healing", "10d4+20"),
+            // Wands
+            wand("Wand of Magic Missiles", "Magic Missile", 1),
+            wand("Wand of Web", "Web", 2),
+            wand("Wand of Fireballs", "Fireball", 3),
+            wand("Wand of Lightning Bolts", "Lightning Bolt", 3),
+            // Consumables
+            Item::new(
+                "Antitoxin",
+                ItemAction::Action,
+                ItemEffect::Effect {
+                    name: "Antitoxin".to_string(),
+                    duration_rounds: Some(600),
+                },
+            ),
+            Item::new("Rations", ItemAction::Free, ItemEffect::None),
+            Item::new("Torch", ItemAction::Action, ItemEffect::None),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_item_and_spell_scrolls() {
+        let potion = ItemDatabase::get_item("potion of healing").unwrap();
+        assert_eq!(
+            potion.effect,
+            ItemEffect::Healing {
+                dice: "2d4+2".to_string()
+            }
+        );
+        assert!(potion.is_consumed());
+
+        let wand = ItemDatabase::get_item("Wand of Magic Missiles").unwrap();
+        assert_eq!(wand.charges, Some(7));
+        assert_eq!(wand.recharge, ItemRecharge::Dawn);
+        assert!(!wand.is_consumed());
+
+        for name in ["Spell Scroll (Fireball)", "scroll of Fireball"] {
+            let scroll = ItemDatabase::get_item(name).unwrap();
+            assert_eq!(scroll.name, "Spell Scroll (Fireball)");
+            assert_eq!(
+                scroll.effect,
+                ItemEffect::Spell {
+                    spell: "Fireball".to_string(),
+                    level: None
+                }
+            );
+        }
+        assert!(ItemDatabase::get_item("Spell Scroll ()").is_none());
+        assert!(ItemDatabase::get_item("Bag of Holding").is_none());
+    }
+}
@@ -9,9 +9,12 @@ pub mod cr_xp;
 pub mod damage;
 pub mod dice;
 pub mod error;
+pub mod initiative;
+pub mod items;
 pub mod server;
 pub mod skills;
 pub mod spells;
+pub mod tables;
 pub mod weapons;
 
 pub use ability::{Ability, AbilityCheckRequest, AbilityCheckResult, AbilityChecker};
@@ -24,6 +27,11 @@ pub use cr_xp::{xp_to_cr_approximate, ChallengeRating};
 pub use damage::{DamageRequest, DamageResolver, DamageResult, DamageType};
 pub use dice::{DiceExpression, DiceRoller, RollMode, RollResult};
 pub use error::{Result, RulesError};
+pub use initiative::{
+    InitiativeBonus, InitiativeCombatant, InitiativeRequest, InitiativeResult, InitiativeRoll,
+    InitiativeRoller,
+};
+pub use items::{Item, ItemAction, ItemDatabase, ItemEffect, ItemRecharge};
 pub use server::RulesServer;
 pub use skills::{
     Skill, SkillBonus, SkillCalculator, SkillCheckRequest, SkillCheckResult, SkillProficiency,
@@ -33,7 +41,8 @@ pub use spells::{
     SpellCaster, SpellComponents, SpellDatabase, SpellDuration, SpellEffect, SpellLevel,
     SpellRange, SpellSavingThrow, SpellSchool, SpellSlots,
 };
-pub use weapons::{Weapon, WeaponCategory, WeaponDatabase, WeaponProperty, WeaponType};
+pub use tables::{RandomTable, TableContext, TableEntry, TableLibrary, TableRoll};
+pub use weapons::{RangeBand, Weapon, WeaponCategory, WeaponDatabase, WeaponProperty, WeaponType};
 
 #[cfg(test)]
 mod tests {
@@ -19,8 +19,10 @@ use crate::cr_xp::{xp_to_cr_approximate, ChallengeRating};
 use crate::damage::{DamageRequest, DamageResolver};
 use crate::dice::{DiceRoller, RollMode};
 use crate::error::{Result, RulesError};
+use crate::initiative::{InitiativeRequest, InitiativeRoller};
 use crate::skills::{Skill, SkillCalculator, SkillCheckResult};
 use crate::spells::{Spell, SpellCastRequest, SpellCaster, SpellDatabase, SpellSchool};
+use crate::tables::{TableContext, TableLibrary, TableRoll};
 use crate::weapons::{Weapon, WeaponCategory, WeaponDatabase, WeaponType};
 
 #[derive(Clone)]
@@ -28,8 +30,10 @@ pub struct AppState {
     ability_checker: Arc<AbilityChecker>,
     attack_resolver: Arc<AttackResolver>,
     damage_resolver: Arc<DamageResolver>,
+    initiative_roller: Arc<InitiativeRoller>,
     spell_caster: Arc<SpellCaster>,
     spell_database: Arc<std::sync::Mutex<SpellDatabase>>,
+    tables: Arc<TableLibrary>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -75,8 +79,10 @@ impl RulesServer {
                 ability_checker: Arc::new(AbilityChecker::new()),
                 attack_resolver: Arc::new(AttackResolver::new()),
                 damage_resolver: Arc::new(DamageResolver::new()),
+                initiative_roller: Arc::new(InitiativeRoller::new()),
                 spell_caster: Arc::new(SpellCaster::new()),
                 spell_database: Arc::new(std::sync::Mutex::new(SpellDatabase::new())),
+                tables: Arc::new(load_tables()?),
             },
         })
     }
@@ -89,6 +95,7 @@ impl RulesServer {
             .route("/ability-check", post(ability_check_handler))
             .route("/saving-throw", post(saving_throw_handler))
             .route("/damage", post(damage_handler))
+            .route("/initiative", post(initiative_handler))
             .route(
                 "/ability-scores/calculate-modifier",
                 post(ability_modifier_handler),
@@ -124,6 +131,8 @@ impl RulesServer {
             .route("/spells/by-class", post(get_spells_by_class_handler))
             .route("/spells/cast", post(cast_spell_handler))
             .route("/spells/slots/for-full-caster", post(create_full_caster_slots_handler))
+            .route("/tables/list", get(list_tables_handler))
+            .route("/tables/roll", post(roll_table_handler))
             .layer(ServiceBuilder::new().layer(CorsLayer::permissive()))
             .with_state(self.state.clone());
 
@@ -258,6 +267,19 @@ async fn damage_handler(
     Json(result)
 }
 
+async fn initiative_handler(
+    State(state): State<AppState>,
+    Json(request): Json<InitiativeRequest>,
+) -> std::result::Result<Json<crate::initiative::InitiativeResult>, (StatusCode, String)> {
+    let result = state.initiative_roller.roll(&request).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Initiative error: {}", e),
+        )
+    })?;
+    Ok(Json(result))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AbilityModifierRequest {
     pub ability_score: u8,
@@ -1000,3 +1022,43 @@ async fn create_full_caster_slots_handler(
         max_level: slots.max_level,
     }))
 }
+
+/// Built-in tables plus any `*.json` tables in `RULES5E_TABLES_DIR`
+fn load_tables() -> Result<TableLibrary> {
+    let mut tables = TableLibrary::builtin();
+    if let Ok(dir) = std::env::var("RULES5E_TABLES_DIR") {
+        let loaded = tables.load_dir(std::path::Path::new(&dir))?;
+        info!("Loaded {} random tables from {}", loaded, dir);
+    }
+    Ok(tables)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListTablesResponse {
+    pub tables: Vec<String>,
+}
+
+async fn list_tables_handler(State(state): State<AppState>) -> Json<ListTablesResponse> {
+    Json(ListTablesResponse {
+        tables: state.tables.names().into_iter().map(String::from).collect(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollTableRequest {
+    pub table: String,
+    #[serde(flatten)]
+    pub context: TableContext,
+    pub seed: Option<u64>,
+}
+
+async fn roll_table_handler(
+    State(state): State<AppState>,
+    Json(request): Json<RollTableRequest>,
+) -> std::result::Result<Json<TableRoll>, (StatusCode, String)> {
+    state
+        .tables
+        .roll(&request.table, &request.context, request.seed)
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
@@ -0,0 +1,327 @@
+use crate::dice::{DiceRoller, RollMode};
+use crate::error::{Result, RulesError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Nested table references deeper than this are treated as a cycle
+const MAX_TABLE_DEPTH: usize = 8;
+
+/// Tables shipped with the service
+const BUILTIN_TABLES: [&str; 4] = [
+    include_str!("../data/tables/wilderness_encounters.json"),
+    include_str!("../data/tables/tavern_rumors.json"),
+    include_str!("../data/tables/trinkets.json"),
+    include_str!("../data/tables/weather.json"),
+];
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// One row of a random table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableEntry {
+    /// d100 range, e.g. `[1, 20]`; takes precedence over `weight`
+    #[serde(default)]
+    pub range: Option<(u32, u32)>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    pub result: String,
+    /// How many, as a dice expression (e.g. "1d4+1")
+    #[serde(default)]
+    pub quantity: Option<String>,
+    /// Roll on this table as well and attach its result
+    #[serde(default)]
+    pub table: Option<String>,
+    /// Only available in these regions (terrain or location names); empty means anywhere
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// Only available at these times of day (dawn, morning, afternoon, dusk, night)
+    #[serde(default)]
+    pub time_of_day: Vec<String>,
+    #[serde(default)]
+    pub min_level: Option<u32>,
+    #[serde(default)]
+    pub max_level: Option<u32>,
+}
+
+impl TableEntry {
+    /// Chance of this row relative to the others
+    pub fn weight(&self) -> u32 {
+        match self.range {
+            Some((low, high)) => high.saturating_sub(low) + 1,
+            None => self.weight,
+        }
+    }
+
+    pub fn is_available(&self, context: &TableContext) -> bool {
+        let matches = |allowed: &[String], value: &Option<String>| {
+            allowed.is_empty()
+                || value
+                    .as_ref()
+                    .is_some_and(|v| allowed.iter().any(|a| a.eq_ignore_ascii_case(v)))
+        };
+        let level_ok = context.party_level.map_or(true, |level| {
+            self.min_level.map_or(true, |min| level >= min)
+                && self.max_level.map_or(true, |max| level <= max)
+        });
+        matches(&self.regions, &context.region)
+            && matches(&self.time_of_day, &context.time_of_day)
+            && level_ok
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomTable {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub entries: Vec<TableEntry>,
+}
+
+/// Where and when a table is rolled; unset fields don't filter anything
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableContext {
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub time_of_day: Option<String>,
+    #[serde(default)]
+    pub party_level: Option<u32>,
+}
+
+/// A rolled result, with any nested table it pointed at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableRoll {
+    pub table: String,
+    /// Roll on the available rows (1..=die)
+    pub roll: u32,
+    pub die: u32,
+    pub result: String,
+    pub quantity: Option<i32>,
+    pub nested: Option<Box<TableRoll>>,
+}
+
+impl TableRoll {
+    /// The result as one line of text, e.g. "3 wolves (weather: light rain)"
+    pub fn describe(&self) -> String {
+        let mut text = match self.quantity {
+            Some(quantity) => format!("{} {}", quantity, self.result),
+            None => self.result.clone(),
+        };
+        if let Some(nested) = &self.nested {
+            text.push_str(&format!(" ({}: {})", nested.table, nested.describe()));
+        }
+        text
+    }
+}
+
+/// Named random tables: encounters, rumors, trinkets, weather...
+#[derive(Debug, Clone, Default)]
+pub struct TableLibrary {
+    tables: BTreeMap<String, RandomTable>,
+}
+
+impl TableLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Library with the tables shipped in `data/tables`
+    pub fn builtin() -> Self {
+        let mut library = Self::new();
+        for json in BUILTIN_TABLES {
+            library
+                .add_json(json)
+                .expect("built-in random tables are valid JSON");
+        }
+        library
+    }
+
+    /// Add a table from JSON, replacing any table with the same name
+    pub fn add_json(&mut self, json: &str) -> Result<()> {
+        self.add(serde_json::from_str(json)?);
+        Ok(())
+    }
+
+    pub fn add(&mut self, table: RandomTable) {
+        self.tables.insert(table.name.to_lowercase(), table);
+    }
+
+    /// Load every `*.json` file in `dir` as a table
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize> {
+        let mut loaded = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                self.add_json(&std::fs::read_to_string(&path)?)?;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RandomTable> {
+        self.tables.get(&name.to_lowercase())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.tables.values().map(|t| t.name.as_str()).collect()
+    }
+
+    /// Roll on `name`, following nested table references
+    pub fn roll(&self, name: &str, context: &TableContext, seed: Option<u64>) -> Result<TableRoll> {
+        let mut roller = seed.map(DiceRoller::with_seed).unwrap_or_default();
+        self.roll_with(name, context, &mut roller, 0)
+    }
+
+    fn roll_with(
+        &self,
+        name: &str,
+        context: &TableContext,
+        roller: &mut DiceRoller,
+        depth: usize,
+    ) -> Result<TableRoll> {
+        if depth >= MAX_TABLE_DEPTH {
+            return Err(RulesError::InvalidInput(format!(
+                "Table references nest too deeply at {}",
+                name
+            )));
+        }
+        let table = self
+            .get(name)
+            .ok_or_else(|| RulesError::InvalidInput(format!("Unknown table: {}", name)))?;
+        let available: Vec<&TableEntry> = table
+            .entries
+            .iter()
+            .filter(|e| e.weight() > 0 && e.is_available(context))
+            .collect();
+        let die: u32 = available.iter().map(|e| e.weight()).sum();
+        if die == 0 {
+            return Err(RulesError::InvalidInput(format!(
+                "No entries of {} apply here",
+                table.name
+            )));
+        }
+
+        let roll = roller
+            .roll(&DiceRoller::parse(&format!("1d{}", die))?, RollMode::Normal)?
+            .total as u32;
+        let mut left = roll;
+        let entry = available
+            .iter()
+            .find(|e| {
+                if left <= e.weight() {
+                    return true;
+                }
+                left -= e.weight();
+                false
+            })
+            .ok_or_else(|| RulesError::Calculation(format!("Roll {} out of range", roll)))?;
+
+        let quantity = entry
+            .quantity
+            .as_ref()
+            .map(|expr| {
+                let expression = DiceRoller::parse(expr)?;
+                roller.roll(&expression, RollMode::Normal).map(|r| r.total)
+            })
+            .transpose()?;
+        let nested = entry
+            .table
+            .as_ref()
+            .map(|nested| {
+                self.roll_with(nested, context, roller, depth + 1)
+                    .map(Box::new)
+            })
+            .transpose()?;
+
+        Ok(TableRoll {
+            table: table.name.clone(),
+            roll,
+            die,
+            result: entry.result.clone(),
+            quantity,
+            nested,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_tables_roll_everywhere() {
+        let library = TableLibrary::builtin();
+        assert_eq!(library.names().len(), 4);
+        for name in ["tavern_rumors", "trinkets", "weather"] {
+            let table = library.get(name).unwrap();
+            let total: u32 = table.entries.iter().map(|e| e.weight()).sum();
+            assert_eq!(total, 100, "{} should cover a d100", name);
+        }
+
+        let context = TableContext {
+            region: Some("forest".to_string()),
+            time_of_day: Some("night".to_string()),
+            party_level: Some(3),
+        };
+        for seed in 0..50 {
+            let roll = library
+                .roll("wilderness_encounters", &context, Some(seed))
+                .unwrap();
+            assert!(roll.roll >= 1 && roll.roll <= roll.die);
+            assert_eq!(
+                roll,
+                library
+                    .roll("wilderness_encounters", &context, Some(seed))
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_conditions_and_nested_tables() {
+        let mut library = TableLibrary::new();
+        library
+            .add_json(
+                r#"{
+                    "name": "camp",
+                    "entries": [
+                        {"range": [1, 50], "result": "wolves", "quantity": "1d4+1",
+                         "time_of_day": ["night"], "table": "sky"},
+                        {"range": [51, 100], "result": "merchant", "regions": ["road"],
+                         "min_level": 5}
+                    ]
+                }"#,
+            )
+            .unwrap();
+        library
+            .add_json(r#"{"name": "sky", "entries": [{"result": "clear"}]}"#)
+            .unwrap();
+
+        let night = TableContext {
+            time_of_day: Some("Night".to_string()),
+            party_level: Some(2),
+            ..Default::default()
+        };
+        let roll = library.roll("camp", &night, Some(7)).unwrap();
+        assert_eq!(roll.die, 50);
+        assert_eq!(roll.result, "wolves");
+        assert!((2..=5).contains(&roll.quantity.unwrap()));
+        assert_eq!(roll.nested.as_ref().unwrap().result, "clear");
+        assert!(roll.describe().ends_with("wolves (sky: clear)"));
+
+        let day = TableContext {
+            time_of_day: Some("morning".to_string()),
+            ..Default::default()
+        };
+        assert!(library.roll("camp", &day, Some(7)).is_err());
+
+        library
+            .add_json(r#"{"name": "loop", "entries": [{"result": "again", "table": "loop"}]}"#)
+            .unwrap();
+        assert!(library.roll("loop", &day, None).is_err());
+    }
+}
@@ -31,6 +31,15 @@ pub enum WeaponProperty {
     Special,
 }
 
+/// How far a ranged attack reaches relative to the weapon's range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RangeBand {
+    Normal,
+    /// Beyond normal range: the attack roll has disadvantage
+    Long,
+    OutOfRange,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Weapon {
     pub name: String,
@@ -62,6 +71,36 @@ impl Weapon {
     pub fn uses_dexterity(&self) -> bool {
         self.properties.contains(&WeaponProperty::Finesse) || self.weapon_type == WeaponType::Ranged
     }
+
+    /// Range band for a target `distance_ft` away, or `None` if the weapon has no range
+    pub fn range_band(&self, distance_ft: u32) -> Option<RangeBand> {
+        let normal = self.range_normal?;
+        let long = self.range_long.unwrap_or(normal);
+        Some(if distance_ft <= normal {
+            RangeBand::Normal
+        } else if distance_ft <= long {
+            RangeBand::Long
+        } else {
+            RangeBand::OutOfRange
+        })
+    }
+
+    /// Ammunition the weapon fires (one piece per attack)
+    pub fn ammunition(&self) -> Option<&'static str> {
+        if !self.properties.contains(&WeaponProperty::Ammunition) {
+            return None;
+        }
+        let name = self.name.to_lowercase();
+        Some(if name.contains("crossbow") {
+            "Crossbow Bolt"
+        } else if name.contains("sling") {
+            "Sling Bullet"
+        } else if name.contains("blowgun") {
+            "Blowgun Needle"
+        } else {
+            "Arrow"
+        })
+    }
 }
 
 pub struct WeaponDatabase;
@@ -845,4 +884,22 @@ mod tests {
         assert!(longbow.range_normal.is_some());
         assert_eq!(longbow.range_normal.unwrap(), 150);
     }
+
+    #[test]
+    fn test_range_bands_and_ammunition() {
+        let longbow = WeaponDatabase::get_weapon("Longbow").unwrap();
+        assert_eq!(longbow.range_band(150), Some(RangeBand::Normal));
+        assert_eq!(longbow.range_band(155), Some(RangeBand::Long));
+        assert_eq!(longbow.range_band(605), Some(RangeBand::OutOfRange));
+        assert_eq!(longbow.ammunition(), Some("Arrow"));
+
+        let crossbow = WeaponDatabase::get_weapon("Hand Crossbow").unwrap();
+        assert_eq!(crossbow.ammunition(), Some("Crossbow Bolt"));
+
+        let dagger = WeaponDatabase::get_weapon("Dagger").unwrap();
+        assert_eq!(dagger.range_band(30), Some(RangeBand::Long));
+        assert_eq!(dagger.ammunition(), None);
+        let longsword = WeaponDatabase::get_weapon("Longsword").unwrap();
+        assert_eq!(longsword.range_band(5), None);
+    }
 }